@@ -0,0 +1,366 @@
+//! 基于 AnyPool 的通用元数据，调用方无需区分数据库驱动
+//!
+//! Mysql 和 Postgres 通过 ANSI information_schema 查询表和列，Sqlite 没有 information_schema，
+//! 使用 sqlite_master 和 pragma_table_info。注释、索引、外键及建表语句等各数据库特有的信息不支持
+
+use sqlx::{AnyPool, Row, any::AnyRow};
+
+use super::{ColumnType, DatabaseMetadata, Driver, Error, Result, TableKind};
+
+/// Sqlite 的表和视图，排除 sqlite_ 开头的内部表
+const SQLITE_TABLES: &str = "SELECT name, type FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' ORDER BY name";
+
+pub struct AnyMetadata {
+    pool: AnyPool,
+    /// 用于选择占位符和类型转换的写法
+    driver: Driver,
+}
+
+impl AnyMetadata {
+    pub fn new(pool: AnyPool, driver: Driver) -> Self {
+        Self { pool, driver }
+    }
+
+    /// 连接数据库，连接前安装 Any 的默认驱动
+    pub async fn connect(url: &str) -> Result<Self> {
+        super::init();
+        let driver = Driver::try_from(url)?;
+        Ok(Self::new(AnyPool::connect(url).await?, driver))
+    }
+
+    /// 转换为字符串，information_schema 中的 sql_identifier 等类型 Any 无法解码
+    fn text(&self, expr: &str) -> String {
+        match self.driver {
+            Driver::Mysql => format!("CAST({expr} AS CHAR)"),
+            Driver::Postgres | Driver::Sqlite => format!("CAST({expr} AS VARCHAR)"),
+        }
+    }
+
+    /// 转换为 64 位整数，information_schema 中的 cardinal_number、无符号整数等类型 Any 无法解码
+    fn int(&self, expr: &str) -> String {
+        match self.driver {
+            Driver::Mysql => format!("CAST({expr} AS SIGNED)"),
+            Driver::Postgres | Driver::Sqlite => format!("CAST({expr} AS BIGINT)"),
+        }
+    }
+
+    /// 模式为空时使用的当前模式
+    fn current_schema_sql(&self) -> &'static str {
+        match self.driver {
+            Driver::Mysql => "DATABASE()",
+            Driver::Postgres => "current_schema()",
+            Driver::Sqlite => "'main'",
+        }
+    }
+
+    /// 列的类型对应的 Rust 类型
+    fn rust_type(&self, ty: &str, length: Option<i32>) -> String {
+        match self.driver {
+            Driver::Mysql if ty.eq_ignore_ascii_case("bit") => {
+                super::mysql::bit_type(length).into()
+            }
+            Driver::Mysql => super::mysql::t2t(ty).into(),
+            Driver::Postgres => super::postgres::rust_type(ty),
+            Driver::Sqlite => super::sqlite::t2t(ty).into(),
+        }
+    }
+
+    async fn sqlite_columns(&self, table_name: &str) -> Result<Vec<super::Column>> {
+        let rows = sqlx::query(super::sqlite::SELECT_COLUMNS)
+            .bind(table_name)
+            .bind("main")
+            .map(|row: AnyRow| super::sqlite::TableColumn {
+                cid: row.get::<Option<i64>, _>(0).map(|cid| cid as i32),
+                name: row.get(1),
+                r#type: row.get(2),
+                notnull: row.get::<Option<i64>, _>(3).map(|n| n as u8),
+                dflt_value: row.get(4),
+                pk: row.get::<Option<i64>, _>(5).map(|pk| pk as u8),
+                hidden: row.get::<Option<i64>, _>(6).map(|h| h as u8),
+            })
+            .fetch_all(&self.pool)
+            .await?;
+        // 不解析建表语句，无法判断是否声明了 AUTOINCREMENT
+        Ok(rows
+            .into_iter()
+            .map(|row| row.into_column("main", table_name, false))
+            .collect())
+    }
+}
+
+impl DatabaseMetadata for AnyMetadata {
+    fn ping(&self) -> super::BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            sqlx::query("SELECT 1").execute(&self.pool).await?;
+            Ok(())
+        })
+    }
+
+    fn databases(&self) -> super::BoxFuture<'_, Result<Vec<super::Database>>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
+
+    fn schemas(&self) -> super::BoxFuture<'_, Result<Vec<super::Schema>>> {
+        Box::pin(async move {
+            if self.driver == Driver::Sqlite {
+                return Ok(vec![super::Schema {
+                    name: "main".to_string(),
+                }]);
+            }
+            let sql = format!(
+                "SELECT {} FROM information_schema.schemata ORDER BY 1",
+                self.text("schema_name")
+            );
+            let rows = sqlx::query(&sql)
+                .map(|row: AnyRow| super::Schema { name: row.get(0) })
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows)
+        })
+    }
+
+    fn current_schema(&self) -> super::BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            let sql = format!("SELECT {}", self.text(self.current_schema_sql()));
+            let (schema,): (Option<String>,) = sqlx::query_as(&sql).fetch_one(&self.pool).await?;
+            Ok(schema.unwrap_or_default())
+        })
+    }
+
+    fn tables<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::Table>>> {
+        Box::pin(async move {
+            if self.driver == Driver::Sqlite {
+                let rows = sqlx::query(SQLITE_TABLES)
+                    .map(|row: AnyRow| {
+                        let r#type: String = row.get(1);
+                        super::Table {
+                            schema: "main".to_string(),
+                            name: row.get(0),
+                            comment: String::new(),
+                            collation: None,
+                            kind: if r#type == "view" {
+                                TableKind::View
+                            } else {
+                                TableKind::BaseTable
+                            },
+                        }
+                    })
+                    .fetch_all(&self.pool)
+                    .await?;
+                return Ok(rows);
+            }
+            let sql = format!(
+                "SELECT {}, {}, {} FROM information_schema.tables WHERE table_schema = COALESCE(NULLIF({}, ''), {}) ORDER BY table_name",
+                self.text("table_schema"),
+                self.text("table_name"),
+                self.text("table_type"),
+                self.driver.placeholder(1),
+                self.current_schema_sql(),
+            );
+            let rows = sqlx::query(&sql)
+                .bind(schema)
+                .map(|row: AnyRow| {
+                    let table_type: String = row.get(2);
+                    super::Table {
+                        schema: row.get(0),
+                        name: row.get(1),
+                        comment: String::new(),
+                        collation: None,
+                        // TABLE_TYPE 为 BASE TABLE、VIEW、SYSTEM VIEW 或 FOREIGN（Postgres）
+                        kind: if table_type.ends_with("VIEW") {
+                            TableKind::View
+                        } else if table_type == "FOREIGN" {
+                            TableKind::ForeignTable
+                        } else {
+                            TableKind::BaseTable
+                        },
+                    }
+                })
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows)
+        })
+    }
+
+    fn columns<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::Column>>> {
+        Box::pin(async move {
+            if self.driver == Driver::Sqlite {
+                return self.sqlite_columns(table_name).await;
+            }
+            // Postgres 的 data_type 不区分数组元素和自定义类型，使用 udt_name
+            let data_type = match self.driver {
+                Driver::Postgres => "col.udt_name",
+                Driver::Mysql | Driver::Sqlite => "col.data_type",
+            };
+            let sql = format!(
+                "SELECT {}, {}, {}, {}, {}, {}, {}, {}, {}, {},
+                CASE WHEN EXISTS (
+                    SELECT 1
+                    FROM information_schema.table_constraints tc
+                    JOIN information_schema.key_column_usage kcu ON kcu.constraint_schema = tc.constraint_schema
+                    AND kcu.constraint_name = tc.constraint_name
+                    AND kcu.table_name = tc.table_name
+                    WHERE tc.constraint_type = 'PRIMARY KEY'
+                    AND tc.table_schema = col.table_schema
+                    AND tc.table_name = col.table_name
+                    AND kcu.column_name = col.column_name
+                ) THEN 1 ELSE 0 END, {}, {}
+                FROM information_schema.columns col
+                WHERE col.table_schema = COALESCE(NULLIF({}, ''), {}) AND col.table_name = {}
+                ORDER BY col.ordinal_position",
+                self.text("col.table_catalog"),
+                self.text("col.table_schema"),
+                self.text("col.column_name"),
+                self.int("col.ordinal_position"),
+                self.text("col.column_default"),
+                self.text("col.is_nullable"),
+                self.text(data_type),
+                self.int("col.character_maximum_length"),
+                self.int("col.numeric_precision"),
+                self.int("col.numeric_scale"),
+                self.text("col.character_set_name"),
+                self.text("col.collation_name"),
+                self.driver.placeholder(1),
+                self.current_schema_sql(),
+                self.driver.placeholder(2),
+            );
+            let rows = sqlx::query(&sql)
+                .bind(schema)
+                .bind(table_name)
+                .map(|row: AnyRow| {
+                    let ty: String = row.get(6);
+                    let character_maximum_length: Option<i64> = row.get(7);
+                    let numeric_precision: Option<i64> = row.get(8);
+                    let numeric_scale: Option<i64> = row.get(9);
+                    let is_primary_key: i64 = row.get(10);
+                    // 整数、浮点数的 numeric_precision 为二进制位数，只保留定点数的精度
+                    let is_decimal = ["numeric", "decimal"]
+                        .iter()
+                        .any(|t| ty.eq_ignore_ascii_case(t));
+                    let (numeric_precision, numeric_scale) = if is_decimal {
+                        (numeric_precision, numeric_scale)
+                    } else {
+                        (None, None)
+                    };
+                    let length = character_maximum_length
+                        .or(numeric_precision)
+                        .map(|l| l as i32);
+                    let is_nullable: String = row.get(5);
+                    let default: Option<String> = row.get(4);
+                    super::Column {
+                        database: row.get(0),
+                        schema: row.get(1),
+                        table_name: table_name.to_string(),
+                        name: row.get(2),
+                        ordinal_position: row.get::<i64, _>(3) as i32,
+                        default_value: super::DefaultValue::classify(
+                            default.as_deref(),
+                            self.driver,
+                        ),
+                        default,
+                        is_null: is_nullable.eq_ignore_ascii_case("yes"),
+                        rust_type: self.rust_type(&ty, length),
+                        r#type: Some(ColumnType::from(ty)),
+                        length,
+                        scale: numeric_scale.map(|s| s as i32),
+                        is_primary_key: is_primary_key == 1,
+                        // Postgres 的 character_set_name 始终为空
+                        charset: row.get(11),
+                        collation: row.get(12),
+                        ..Default::default()
+                    }
+                })
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows)
+        })
+    }
+
+    fn indexs<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        _table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::Index>>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
+
+    fn create_table_sql<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        _table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<String>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sqlite_columns() {
+        // Any 连接池中的每个连接各自打开内存数据库，使用临时文件共享同一个数据库
+        let path = std::env::temp_dir().join(format!("database_any_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        let meta = AnyMetadata::connect(&url).await.unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR(20) NOT NULL, score REAL);
+            CREATE VIEW names AS SELECT name FROM users;",
+        )
+        .execute(&meta.pool)
+        .await
+        .unwrap();
+        let tables = meta
+            .tables("", "")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.name, t.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tables,
+            [
+                ("names".to_string(), TableKind::View),
+                ("users".to_string(), TableKind::BaseTable),
+            ]
+        );
+        let columns = meta.columns("", "", "users").await.unwrap();
+        let columns = columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.rust_type.as_str(),
+                    c.is_null,
+                    c.is_primary_key,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            [
+                ("id", "i64", false, true),
+                ("name", "String", false, false),
+                ("score", "f64", true, false),
+            ]
+        );
+        assert!(matches!(
+            meta.create_table_sql("", "", "users").await,
+            Err(Error::E(_))
+        ));
+        meta.pool.close().await;
+        let _ = std::fs::remove_file(path);
+    }
+}
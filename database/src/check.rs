@@ -0,0 +1,236 @@
+//! 检查约束中的数值范围
+//!
+//! 只识别由 AND 连接的列与数值常量的比较，如 `age >= 0 AND age <= 150`、`age BETWEEN 0 AND 150`、
+//! `price > 0`，包含 OR、函数调用或其他列的约束会被忽略
+
+use serde::Serialize;
+use sqlparser::{
+    ast::{BinaryOperator, Expr, UnaryOperator, Value},
+    parser::Parser,
+};
+
+use super::{CheckConstraint, Driver, dump};
+
+/// 列的数值范围，取值保留约束中的写法，如 `0`、`99.5`、`-1`
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumericRange {
+    /// 列名
+    pub column: String,
+    /// 最小值（包含），`>=`
+    pub min: Option<String>,
+    /// 最大值（包含），`<=`
+    pub max: Option<String>,
+    /// 最小值（不包含），`>`
+    pub exclusive_min: Option<String>,
+    /// 最大值（不包含），`<`
+    pub exclusive_max: Option<String>,
+}
+
+/// 从检查约束中提取各列的数值范围，同一列的多个约束合并为一个范围，按列首次出现的顺序排列
+///
+/// `driver` 决定解析表达式使用的 SQL 方言，无法解析的表达式会被忽略
+pub fn numeric_ranges(checks: &[CheckConstraint], driver: Driver) -> Vec<NumericRange> {
+    let dialect = dump::dialect(driver);
+    let mut ranges = vec![];
+    for check in checks {
+        let Ok(expr) = Parser::new(dialect.as_ref())
+            .try_with_sql(&check.expression)
+            .and_then(|mut parser| parser.parse_expr())
+        else {
+            continue;
+        };
+        collect(&expr, &mut ranges);
+    }
+    ranges
+}
+
+fn collect(expr: &Expr, ranges: &mut Vec<NumericRange>) {
+    match expr {
+        Expr::Nested(expr) => collect(expr, ranges),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            collect(left, ranges);
+            collect(right, ranges);
+        }
+        Expr::BinaryOp { left, op, right } => {
+            if let (Some(column), Some(value)) = (column(left), number(right)) {
+                bound(ranges, column, op, value);
+            } else if let (Some(value), Some(column)) = (number(left), column(right)) {
+                // 常量在左侧时比较方向相反，如 `0 <= age` 即 `age >= 0`
+                let op = match op {
+                    BinaryOperator::Gt => BinaryOperator::Lt,
+                    BinaryOperator::GtEq => BinaryOperator::LtEq,
+                    BinaryOperator::Lt => BinaryOperator::Gt,
+                    BinaryOperator::LtEq => BinaryOperator::GtEq,
+                    _ => return,
+                };
+                bound(ranges, column, &op, value);
+            }
+        }
+        Expr::Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        } => {
+            if let (Some(column), Some(low), Some(high)) = (column(expr), number(low), number(high))
+            {
+                bound(ranges, column, &BinaryOperator::GtEq, low);
+                bound(ranges, column, &BinaryOperator::LtEq, high);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 记录列的一个边界，同一边界出现多次时保留更严格的值
+fn bound(ranges: &mut Vec<NumericRange>, column: &str, op: &BinaryOperator, value: String) {
+    let index = match ranges.iter().position(|r| r.column == column) {
+        Some(index) => index,
+        None => {
+            ranges.push(NumericRange {
+                column: column.to_string(),
+                ..Default::default()
+            });
+            ranges.len() - 1
+        }
+    };
+    let range = &mut ranges[index];
+    let (slot, lower) = match op {
+        BinaryOperator::GtEq => (&mut range.min, true),
+        BinaryOperator::Gt => (&mut range.exclusive_min, true),
+        BinaryOperator::LtEq => (&mut range.max, false),
+        BinaryOperator::Lt => (&mut range.exclusive_max, false),
+        _ => return,
+    };
+    let stricter = match (slot.as_deref().map(str::parse::<f64>), value.parse::<f64>()) {
+        (Some(Ok(old)), Ok(new)) => (lower && new > old) || (!lower && new < old),
+        _ => true,
+    };
+    if stricter {
+        *slot = Some(value);
+    }
+}
+
+/// 比较中的列名，Postgres 会为列加上类型转换，如 `(price)::numeric`
+fn column(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Identifier(ident) => Some(&ident.value),
+        Expr::CompoundIdentifier(idents) => idents.last().map(|i| i.value.as_str()),
+        Expr::Nested(expr) | Expr::Cast { expr, .. } => column(expr),
+        _ => None,
+    }
+}
+
+/// 数值常量，可以带负号和类型转换，如 `-1`、`(0)::numeric`
+fn number(expr: &Expr) -> Option<String> {
+    match expr {
+        // Postgres 将负数常量保存为带类型转换的字符串，如 `'-5'::integer`
+        Expr::Cast { expr, .. }
+            if let Expr::Value(value) = expr.as_ref()
+                && let Value::SingleQuotedString(s) = &value.value
+                && s.parse::<f64>().is_ok() =>
+        {
+            Some(s.clone())
+        }
+        Expr::Value(value) => match &value.value {
+            Value::Number(n, _) => Some(n.to_string()),
+            _ => None,
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => number(expr).map(|n| format!("-{n}")),
+        Expr::Nested(expr) | Expr::Cast { expr, .. } => number(expr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(expressions: &[&str], driver: Driver) -> Vec<NumericRange> {
+        let checks = expressions
+            .iter()
+            .map(|e| CheckConstraint {
+                expression: e.to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        numeric_ranges(&checks, driver)
+    }
+
+    #[test]
+    fn between_and_comparisons() {
+        let ranges = ranges(
+            &["age BETWEEN 0 AND 150", "0 < price", "price <= 99.5"],
+            Driver::Mysql,
+        );
+        assert_eq!(
+            ranges,
+            vec![
+                NumericRange {
+                    column: "age".to_string(),
+                    min: Some("0".to_string()),
+                    max: Some("150".to_string()),
+                    ..Default::default()
+                },
+                NumericRange {
+                    column: "price".to_string(),
+                    max: Some("99.5".to_string()),
+                    exclusive_min: Some("0".to_string()),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn postgres_casts() {
+        // pg_get_expr 的输出形式
+        let ranges = ranges(
+            &[
+                "((score >= (0)::double precision) AND (score <= (100)::double precision))",
+                "(neg > '-5'::integer)",
+            ],
+            Driver::Postgres,
+        );
+        assert_eq!(ranges[0].min.as_deref(), Some("0"));
+        assert_eq!(ranges[0].max.as_deref(), Some("100"));
+        assert_eq!(ranges[1].column, "neg");
+        assert_eq!(ranges[1].exclusive_min.as_deref(), Some("-5"));
+    }
+
+    #[test]
+    fn keeps_stricter_bound() {
+        let ranges = ranges(
+            &["age >= 0", "age >= 18 AND age <= 200", "age <= 150"],
+            Driver::Sqlite,
+        );
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].min.as_deref(), Some("18"));
+        assert_eq!(ranges[0].max.as_deref(), Some("150"));
+    }
+
+    #[test]
+    fn ignores_unsupported_expressions() {
+        let ranges = ranges(
+            &[
+                "age >= 0 OR age IS NULL",
+                "NOT (age < 0)",
+                "length(name) <= 10",
+                "start_at < end_at",
+                "age NOT BETWEEN 0 AND 10",
+                "status IN ('a', 'b')",
+                "(",
+            ],
+            Driver::Postgres,
+        );
+        assert!(ranges.is_empty());
+    }
+}
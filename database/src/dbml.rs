@@ -0,0 +1,205 @@
+//! DBML 导出
+//!
+//! 将结构快照渲染为 dbdiagram.io 等工具使用的 DBML，包括表、列及外键关系
+
+use super::{Column, ForeignKey, SchemaSnapshot};
+
+/// DBML 关键字，作为表名或列名时需要加引号
+const DBML_KEYWORDS: [&str; 15] = [
+    "project",
+    "table",
+    "tablegroup",
+    "ref",
+    "enum",
+    "note",
+    "indexes",
+    "as",
+    "pk",
+    "null",
+    "not",
+    "increment",
+    "unique",
+    "default",
+    "headercolor",
+];
+
+/// 将结构快照渲染为 DBML
+pub fn to_dbml(snapshot: &SchemaSnapshot) -> String {
+    let mut dbml = String::new();
+    for table in snapshot.tables.iter() {
+        dbml.push_str(&format!("Table {} {{\n", quote(&table.table.name)));
+        // 组合主键不能在多个列上标记 pk，需要在 indexes 中声明
+        let pk = table
+            .columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.clone())
+            .collect::<Vec<_>>();
+        for column in table.columns.iter() {
+            dbml.push_str(&format!(
+                "  {} {}{}\n",
+                quote(&column.name),
+                column_type(column),
+                settings(column, pk.len() == 1)
+            ));
+        }
+        if pk.len() > 1 {
+            dbml.push_str(&format!(
+                "\n  indexes {{\n    {} [pk]\n  }}\n",
+                columns(&pk)
+            ));
+        }
+        if !table.table.comment.is_empty() {
+            dbml.push_str(&format!("\n  Note: {}\n", string(&table.table.comment)));
+        }
+        dbml.push_str("}\n\n");
+    }
+    for fk in snapshot.tables.iter().flat_map(|t| t.foreign_keys.iter()) {
+        dbml.push_str(&reference(fk));
+    }
+    dbml.trim_end().to_string() + "\n"
+}
+
+/// 列类型，如 `VARCHAR(255)`，包含空格等字符的类型加引号
+fn column_type(column: &Column) -> String {
+    let ty = column.full_type();
+    let name = ty.split_once('(').map_or(ty.as_str(), |(name, _)| name);
+    if is_identifier(name) { ty } else { quoted(&ty) }
+}
+
+/// 列设置，如 ` [pk, increment, not null]`，没有设置时为空；组合主键的列不标记 pk
+fn settings(column: &Column, single_pk: bool) -> String {
+    let mut settings = vec![];
+    if column.is_primary_key && single_pk {
+        settings.push("pk".to_string());
+    }
+    if column.is_auto_incr {
+        settings.push("increment".to_string());
+    }
+    if !column.is_null {
+        settings.push("not null".to_string());
+    }
+    if column.is_unique && !column.is_primary_key {
+        settings.push("unique".to_string());
+    }
+    if !column.comment.is_empty() {
+        settings.push(format!("note: {}", string(&column.comment)));
+    }
+    if settings.is_empty() {
+        return String::new();
+    }
+    format!(" [{}]", settings.join(", "))
+}
+
+/// 外键关系，如 `Ref fk_order_user: orders.user_id > user.id [delete: cascade]`，组合外键的列用括号包裹
+fn reference(fk: &ForeignKey) -> String {
+    // NO ACTION 为默认动作，无需声明
+    let settings = [("delete", &fk.on_delete), ("update", &fk.on_update)]
+        .into_iter()
+        .filter_map(|(event, action)| {
+            let action = action.as_deref()?.to_lowercase();
+            (action != "no action").then(|| format!("{event}: {action}"))
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "Ref {}: {}.{} > {}.{}{}\n",
+        quote(&fk.name),
+        quote(&fk.table_name),
+        columns(&fk.columns),
+        quote(&fk.referenced_table),
+        columns(&fk.referenced_columns),
+        if settings.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", settings.join(", "))
+        }
+    )
+}
+
+fn columns(columns: &[String]) -> String {
+    match columns {
+        [column] => quote(column),
+        columns => format!(
+            "({})",
+            columns
+                .iter()
+                .map(|c| quote(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// 表名、列名不是合法标识符或为关键字时加双引号
+fn quote(name: &str) -> String {
+    if is_identifier(name) && !DBML_KEYWORDS.contains(&name.to_lowercase().as_str()) {
+        name.to_string()
+    } else {
+        quoted(name)
+    }
+}
+
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 单引号字符串，用于备注
+fn string(value: &str) -> String {
+    format!(
+        "'{}'",
+        value
+            .replace('\\', "\\\\")
+            .replace('\'', "\\'")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Driver, DumpMetadata, schema_snapshot};
+
+    #[tokio::test]
+    async fn two_tables_with_relation() {
+        let meta = DumpMetadata::new(
+            "CREATE TABLE users (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                name VARCHAR(50) NOT NULL COMMENT '用户名'
+            ) COMMENT='用户';
+            CREATE TABLE `order items` (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                user_id BIGINT NOT NULL,
+                note VARCHAR(200),
+                `Table` INT,
+                CONSTRAINT fk_user FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+            );",
+            Driver::Mysql,
+        )
+        .unwrap();
+        let snapshot = schema_snapshot(&meta, "").await.unwrap();
+        assert_eq!(
+            to_dbml(&snapshot),
+            r#"Table users {
+  id BIGINT [pk, increment, not null]
+  name VARCHAR(50) [not null, note: '用户名']
+
+  Note: '用户'
+}
+
+Table "order items" {
+  id BIGINT [pk, increment, not null]
+  user_id BIGINT [not null]
+  "note" VARCHAR(200)
+  "Table" INT
+}
+
+Ref fk_user: "order items".user_id > users.id [delete: cascade]
+"#
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! 结构差异
+//!
+//! 比较两个结构快照，列出新增、删除的表，以及表中新增、删除、变更的列和索引
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Column, Index, SchemaSnapshot, TableDetail};
+
+/// 两个结构快照之间的差异
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiff {
+    /// 新增的表
+    pub added_tables: Vec<String>,
+    /// 删除的表
+    pub removed_tables: Vec<String>,
+    /// 两边都存在但结构有变化的表
+    pub changed_tables: Vec<TableDiff>,
+}
+
+/// 表的差异
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableDiff {
+    /// 表名
+    pub name: String,
+    pub added_columns: Vec<Column>,
+    pub removed_columns: Vec<Column>,
+    pub changed_columns: Vec<ColumnDiff>,
+    /// 新增的索引名
+    pub added_indexes: Vec<String>,
+    /// 删除的索引名
+    pub removed_indexes: Vec<String>,
+    /// 列、唯一性或索引方法有变化的索引名
+    pub changed_indexes: Vec<String>,
+}
+
+/// 列的变化
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnDiff {
+    pub old: Column,
+    pub new: Column,
+}
+
+/// 比较两个结构快照
+///
+/// 列的类型、长度、小数位数或是否为空不同时视为变更
+pub fn diff_schemas(old: &SchemaSnapshot, new: &SchemaSnapshot) -> SchemaDiff {
+    let mut diff = SchemaDiff::default();
+    for table in new.tables.iter() {
+        match old.tables.iter().find(|t| t.table.name == table.table.name) {
+            Some(old_table) => {
+                let table_diff = diff_table(old_table, table);
+                if !table_diff.is_empty() {
+                    diff.changed_tables.push(table_diff);
+                }
+            }
+            None => diff.added_tables.push(table.table.name.clone()),
+        }
+    }
+    diff.removed_tables = old
+        .tables
+        .iter()
+        .filter(|t| !new.tables.iter().any(|n| n.table.name == t.table.name))
+        .map(|t| t.table.name.clone())
+        .collect();
+    diff
+}
+
+fn diff_table(old: &TableDetail, new: &TableDetail) -> TableDiff {
+    let mut diff = TableDiff {
+        name: new.table.name.clone(),
+        ..Default::default()
+    };
+    for column in new.columns.iter() {
+        match old.columns.iter().find(|c| c.name == column.name) {
+            Some(old_column) if is_changed(old_column, column) => {
+                diff.changed_columns.push(ColumnDiff {
+                    old: old_column.clone(),
+                    new: column.clone(),
+                })
+            }
+            Some(_) => {}
+            None => diff.added_columns.push(column.clone()),
+        }
+    }
+    diff.removed_columns = old
+        .columns
+        .iter()
+        .filter(|c| !new.columns.iter().any(|n| n.name == c.name))
+        .cloned()
+        .collect();
+
+    let old_indexes = index_signatures(&old.indexes);
+    let new_indexes = index_signatures(&new.indexes);
+    for (name, signature) in new_indexes.iter() {
+        match old_indexes.get(name) {
+            Some(old_signature) if old_signature != signature => {
+                diff.changed_indexes.push(name.to_string())
+            }
+            Some(_) => {}
+            None => diff.added_indexes.push(name.to_string()),
+        }
+    }
+    diff.removed_indexes = old_indexes
+        .keys()
+        .filter(|name| !new_indexes.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect();
+    diff
+}
+
+fn is_changed(old: &Column, new: &Column) -> bool {
+    old.r#type != new.r#type
+        || old.length != new.length
+        || old.scale != new.scale
+        || old.is_null != new.is_null
+}
+
+/// 用于比较的索引特征：是否不唯一，索引方法，按顺序排列的列
+type IndexSignature<'a> = (i32, &'a str, Vec<&'a str>);
+
+/// 索引特征，K：索引名
+fn index_signatures(indexes: &[Index]) -> BTreeMap<&str, IndexSignature<'_>> {
+    // 多列索引每列一行，按索引名分组
+    let mut groups: BTreeMap<&str, Vec<&Index>> = BTreeMap::new();
+    for index in indexes {
+        groups.entry(&index.key_name).or_default().push(index);
+    }
+    groups
+        .into_iter()
+        .map(|(name, mut columns)| {
+            columns.sort_by_key(|c| c.seq_in_index);
+            let signature = (
+                columns[0].non_unique,
+                columns[0].index_type.as_str(),
+                columns.iter().map(|c| c.column_name.as_str()).collect(),
+            );
+            (name, signature)
+        })
+        .collect()
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.changed_columns.is_empty()
+            && self.added_indexes.is_empty()
+            && self.removed_indexes.is_empty()
+            && self.changed_indexes.is_empty()
+    }
+}
+
+impl SchemaDiff {
+    /// 两个快照的结构是否一致
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty()
+            && self.removed_tables.is_empty()
+            && self.changed_tables.is_empty()
+    }
+}
+
+impl Display for SchemaDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for table in self.added_tables.iter() {
+            writeln!(f, "新增表 {table}")?;
+        }
+        for table in self.removed_tables.iter() {
+            writeln!(f, "删除表 {table}")?;
+        }
+        for table in self.changed_tables.iter() {
+            let name = &table.name;
+            for column in table.added_columns.iter() {
+                writeln!(f, "表 {name} 新增列 {} {}", column.name, describe(column))?;
+            }
+            for column in table.removed_columns.iter() {
+                writeln!(f, "表 {name} 删除列 {}", column.name)?;
+            }
+            for ColumnDiff { old, new } in table.changed_columns.iter() {
+                writeln!(
+                    f,
+                    "表 {name} 变更列 {}：{} -> {}",
+                    new.name,
+                    describe(old),
+                    describe(new)
+                )?;
+            }
+            for index in table.added_indexes.iter() {
+                writeln!(f, "表 {name} 新增索引 {index}")?;
+            }
+            for index in table.removed_indexes.iter() {
+                writeln!(f, "表 {name} 删除索引 {index}")?;
+            }
+            for index in table.changed_indexes.iter() {
+                writeln!(f, "表 {name} 变更索引 {index}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 列的类型、是否为空及默认值，如 `VARCHAR(20) NOT NULL DEFAULT ''`
+fn describe(column: &Column) -> String {
+    let mut description = column.full_type();
+    if !column.is_null {
+        description.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.default {
+        description.push_str(&format!(" DEFAULT {default}"));
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Driver, DumpMetadata, schema_snapshot};
+
+    async fn snapshot(sql: &str) -> SchemaSnapshot {
+        let meta = DumpMetadata::new(sql, Driver::Mysql).unwrap();
+        schema_snapshot(&meta, "").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn dropped_widened_and_new() {
+        let old = snapshot(
+            "CREATE TABLE users (
+                id BIGINT PRIMARY KEY,
+                name VARCHAR(20) NOT NULL,
+                age INT
+            );",
+        )
+        .await;
+        let new = snapshot(
+            "CREATE TABLE users (
+                id BIGINT PRIMARY KEY,
+                name VARCHAR(50) NOT NULL
+            );
+            CREATE TABLE orders (id BIGINT PRIMARY KEY);",
+        )
+        .await;
+        let diff = diff_schemas(&old, &new);
+        assert_eq!(diff.added_tables, ["orders"]);
+        assert!(diff.removed_tables.is_empty());
+        assert_eq!(diff.changed_tables.len(), 1);
+        let users = &diff.changed_tables[0];
+        assert_eq!(users.name, "users");
+        assert!(users.added_columns.is_empty());
+        assert_eq!(users.removed_columns.len(), 1);
+        assert_eq!(users.removed_columns[0].name, "age");
+        assert_eq!(users.changed_columns.len(), 1);
+        assert_eq!(users.changed_columns[0].old.length, Some(20));
+        assert_eq!(users.changed_columns[0].new.length, Some(50));
+        assert_eq!(
+            diff.to_string(),
+            "新增表 orders\n表 users 删除列 age\n表 users 变更列 name：VARCHAR(20) NOT NULL -> VARCHAR(50) NOT NULL\n"
+        );
+        assert!(diff_schemas(&new, &new).is_empty());
+    }
+}
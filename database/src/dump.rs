@@ -0,0 +1,684 @@
+//! SQL 转储文件元数据
+//!
+//! 解析 `.sql` 结构转储中的建表语句，无需连接数据库即可获取表结构。
+//! 支持 `CREATE TABLE`、`CREATE INDEX`、`ALTER TABLE ... ADD CONSTRAINT` 以及
+//! `COMMENT ON TABLE/COLUMN`，其余语句（`SET`、`INSERT` 等）会被忽略
+
+use std::{fs, path::Path};
+
+use sqlparser::{
+    ast::{
+        AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, CommentDef,
+        CommentObject, CreateIndex, CreateTable, CreateTableOptions, Expr, ForeignKeyConstraint,
+        Ident, IndexColumn, IndexType, ObjectName, SqlOption, Statement, TableConstraint,
+        UnaryOperator, Value,
+    },
+    dialect::{Dialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect},
+    parser::Parser,
+    tokenizer::{Token, Tokenizer},
+};
+
+use super::{
+    CheckConstraint, Column, ColumnType, Database, DatabaseMetadata, DefaultValue, Driver, Error,
+    ForeignKey, Index, Result, Schema, Table, TableKind, mysql, postgres, sqlite,
+};
+
+/// 主键索引名称
+const PRIMARY_KEY_NAME: &str = "PRIMARY";
+/// 未指定索引方法时的默认值
+const DEFAULT_INDEX_TYPE: &str = "BTREE";
+
+/// SQL 转储文件元数据
+pub struct DumpMetadata {
+    driver: Driver,
+    tables: Vec<DumpTable>,
+}
+
+/// 转储文件中的表
+struct DumpTable {
+    table: Table,
+    columns: Vec<Column>,
+    indexs: Vec<Index>,
+    foreign_keys: Vec<ForeignKey>,
+    checks: Vec<CheckConstraint>,
+    /// 建表语句
+    sql: String,
+}
+
+impl DumpMetadata {
+    /// 解析 SQL 转储内容，`driver` 决定使用的 SQL 方言
+    pub fn new(sql: &str, driver: Driver) -> Result<Self> {
+        let dialect = dialect(driver);
+        let tokens = Tokenizer::new(dialect.as_ref(), sql)
+            .tokenize()
+            .map_err(|_| Error::E("SQL 转储文件解析失败"))?;
+
+        let mut metadata = Self {
+            driver,
+            tables: vec![],
+        };
+        for tokens in tokens.split(|t| *t == Token::SemiColon) {
+            // 转储中与表结构无关或方言不支持的语句直接跳过
+            let Ok(statement) = Parser::new(dialect.as_ref())
+                .with_tokens(tokens.to_vec())
+                .parse_statement()
+            else {
+                continue;
+            };
+            metadata.apply(statement);
+        }
+        Ok(metadata)
+    }
+
+    /// 读取并解析 SQL 转储文件
+    pub fn from_file(path: impl AsRef<Path>, driver: Driver) -> Result<Self> {
+        Self::new(&fs::read_to_string(path)?, driver)
+    }
+
+    fn apply(&mut self, statement: Statement) {
+        match statement {
+            Statement::CreateTable(create) => self.create_table(create),
+            Statement::CreateIndex(create) => self.create_index(create),
+            Statement::AlterTable(alter) => {
+                let Some(table) = self.table_mut(&alter.name) else {
+                    return;
+                };
+                for operation in alter.operations {
+                    match operation {
+                        AlterTableOperation::AddConstraint { constraint, .. } => {
+                            table.add_constraint(constraint)
+                        }
+                        // pg_dump 在建表后通过 ALTER COLUMN 设置序列默认值
+                        AlterTableOperation::AlterColumn {
+                            column_name,
+                            op: AlterColumnOperation::SetDefault { value },
+                        } => {
+                            if let Some(column) = table
+                                .columns
+                                .iter_mut()
+                                .find(|c| c.name == column_name.value)
+                            {
+                                set_default(column, &value);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Statement::Comment {
+                object_type,
+                object_name,
+                comment,
+                ..
+            } => {
+                let comment = comment.unwrap_or_default();
+                match object_type {
+                    CommentObject::Table => {
+                        if let Some(table) = self.table_mut(&object_name) {
+                            table.table.comment = comment;
+                        }
+                    }
+                    CommentObject::Column => {
+                        // 列名为 [模式.]表名.列名
+                        let mut parts = object_name.0.clone();
+                        let Some(column) = parts.pop() else {
+                            return;
+                        };
+                        let column = column.as_ident().map(|c| c.value.clone());
+                        if let Some(table) = self.table_mut(&ObjectName(parts))
+                            && let Some(c) = table
+                                .columns
+                                .iter_mut()
+                                .find(|c| Some(&c.name) == column.as_ref())
+                        {
+                            c.comment = comment;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn create_table(&mut self, create: CreateTable) {
+        let sql = create.to_string();
+        let (schema, name) = split_name(&create.name);
+        let comment = table_comment(&create).unwrap_or_default();
+
+        let columns = create
+            .columns
+            .iter()
+            .zip(1..)
+            .map(|(def, ordinal_position)| Column {
+                ordinal_position,
+                ..self.column(&schema, &name, def)
+            })
+            .collect();
+        let mut table = DumpTable {
+            table: Table {
+                schema,
+                name,
+                comment,
+                collation: table_collation(&create),
+                kind: TableKind::BaseTable,
+            },
+            columns,
+            indexs: vec![],
+            foreign_keys: vec![],
+            checks: vec![],
+            sql,
+        };
+        for def in &create.columns {
+            for option in &def.options {
+                match option.option {
+                    ColumnOption::PrimaryKey(_) => {
+                        table.add_index(PRIMARY_KEY_NAME, false, None, [def.name.value.clone()])
+                    }
+                    ColumnOption::Unique(_) => {
+                        table.add_index(&def.name.value, false, None, [def.name.value.clone()])
+                    }
+                    // 列级外键 REFERENCES t (c) 中的外键列即该列本身
+                    ColumnOption::ForeignKey(ref fk) => {
+                        table.add_foreign_key(fk, vec![def.name.value.clone()])
+                    }
+                    ColumnOption::Check(ref c) => table.add_check(c, option.name.as_ref()),
+                    _ => {}
+                }
+            }
+        }
+        for constraint in create.constraints {
+            table.add_constraint(constraint);
+        }
+
+        // 重复的建表语句以最后一条为准
+        self.tables.retain(|t| t.table.name != table.table.name);
+        self.tables.push(table);
+    }
+
+    fn create_index(&mut self, create: CreateIndex) {
+        let Some(table) = self.table_mut(&create.table_name) else {
+            return;
+        };
+        let key_name = create
+            .name
+            .as_ref()
+            .map(|n| split_name(n).1)
+            .unwrap_or_else(|| index_column_names(&create.columns).join("_"));
+        table.add_index(
+            &key_name,
+            !create.unique,
+            create.using.as_ref(),
+            index_column_names(&create.columns),
+        );
+    }
+
+    fn column(&self, schema: &str, table_name: &str, def: &ColumnDef) -> Column {
+        let data_type = def.data_type.to_string();
+        let (base, length, scale, enum_values) = split_type(&data_type);
+        let is_unsigned = base.ends_with(" UNSIGNED");
+        let r#type = ColumnType::from(base.trim_end_matches(" UNSIGNED").to_string());
+
+        let mut column = Column {
+            database: schema.to_string(),
+            schema: schema.to_string(),
+            table_name: table_name.to_string(),
+            name: def.name.value.clone(),
+            rust_type: self.rust_type(&r#type, length, is_unsigned),
+            r#type: Some(r#type),
+            length,
+            scale,
+            enum_values,
+            is_null: true,
+            is_auto_incr: matches!(base.as_str(), "SERIAL" | "SMALLSERIAL" | "BIGSERIAL"),
+            is_unsigned,
+            ..Default::default()
+        };
+        for option in &def.options {
+            match &option.option {
+                ColumnOption::Null => column.is_null = true,
+                ColumnOption::NotNull => column.is_null = false,
+                ColumnOption::Default(expr) => set_default(&mut column, expr),
+                ColumnOption::PrimaryKey(_) => {
+                    column.is_primary_key = true;
+                    column.is_null = false;
+                }
+                ColumnOption::Unique(_) => column.is_unique = true,
+                ColumnOption::Comment(comment) => column.comment = comment.clone(),
+                ColumnOption::CharacterSet(name) => column.charset = Some(name.to_string()),
+                ColumnOption::Collation(name) => column.collation = Some(name.to_string()),
+                ColumnOption::Identity(_) => column.is_auto_incr = true,
+                ColumnOption::Generated {
+                    generation_expr: None,
+                    ..
+                } => column.is_auto_incr = true,
+                ColumnOption::Generated {
+                    generation_expr: Some(_),
+                    ..
+                } => column.is_generated = true,
+                ColumnOption::DialectSpecific(tokens) => {
+                    column.is_auto_incr |= tokens.iter().any(|t| {
+                        let t = t.to_string();
+                        t.eq_ignore_ascii_case("AUTO_INCREMENT")
+                            || t.eq_ignore_ascii_case("AUTOINCREMENT")
+                    })
+                }
+                _ => {}
+            }
+        }
+        column
+    }
+
+    /// 使用对应数据库的类型映射获取 Rust 类型
+    fn rust_type(&self, ty: &ColumnType, length: Option<i32>, is_unsigned: bool) -> String {
+        match self.driver {
+            Driver::Mysql => mysql::rust_type(ty, length, is_unsigned),
+            Driver::Postgres => postgres::rust_type(&ty.to_string()),
+            Driver::Sqlite => sqlite::t2t(&ty.to_string()).to_string(),
+        }
+    }
+
+    fn table(&self, table_name: &str) -> Option<&DumpTable> {
+        self.tables.iter().find(|t| t.table.name == table_name)
+    }
+
+    fn table_mut(&mut self, name: &ObjectName) -> Option<&mut DumpTable> {
+        let (_, name) = split_name(name);
+        self.tables.iter_mut().find(|t| t.table.name == name)
+    }
+}
+
+impl DumpTable {
+    fn add_constraint(&mut self, constraint: TableConstraint) {
+        match constraint {
+            TableConstraint::PrimaryKey(c) => {
+                let columns = index_column_names(&c.columns);
+                let key_name = c
+                    .name
+                    .map(|n| n.value)
+                    .unwrap_or_else(|| PRIMARY_KEY_NAME.to_string());
+                for column in self.columns.iter_mut() {
+                    if columns.contains(&column.name) {
+                        column.is_primary_key = true;
+                        column.is_null = false;
+                    }
+                }
+                self.add_index(&key_name, false, c.index_type.as_ref(), columns);
+            }
+            TableConstraint::Unique(c) => {
+                let columns = index_column_names(&c.columns);
+                if let [name] = columns.as_slice()
+                    && let Some(column) = self.columns.iter_mut().find(|c| &c.name == name)
+                {
+                    column.is_unique = true;
+                }
+                let key_name = c
+                    .index_name
+                    .or(c.name)
+                    .map(|n| n.value)
+                    .unwrap_or_else(|| columns.join("_"));
+                self.add_index(&key_name, false, c.index_type.as_ref(), columns);
+            }
+            TableConstraint::Index(c) => {
+                let columns = index_column_names(&c.columns);
+                let key_name = c.name.map(|n| n.value).unwrap_or_else(|| columns.join("_"));
+                self.add_index(&key_name, true, c.index_type.as_ref(), columns);
+            }
+            TableConstraint::ForeignKey(c) => {
+                let columns = c.columns.iter().map(|c| c.value.clone()).collect();
+                self.add_foreign_key(&c, columns);
+            }
+            TableConstraint::Check(c) => self.add_check(&c, None),
+            _ => {}
+        }
+    }
+
+    fn add_foreign_key(&mut self, fk: &ForeignKeyConstraint, columns: Vec<String>) {
+        // 未命名的外键使用 Postgres 的默认命名 表名_列名_fkey
+        let name = fk
+            .name
+            .as_ref()
+            .map(|n| n.value.clone())
+            .unwrap_or_else(|| format!("{}_{}_fkey", self.table.name, columns.join("_")));
+        self.foreign_keys.push(ForeignKey {
+            table_name: self.table.name.clone(),
+            name,
+            columns,
+            referenced_table: split_name(&fk.foreign_table).1,
+            referenced_columns: fk
+                .referred_columns
+                .iter()
+                .map(|c| c.value.clone())
+                .collect(),
+            on_delete: fk.on_delete.map(|a| a.to_string()),
+            on_update: fk.on_update.map(|a| a.to_string()),
+        });
+    }
+
+    /// 列级约束的名称写在 `CONSTRAINT name` 中，作为 `name` 传入
+    fn add_check(&mut self, check: &sqlparser::ast::CheckConstraint, name: Option<&Ident>) {
+        self.checks.push(CheckConstraint {
+            table_name: self.table.name.clone(),
+            name: check
+                .name
+                .as_ref()
+                .or(name)
+                .map(|n| n.value.clone())
+                .unwrap_or_default(),
+            expression: check.expr.to_string(),
+        });
+    }
+
+    fn add_index(
+        &mut self,
+        key_name: &str,
+        non_unique: bool,
+        index_type: Option<&IndexType>,
+        columns: impl IntoIterator<Item = String>,
+    ) {
+        let index_type = index_type
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| DEFAULT_INDEX_TYPE.to_string());
+        for (i, column_name) in columns.into_iter().enumerate() {
+            self.indexs.push(Index {
+                table_name: self.table.name.clone(),
+                non_unique: non_unique as i32,
+                key_name: key_name.to_string(),
+                seq_in_index: i as u32 + 1,
+                column_name,
+                sub_part: None,
+                index_type: index_type.clone(),
+                index_comment: String::new(),
+            });
+        }
+    }
+}
+
+impl DatabaseMetadata for DumpMetadata {
+    fn ping(&self) -> super::BoxFuture<'_, Result<()>> {
+        // 转储文件已在创建时解析，没有连接
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn databases(&self) -> super::BoxFuture<'_, Result<Vec<Database>>> {
+        Box::pin(async move { Ok(vec![]) })
+    }
+
+    fn schemas(&self) -> super::BoxFuture<'_, Result<Vec<Schema>>> {
+        Box::pin(async move { Ok(vec![]) })
+    }
+
+    /// 转储文件不区分模式，返回空字符串
+    fn current_schema(&self) -> super::BoxFuture<'_, Result<String>> {
+        Box::pin(async move { Ok(String::new()) })
+    }
+
+    fn tables<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<Table>>> {
+        Box::pin(async move { Ok(self.tables.iter().map(|t| t.table.clone()).collect()) })
+    }
+
+    fn columns<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<Column>>> {
+        Box::pin(async move {
+            Ok(self
+                .table(table_name)
+                .map(|t| t.columns.clone())
+                .unwrap_or_default())
+        })
+    }
+
+    fn indexs<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<Index>>> {
+        Box::pin(async move {
+            Ok(self
+                .table(table_name)
+                .map(|t| t.indexs.clone())
+                .unwrap_or_default())
+        })
+    }
+
+    fn foreign_keys<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<ForeignKey>>> {
+        Box::pin(async move {
+            Ok(self
+                .table(table_name)
+                .map(|t| t.foreign_keys.clone())
+                .unwrap_or_default())
+        })
+    }
+
+    fn create_table_sql<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            self.table(table_name)
+                .map(|t| format!("{};", t.sql))
+                .ok_or(Error::E("table not found"))
+        })
+    }
+
+    fn check_constraints<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<CheckConstraint>>> {
+        Box::pin(async move {
+            let mut checks = self
+                .table(table_name)
+                .map(|t| t.checks.clone())
+                .unwrap_or_default();
+            checks.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(checks)
+        })
+    }
+}
+
+/// 设置默认值，Postgres 使用序列作为默认值的视为自增
+fn set_default(column: &mut Column, expr: &Expr) {
+    let default = expr.to_string();
+    column.is_auto_incr |= default.to_lowercase().starts_with("nextval(");
+    column.default_value = default_value(expr);
+    column.default = Some(default);
+}
+
+/// 默认值的分类，字面量可以带类型转换，如 `'active'::VARCHAR`、`-1`
+fn default_value(expr: &Expr) -> DefaultValue {
+    match expr {
+        Expr::Value(value) => match &value.value {
+            Value::Null => DefaultValue::None,
+            v => match v.clone().into_string() {
+                Some(s) => DefaultValue::Literal(s),
+                None => DefaultValue::Literal(v.to_string()),
+            },
+        },
+        Expr::Cast { expr, .. } | Expr::Nested(expr) => default_value(expr),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: inner,
+        } if matches!(inner.as_ref(), Expr::Value(_)) => DefaultValue::Literal(expr.to_string()),
+        _ => DefaultValue::Expression(expr.to_string()),
+    }
+}
+
+pub(crate) fn dialect(driver: Driver) -> Box<dyn Dialect> {
+    match driver {
+        Driver::Mysql => Box::new(MySqlDialect {}),
+        Driver::Postgres => Box::new(PostgreSqlDialect {}),
+        Driver::Sqlite => Box::new(SQLiteDialect {}),
+    }
+}
+
+/// 拆分 `[模式.]表名`，返回不带引号的模式和表名
+fn split_name(name: &ObjectName) -> (String, String) {
+    let mut parts = name
+        .0
+        .iter()
+        .rev()
+        .map(|p| p.as_ident().map(|i| i.value.clone()).unwrap_or_default());
+    let name = parts.next().unwrap_or_default();
+    (parts.next().unwrap_or_default(), name)
+}
+
+fn table_comment(create: &CreateTable) -> Option<String> {
+    let options = match &create.table_options {
+        CreateTableOptions::Plain(options) | CreateTableOptions::With(options) => {
+            options.as_slice()
+        }
+        _ => &[],
+    };
+    create
+        .comment
+        .iter()
+        .chain(options.iter().filter_map(|o| match o {
+            SqlOption::Comment(comment) => Some(comment),
+            _ => None,
+        }))
+        .map(|comment| match comment {
+            CommentDef::WithEq(c) | CommentDef::WithoutEq(c) => c.clone(),
+        })
+        .next()
+}
+
+/// Mysql 建表语句中的 `COLLATE=` 或 `DEFAULT COLLATE=` 表选项
+fn table_collation(create: &CreateTable) -> Option<String> {
+    let CreateTableOptions::Plain(options) = &create.table_options else {
+        return None;
+    };
+    options.iter().find_map(|o| match o {
+        SqlOption::KeyValue { key, value }
+            if key.value.eq_ignore_ascii_case("COLLATE")
+                || key.value.eq_ignore_ascii_case("DEFAULT COLLATE") =>
+        {
+            Some(value.to_string())
+        }
+        _ => None,
+    })
+}
+
+fn index_column_names(columns: &[IndexColumn]) -> Vec<String> {
+    columns
+        .iter()
+        .map(|c| match &c.column.expr {
+            Expr::Identifier(ident) => ident.value.clone(),
+            // Mysql 前缀索引 `name(10)` 等表达式保留原样
+            expr => expr.to_string(),
+        })
+        .collect()
+}
+
+/// 拆分字段类型，如 `INT(11) UNSIGNED`、`DECIMAL(10,2)`、`ENUM('a', 'b')`，
+/// 返回去掉括号部分的类型名、长度、精度以及枚举值
+fn split_type(data_type: &str) -> (String, Option<i32>, Option<i32>, Option<Vec<String>>) {
+    let (Some(start), Some(end)) = (data_type.find('('), data_type.rfind(')')) else {
+        return (data_type.to_uppercase(), None, None, None);
+    };
+    let base = format!("{} {}", &data_type[..start], &data_type[end + 1..]).to_uppercase();
+    let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+    let args = &data_type[start + 1..end];
+
+    if base == "ENUM" || base == "SET" {
+        return (base, None, None, Some(mysql::parse_enum_values(args)));
+    }
+    let mut args = args.split(',').map(|a| a.trim().parse::<i32>().ok());
+    let length = args.next().flatten();
+    let scale = args.next().flatten();
+    (base, length, scale, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// mysqldump 输出的片段
+    const MYSQL_DUMP: &str = r#"
+/*!40101 SET NAMES utf8mb4 */;
+DROP TABLE IF EXISTS `users`;
+CREATE TABLE `users` (
+  `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+  `name` varchar(50) NOT NULL COMMENT '用户名',
+  `email` varchar(100) DEFAULT NULL,
+  `created_at` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_email` (`email`),
+  KEY `idx_name_created` (`name`,`created_at`)
+) ENGINE=InnoDB AUTO_INCREMENT=3 DEFAULT CHARSET=utf8mb4 COMMENT='用户';
+LOCK TABLES `users` WRITE;
+INSERT INTO `users` VALUES (1,'a','a@example.com','2024-01-01 00:00:00');
+UNLOCK TABLES;
+"#;
+
+    #[tokio::test]
+    async fn mysql_dump() {
+        let meta = DumpMetadata::new(MYSQL_DUMP, Driver::Mysql).unwrap();
+        let tables = meta.tables("", "").await.unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].name, "users");
+        assert_eq!(tables[0].comment, "用户");
+
+        let columns = meta.columns("", "", "users").await.unwrap();
+        let names = columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["id", "name", "email", "created_at"]);
+        let id = &columns[0];
+        assert!(id.is_primary_key && id.is_auto_incr && id.is_unsigned && !id.is_null);
+        assert_eq!(columns[1].r#type, Some(ColumnType::VarChar));
+        assert_eq!(columns[1].length, Some(50));
+        assert_eq!(columns[1].comment, "用户名");
+        assert!(columns[2].is_null);
+
+        let indexs = meta.indexs("", "", "users").await.unwrap();
+        let rows = indexs
+            .iter()
+            .map(|i| {
+                (
+                    i.key_name.as_str(),
+                    i.column_name.as_str(),
+                    i.seq_in_index,
+                    i.non_unique,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                ("PRIMARY", "id", 1, 0),
+                ("uk_email", "email", 1, 0),
+                ("idx_name_created", "name", 1, 1),
+                ("idx_name_created", "created_at", 2, 1),
+            ]
+        );
+        assert!(meta.columns("", "", "missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn ordinal_position_starts_at_one() {
+        let meta = DumpMetadata::new(MYSQL_DUMP, Driver::Mysql).unwrap();
+        let columns = meta.columns("", "", "users").await.unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| c.ordinal_position)
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+    }
+}
@@ -1,26 +1,53 @@
 // #![allow(unused)]
 
-use std::{fmt::Display, pin::Pin};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    ops::Range,
+    path::PathBuf,
+    pin::Pin,
+    str::FromStr,
+    time::Duration,
+};
 
 use error::{Error, Result};
+use futures_util::{StreamExt as _, TryStreamExt as _, future, stream};
 use serde::{Deserialize, Serialize};
-use sqlx::{AnyPool, MySqlPool, PgPool, SqlitePool};
+use sqlx::{
+    Executor as _, MySql, MySqlPool, PgPool,
+    mysql::{MySqlConnectOptions, MySqlSslMode},
+    pool::PoolOptions,
+    postgres::{PgConnectOptions, PgSslMode},
+    sqlite::SqliteConnectOptions,
+};
 
+mod any;
+mod check;
+mod dbml;
+mod diff;
+mod dump;
 pub mod error;
+mod mermaid;
 mod mysql;
 mod postgres;
 mod sqlite;
 
+pub use any::AnyMetadata;
+pub use check::{NumericRange, numeric_ranges};
+pub use dbml::to_dbml;
+pub use diff::{ColumnDiff, SchemaDiff, TableDiff, diff_schemas};
+pub use dump::DumpMetadata;
+pub use mermaid::to_mermaid_er;
 pub use mysql::MysqlMetadata;
 pub use postgres::PostgresMetadata;
 pub use sqlite::SqliteMetadata;
 
-/// Rust1.70 关键字
-static RUST_KEYWORDS: [&str; 52] = [
+/// Rust 1.85关键字
+static RUST_KEYWORDS: [&str; 53] = [
     "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
     "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
     "ref", "return", "Self", "self", "static", "struct", "super", "trait", "true", "type", "union",
-    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
     "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
 ];
 
@@ -43,12 +70,34 @@ impl Display for Driver {
     }
 }
 
+impl Driver {
+    /// 分页子句，limit 和 offset 可以是具体数值，也可以是占位符
+    ///
+    /// Mysql、Postgres、Sqlite 均支持 `LIMIT ? OFFSET ?`
+    pub fn limit_clause(&self, limit: impl Display, offset: impl Display) -> String {
+        match self {
+            Driver::Mysql | Driver::Postgres | Driver::Sqlite => {
+                format!("LIMIT {limit} OFFSET {offset}")
+            }
+        }
+    }
+
+    /// 绑定参数占位符，index 从 1 开始
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            Driver::Postgres => format!("${index}"),
+            Driver::Mysql | Driver::Sqlite => "?".to_string(),
+        }
+    }
+}
+
 impl TryFrom<&str> for Driver {
     type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let driver = value.trim().to_lowercase();
-        if driver.starts_with("mysql") {
+        // mariadb 使用 Mysql 协议；postgres 前缀同时匹配 postgresql
+        if driver.starts_with("mysql") || driver.starts_with("mariadb") {
             return Ok(Self::Mysql);
         }
         if driver.starts_with("postgres") {
@@ -61,14 +110,41 @@ impl TryFrom<&str> for Driver {
     }
 }
 
+impl FromStr for Driver {
+    type Err = Error;
+
+    /// 解析驱动名称，如配置中的 `mysql`，不区分大小写
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "mysql" | "mariadb" => Ok(Self::Mysql),
+            "postgres" | "postgresql" => Ok(Self::Postgres),
+            "sqlite" => Ok(Self::Sqlite),
+            _ => Err(Error::E("driver not support")),
+        }
+    }
+}
+
+/// 并发查询列信息时同时进行的最大查询数
+const FETCH_COLUMNS_CONCURRENCY: usize = 8;
+
 type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 /// 数据库元数据
 pub trait DatabaseMetadata: Send + Sync {
+    /// 检查连接是否可用，在连接池上执行 `SELECT 1`，默认不支持
+    fn ping(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
     /// 获取所有的库
     fn databases(&self) -> BoxFuture<'_, Result<Vec<Database>>>;
-    /// 获取所有的模式
+    /// 获取所有的模式，不包含系统模式
+    ///
+    /// Mysql 中模式即数据库，返回与 `databases` 相同的列表
     fn schemas(&self) -> BoxFuture<'_, Result<Vec<Schema>>>;
+    /// 获取当前连接使用的模式，schema 参数为空时即使用该模式，默认不支持
+    fn current_schema(&self) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
     /// 获取所有的表
     fn tables<'a>(
         &'a self,
@@ -82,6 +158,26 @@ pub trait DatabaseMetadata: Send + Sync {
         schema: &'a str,
         table_name: &'a str,
     ) -> BoxFuture<'a, Result<Vec<Column>>>;
+    /// 获取多个表的字段，默认逐表并发查询，按 table_names 的顺序返回
+    fn all_columns<'a>(
+        &'a self,
+        database: &'a str,
+        schema: &'a str,
+        table_names: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<Vec<Column>>> {
+        Box::pin(async move {
+            // 限制同时进行的查询数量，避免占满连接池；buffered 保持表的顺序
+            let futures = table_names
+                .iter()
+                .map(|table_name| self.columns(database, schema, table_name))
+                .collect::<Vec<_>>();
+            let columns = stream::iter(futures)
+                .buffered(FETCH_COLUMNS_CONCURRENCY)
+                .try_collect::<Vec<_>>()
+                .await?;
+            Ok(columns.into_iter().flatten().collect())
+        })
+    }
     /// 获取表索引
     fn indexs<'a>(
         &'a self,
@@ -89,6 +185,15 @@ pub trait DatabaseMetadata: Send + Sync {
         schema: &'a str,
         table_name: &'a str,
     ) -> BoxFuture<'a, Result<Vec<Index>>>;
+    /// 获取表的外键，默认不支持
+    fn foreign_keys<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        _table_name: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ForeignKey>>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
     /// 创建表SQL
     fn create_table_sql<'a>(
         &'a self,
@@ -96,18 +201,270 @@ pub trait DatabaseMetadata: Send + Sync {
         schema: &'a str,
         table_name: &'a str,
     ) -> BoxFuture<'a, Result<String>>;
+    /// 获取表的检查约束，按约束名排序，默认不支持
+    fn check_constraints<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+        _table_name: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<CheckConstraint>>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
+    /// 获取模式下的存储过程和函数，按名称排序，默认不支持
+    fn routines<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Routine>>> {
+        Box::pin(async move { Err(Error::E("not supported")) })
+    }
+    /// 获取单个表的完整定义，包括列、索引和外键，默认并发查询，表不存在时返回错误
+    fn table_detail<'a>(
+        &'a self,
+        database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
+    ) -> BoxFuture<'a, Result<TableDetail>> {
+        Box::pin(async move {
+            let (tables, columns, indexes, foreign_keys) = future::try_join4(
+                self.tables(database, schema),
+                self.columns(database, schema, table_name),
+                self.indexs(database, schema, table_name),
+                self.foreign_keys(database, schema, table_name),
+            )
+            .await?;
+            let table = tables
+                .into_iter()
+                .find(|t| t.name == table_name)
+                .ok_or(Error::E("table not found"))?;
+            Ok(TableDetail {
+                table,
+                columns,
+                indexes,
+                foreign_keys,
+            })
+        })
+    }
 }
 
-pub async fn database_metadata(url: &str) -> Box<dyn DatabaseMetadata> {
-    let driver = Driver::try_from(url).unwrap_or_else(|e| {
-        eprintln!("database metadata error: {:?}", e);
-        std::process::exit(1);
-    });
-    match driver {
-        Driver::Mysql => Box::new(MysqlMetadata::new(MySqlPool::connect(url).await.unwrap())),
-        Driver::Postgres => Box::new(PostgresMetadata::new(PgPool::connect(url).await.unwrap())),
-        Driver::Sqlite => Box::new(SqliteMetadata::new(SqlitePool::connect(url).await.unwrap())),
+/// 数据库连接选项
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    /// Unix 域套接字路径，设置后将覆盖 url 中的主机，仅对 Mysql 和 Postgres 有效
+    ///
+    /// url 中也可以直接指定套接字：
+    /// - Postgres: `postgres://postgres@localhost/test?host=/var/run/postgresql`
+    /// - Mysql: `mysql://root@localhost/test?socket=/var/run/mysqld/mysqld.sock`
+    pub socket: Option<PathBuf>,
+    /// 单条语句的最长执行时间，连接建立时设置到会话上，由服务端中止超时的查询
+    ///
+    /// - Postgres: `statement_timeout`
+    /// - Mysql: `max_execution_time`，仅对 SELECT 语句生效
+    /// - Sqlite 不支持，忽略该选项
+    pub statement_timeout: Option<Duration>,
+    /// 连接池的最大连接数，未设置时使用 sqlx 的默认值 10
+    pub max_connections: Option<u32>,
+    /// 连接池保持的最小连接数，连接池创建后由后台任务预先建立这些连接
+    pub min_connections: Option<u32>,
+    /// 获取连接的最长等待时间（包括建立连接），服务端不可达时在该时间后返回错误，未设置时为 30 秒
+    pub acquire_timeout: Option<Duration>,
+    /// TLS 连接模式，仅对 Mysql 和 Postgres 有效
+    ///
+    /// 设置后覆盖 url 中的 `sslmode`（Postgres）或 `ssl-mode`（Mysql），未设置时使用 url 中的值，
+    /// 两者都未设置时为 Prefer
+    pub ssl_mode: Option<SslMode>,
+    /// 校验服务端证书使用的 CA 证书（PEM 格式），设置后覆盖 url 中的 `sslrootcert`（Postgres）
+    /// 或 `ssl-ca`（Mysql）
+    pub ssl_root_cert: Option<PathBuf>,
+}
+
+/// TLS 连接模式，与 Postgres 的 sslmode 取值一致
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    /// 不使用 TLS
+    Disable,
+    /// 服务端支持时使用 TLS，否则使用明文连接
+    Prefer,
+    /// 必须使用 TLS，不校验服务端证书
+    Require,
+    /// 必须使用 TLS，并校验服务端证书由受信任的 CA 签发
+    VerifyCa,
+    /// 在 VerifyCa 的基础上校验证书中的主机名
+    VerifyFull,
+}
+
+impl From<SslMode> for PgSslMode {
+    fn from(mode: SslMode) -> Self {
+        match mode {
+            SslMode::Disable => Self::Disable,
+            SslMode::Prefer => Self::Prefer,
+            SslMode::Require => Self::Require,
+            SslMode::VerifyCa => Self::VerifyCa,
+            SslMode::VerifyFull => Self::VerifyFull,
+        }
+    }
+}
+
+impl From<SslMode> for MySqlSslMode {
+    fn from(mode: SslMode) -> Self {
+        match mode {
+            SslMode::Disable => Self::Disabled,
+            SslMode::Prefer => Self::Preferred,
+            SslMode::Require => Self::Required,
+            SslMode::VerifyCa => Self::VerifyCa,
+            SslMode::VerifyFull => Self::VerifyIdentity,
+        }
+    }
+}
+
+/// 获取数据库元数据，url 不支持或无法连接时返回错误
+pub async fn database_metadata(url: &str) -> Result<Box<dyn DatabaseMetadata>> {
+    database_metadata_with(url, &ConnectOptions::default()).await
+}
+
+/// 获取数据库元数据，连接失败时按指数退避重试，用于数据库尚未就绪（如容器启动中）的场景
+///
+/// 共尝试 attempts 次（至少一次），第 n 次重试前等待 base_delay * 2^(n-1)，全部失败时返回最后一次的错误
+pub async fn database_metadata_retry(
+    url: &str,
+    attempts: u32,
+    base_delay: Duration,
+) -> Result<Box<dyn DatabaseMetadata>> {
+    database_metadata_retry_with(url, &ConnectOptions::default(), attempts, base_delay).await
+}
+
+/// 使用连接选项获取数据库元数据，连接失败时按指数退避重试
+pub async fn database_metadata_retry_with(
+    url: &str,
+    opts: &ConnectOptions,
+    attempts: u32,
+    base_delay: Duration,
+) -> Result<Box<dyn DatabaseMetadata>> {
+    retry(attempts, base_delay, || database_metadata_with(url, opts)).await
+}
+
+/// 按指数退避重试，只重试连接错误，URL 格式错误等直接返回
+async fn retry<T, F, Fut>(attempts: u32, base_delay: Duration, mut f: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut delay = base_delay;
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Err(err) if attempt < attempts && is_connection_error(&err) => {
+                tokio::time::sleep(delay).await;
+                delay = delay.saturating_mul(2);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 是否为可以重试的连接错误
+fn is_connection_error(err: &Error) -> bool {
+    match err {
+        Error::Io(_) => true,
+        Error::Sql(sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut) => true,
+        // Postgres 启动中拒绝连接：the database system is starting up
+        Error::Sql(sqlx::Error::Database(e)) => e.code().as_deref() == Some("57P03"),
+        _ => false,
+    }
+}
+
+impl ConnectOptions {
+    /// 根据连接池相关的选项构建连接池配置
+    fn pool_options<DB: sqlx::Database>(&self) -> PoolOptions<DB> {
+        let mut pool_options = PoolOptions::new();
+        if let Some(max) = self.max_connections {
+            pool_options = pool_options.max_connections(max);
+        }
+        if let Some(min) = self.min_connections {
+            pool_options = pool_options.min_connections(min);
+        }
+        if let Some(timeout) = self.acquire_timeout {
+            pool_options = pool_options.acquire_timeout(timeout);
+        }
+        pool_options
+    }
+}
+
+/// 使用指定的连接选项获取数据库元数据
+pub async fn database_metadata_with(
+    url: &str,
+    opts: &ConnectOptions,
+) -> Result<Box<dyn DatabaseMetadata>> {
+    let metadata: Box<dyn DatabaseMetadata> = match Driver::try_from(url)? {
+        Driver::Mysql => Box::new(MysqlMetadata::new(mysql_pool(url, opts).await?)),
+        Driver::Postgres => Box::new(PostgresMetadata::new(postgres_pool(url, opts).await?)),
+        Driver::Sqlite => {
+            let options = SqliteConnectOptions::from_str(url)?;
+            Box::new(SqliteMetadata::new(
+                opts.pool_options().connect_with(options).await?,
+            ))
+        }
+    };
+    Ok(metadata)
+}
+
+/// 解析 url 并应用套接字和 TLS 选项，连接选项中的设置优先于 url 中的参数
+fn mysql_connect_options(url: &str, opts: &ConnectOptions) -> Result<MySqlConnectOptions> {
+    let mut options = MySqlConnectOptions::from_str(url)?;
+    if let Some(socket) = &opts.socket {
+        options = options.socket(socket);
+    }
+    if let Some(mode) = opts.ssl_mode {
+        options = options.ssl_mode(mode.into());
+    }
+    if let Some(cert) = &opts.ssl_root_cert {
+        options = options.ssl_ca(cert);
+    }
+    Ok(options)
+}
+
+/// 按连接选项创建 Mysql 连接池
+async fn mysql_pool(url: &str, opts: &ConnectOptions) -> Result<MySqlPool> {
+    let options = mysql_connect_options(url, opts)?;
+    let mut pool_options = opts.pool_options::<MySql>();
+    if let Some(timeout) = opts.statement_timeout {
+        // Mysql 没有对应的连接参数，需要在每个连接建立后设置会话变量
+        let sql = format!("SET SESSION max_execution_time = {}", timeout.as_millis());
+        pool_options = pool_options.after_connect(move |conn, _| {
+            let sql = sql.clone();
+            Box::pin(async move {
+                conn.execute(sql.as_str()).await?;
+                Ok(())
+            })
+        });
+    }
+    Ok(pool_options.connect_with(options).await?)
+}
+
+/// 解析 url 并应用连接选项，连接选项中的设置优先于 url 中的参数
+fn postgres_connect_options(url: &str, opts: &ConnectOptions) -> Result<PgConnectOptions> {
+    let mut options = PgConnectOptions::from_str(url)?;
+    if let Some(socket) = &opts.socket {
+        options = options.socket(socket);
+    }
+    if let Some(mode) = opts.ssl_mode {
+        options = options.ssl_mode(mode.into());
+    }
+    if let Some(cert) = &opts.ssl_root_cert {
+        options = options.ssl_root_cert(cert);
+    }
+    if let Some(timeout) = opts.statement_timeout {
+        options = options.options([("statement_timeout", timeout.as_millis())]);
     }
+    Ok(options)
+}
+
+/// 按连接选项创建 Postgres 连接池
+async fn postgres_pool(url: &str, opts: &ConnectOptions) -> Result<PgPool> {
+    let options = postgres_connect_options(url, opts)?;
+    Ok(opts.pool_options().connect_with(options).await?)
 }
 
 /// 数据库
@@ -123,15 +480,35 @@ pub struct Schema {
 }
 
 /// 表信息
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Table {
     pub schema: String,
     pub name: String,
     pub comment: String,
+    /// 表的默认排序规则（Mysql 的 TABLE_COLLATION），列未指定排序规则时继承该值
+    pub collation: Option<String>,
+    /// 表的类型
+    #[serde(default)]
+    pub kind: TableKind,
+}
+
+/// 表的类型
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TableKind {
+    /// 普通表
+    #[default]
+    BaseTable,
+    /// 视图
+    View,
+    /// 物化视图（Postgres）
+    MaterializedView,
+    /// 外部表（Postgres）
+    ForeignTable,
 }
 
 /// 列信息
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Column {
     // 库名
@@ -142,6 +519,8 @@ pub struct Column {
     pub table_name: String,
     /// 字段名
     pub name: String,
+    /// 列在表中的位置，从 1 开始
+    pub ordinal_position: i32,
     /// 字段类型
     pub r#type: Option<ColumnType>,
     /// 字段长度，可以为空
@@ -150,6 +529,9 @@ pub struct Column {
     pub scale: Option<i32>,
     /// 默认值
     pub default: Option<String>,
+    /// 默认值的分类，区分字面量和函数、关键字等表达式
+    #[serde(default)]
+    pub default_value: DefaultValue,
     /// 枚举值列表
     pub enum_values: Option<Vec<String>>,
     /// 备注
@@ -165,12 +547,202 @@ pub struct Column {
     pub is_primary_key: bool,
     /// 是否无符号
     pub is_unsigned: bool,
+    /// 是否为生成列，值由数据库根据表达式计算，新增和修改时不能写入
+    #[serde(default)]
+    pub is_generated: bool,
+    /// 拥有该列的序列名（Postgres serial 列），如 `public.user_id_seq`
+    pub owned_sequence: Option<String>,
+    /// 字符集，仅字符类型的列有值，如 Mysql 的 `utf8mb4`，Postgres 和 Sqlite 没有列级字符集
+    #[serde(default)]
+    pub charset: Option<String>,
+    /// 排序规则，仅字符类型的列有值，如 Mysql 的 `utf8mb4_general_ci`，
+    /// Postgres 只在显式指定时有值，如 `C`
+    pub collation: Option<String>,
 
     // 对应 Rust 类型
     pub rust_type: String,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// 列默认值的分类
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum DefaultValue {
+    /// 没有默认值或默认值为 NULL
+    #[default]
+    None,
+    /// 字面量，字符串已去掉引号，如 `'active'` 为 `active`
+    Literal(String),
+    /// 函数、关键字等表达式，如 `CURRENT_TIMESTAMP`、`nextval('user_id_seq'::regclass)`
+    Expression(String),
+}
+
+/// 取当前时间的关键字，Mysql 的 COLUMN_DEFAULT 中不带引号，需要与字符串区分
+const TIME_KEYWORDS: [&str; 6] = [
+    "CURRENT_TIMESTAMP",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "NOW",
+];
+
+impl DefaultValue {
+    /// 按数据库元数据中默认值的写法分类
+    ///
+    /// - Postgres: 字符串带引号，字面量可能带类型转换，如 `'active'::character varying`
+    /// - Sqlite: 字符串带引号，表达式为建表语句中的原文，如 `(datetime('now'))`
+    /// - Mysql: 字符串不带引号，除取当前时间的关键字外均视为字面量，
+    ///   8.0 的表达式默认值需由调用方根据 EXTRA 中的 `DEFAULT_GENERATED` 判断
+    pub fn classify(default: Option<&str>, driver: Driver) -> Self {
+        let Some(default) = default.map(str::trim).filter(|d| !d.is_empty()) else {
+            return Self::None;
+        };
+        let value = match driver {
+            Driver::Postgres => strip_pg_cast(default),
+            Driver::Mysql | Driver::Sqlite => default,
+        };
+        if value.eq_ignore_ascii_case("null") {
+            return Self::None;
+        }
+        if is_time_keyword(value) {
+            return Self::Expression(default.to_string());
+        }
+        if let Some((literal, "")) = quoted_literal(value) {
+            return Self::Literal(literal);
+        }
+        if value.parse::<f64>().is_ok()
+            || value.eq_ignore_ascii_case("true")
+            || value.eq_ignore_ascii_case("false")
+        {
+            return Self::Literal(value.to_string());
+        }
+        match driver {
+            Driver::Mysql => Self::Literal(default.to_string()),
+            Driver::Postgres | Driver::Sqlite => Self::Expression(default.to_string()),
+        }
+    }
+}
+
+/// 去掉 Postgres 字面量后的类型转换，如 `'a'::text` 为 `'a'`、`NULL::integer` 为 `NULL`，
+/// 不是字面量的表达式原样返回
+fn strip_pg_cast(default: &str) -> &str {
+    let literal_len = match quoted_literal(default) {
+        Some((_, rest)) => default.len() - rest.len(),
+        None => default.find("::").unwrap_or(default.len()),
+    };
+    let (literal, cast) = default.split_at(literal_len);
+    let is_literal = literal.starts_with('\'')
+        || literal.eq_ignore_ascii_case("null")
+        || literal.parse::<f64>().is_ok();
+    if is_literal && (cast.is_empty() || cast.starts_with("::")) && !cast.contains('\'') {
+        literal
+    } else {
+        default
+    }
+}
+
+/// 解析开头的单引号字符串，返回去掉引号和转义后的内容及剩余部分
+fn quoted_literal(s: &str) -> Option<(String, &str)> {
+    let rest = s.strip_prefix('\'')?;
+    let mut literal = String::new();
+    let mut chars = rest.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\'' {
+            literal.push(c);
+            continue;
+        }
+        // 两个单引号为转义的单引号
+        if chars.next_if(|(_, c)| *c == '\'').is_some() {
+            literal.push('\'');
+            continue;
+        }
+        return Some((literal, &rest[i + 1..]));
+    }
+    None
+}
+
+/// 是否为取当前时间的关键字或函数，如 `CURRENT_TIMESTAMP`、`CURRENT_TIMESTAMP(3)`、`now()`
+fn is_time_keyword(value: &str) -> bool {
+    let (name, args) = value.split_once('(').unwrap_or((value, ""));
+    let args_valid = value.find('(').is_none()
+        || args
+            .strip_suffix(')')
+            .is_some_and(|a| a.chars().all(|c| c.is_ascii_digit()));
+    args_valid
+        && TIME_KEYWORDS
+            .iter()
+            .any(|k| name.trim().eq_ignore_ascii_case(k))
+}
+
+/// 注释中的类型提示指令前缀
+const TYPE_HINT_DIRECTIVE: &str = "@rust:";
+
+impl Column {
+    /// 建表语句中的字符集和排序规则子句，与表的默认排序规则相同时无需声明，返回 None
+    pub fn collate_clause(&self, table: &Table) -> Option<String> {
+        let collation = self.collation.as_deref()?;
+        if table.collation.as_deref() == Some(collation) {
+            return None;
+        }
+        Some(match &self.charset {
+            Some(charset) => format!("CHARACTER SET {charset} COLLATE {collation}"),
+            None => format!("COLLATE {collation}"),
+        })
+    }
+
+    /// 带长度的字段类型，如 `VARCHAR(255)`、`DECIMAL(10,2)`
+    pub fn full_type(&self) -> String {
+        let ty = self
+            .r#type
+            .as_ref()
+            .map(|t| t.to_string())
+            .unwrap_or_default();
+        match (self.length, self.scale) {
+            (Some(length), Some(scale)) if length > 0 => format!("{ty}({length},{scale})"),
+            (Some(length), _) if length > 0 => format!("{ty}({length})"),
+            _ => ty,
+        }
+    }
+
+    /// 从字段备注中解析类型提示
+    ///
+    /// 语法为 `@rust:<类型>`，类型到空白字符为止，可以出现在备注的任意位置，如：
+    /// `用户ID @rust:uuid::Uuid`、`@rust:Vec<my_crate::Tag> 标签`。
+    /// 有多个指令时取第一个合法的，格式不合法的指令会被忽略
+    pub fn type_hint(&self) -> Option<&str> {
+        self.comment
+            .match_indices(TYPE_HINT_DIRECTIVE)
+            .map(|(pos, directive)| {
+                let rest = &self.comment[pos + directive.len()..];
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                &rest[..end]
+            })
+            .find(|ty| valid_type_hint(ty))
+    }
+}
+
+/// 校验类型提示是否为形如 `a::B<C, D>` 的合法 Rust 类型路径
+fn valid_type_hint(ty: &str) -> bool {
+    if !ty.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        return false;
+    }
+    let mut depth = 0usize;
+    for c in ty.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            ':' | ',' | '_' => {}
+            c if c.is_ascii_alphanumeric() => {}
+            _ => return false,
+        }
+    }
+    depth == 0 && !ty.ends_with(':') && !ty.contains(":::")
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Index {
     /// 表名
@@ -191,7 +763,145 @@ pub struct Index {
     pub index_comment: String,
 }
 
-#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+/// 外键
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignKey {
+    /// 表名
+    pub table_name: String,
+    /// 外键约束名
+    pub name: String,
+    /// 外键列，组合外键按定义顺序排列
+    pub columns: Vec<String>,
+    /// 引用的表名
+    pub referenced_table: String,
+    /// 引用的列，与 columns 一一对应
+    pub referenced_columns: Vec<String>,
+    /// 删除引用的行时执行的动作，如 `CASCADE`、`SET NULL`，未声明时为 None
+    #[serde(default)]
+    pub on_delete: Option<String>,
+    /// 更新引用的行时执行的动作
+    #[serde(default)]
+    pub on_update: Option<String>,
+}
+
+/// 检查约束
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckConstraint {
+    /// 表名
+    pub table_name: String,
+    /// 约束名，Sqlite 和转储文件中未命名的约束为空
+    pub name: String,
+    /// 约束的表达式，不含 CHECK 关键字，如 `(age >= 0)`
+    pub expression: String,
+}
+
+/// 存储过程或函数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Routine {
+    /// 模式
+    pub schema: String,
+    /// 名称，Postgres 的重载函数名称相同，参数不同
+    pub name: String,
+    pub kind: RoutineKind,
+    /// 函数的返回类型，存储过程为 None
+    pub return_type: Option<String>,
+    /// 参数，按声明顺序排列
+    pub parameters: Vec<RoutineParameter>,
+}
+
+/// 存储过程或函数
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum RoutineKind {
+    Procedure,
+    Function,
+}
+
+/// 存储过程或函数的参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutineParameter {
+    /// 参数名，未命名的参数为空字符串
+    pub name: String,
+    /// 参数模式：IN、OUT、INOUT，Postgres 还有 VARIADIC
+    pub mode: String,
+    /// 参数类型
+    pub r#type: String,
+}
+
+/// 合并按约束名、列序号排列的单列外键，组合外键每列查询出一行
+fn merge_foreign_keys(rows: Vec<ForeignKey>) -> Vec<ForeignKey> {
+    let mut foreign_keys: Vec<ForeignKey> = vec![];
+    for row in rows {
+        match foreign_keys.last_mut() {
+            Some(fk) if fk.name == row.name => {
+                fk.columns.extend(row.columns);
+                fk.referenced_columns.extend(row.referenced_columns);
+            }
+            _ => foreign_keys.push(row),
+        }
+    }
+    foreign_keys
+}
+
+/// 日期时间类型使用的库
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DateTimeBackend {
+    /// time，各驱动默认映射的类型
+    #[default]
+    Time,
+    /// chrono
+    Chrono,
+}
+
+impl DateTimeBackend {
+    /// 将驱动映射出的 time 类型转换为对应库的类型，非日期时间类型原样返回
+    pub fn rust_type<'a>(&self, rust_type: &'a str) -> &'a str {
+        match (self, rust_type) {
+            (Self::Chrono, "time::Date") => "chrono::NaiveDate",
+            (Self::Chrono, "time::Time") => "chrono::NaiveTime",
+            (Self::Chrono, "time::PrimitiveDateTime") => "chrono::NaiveDateTime",
+            (Self::Chrono, "time::OffsetDateTime") => "chrono::DateTime<chrono::Utc>",
+            _ => rust_type,
+        }
+    }
+}
+
+/// 定点数类型使用的库
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub enum DecimalBackend {
+    /// bigdecimal，各驱动默认映射的类型
+    #[default]
+    #[serde(rename = "bigdecimal")]
+    BigDecimal,
+    /// rust_decimal
+    #[serde(rename = "rust_decimal")]
+    RustDecimal,
+}
+
+/// rust_decimal::Decimal 能表示的最大有效数字位数
+const RUST_DECIMAL_MAX_PRECISION: i32 = 28;
+
+impl DecimalBackend {
+    /// 将驱动映射出的 bigdecimal 类型转换为对应库的类型，非定点数类型原样返回
+    ///
+    /// precision 为列声明的精度，超过 rust_decimal 能表示的位数时仍使用 bigdecimal
+    pub fn rust_type<'a>(&self, rust_type: &'a str, precision: Option<i32>) -> &'a str {
+        match (self, rust_type) {
+            (Self::RustDecimal, "bigdecimal::BigDecimal")
+                if precision.is_none_or(|p| p <= RUST_DECIMAL_MAX_PRECISION) =>
+            {
+                "rust_decimal::Decimal"
+            }
+            _ => rust_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ColumnType {
     Bigint,
     Binary,
@@ -232,50 +942,53 @@ pub enum ColumnType {
     Varbinary,
     VarChar,
     Year,
+    /// 未识别的类型，保留数据库返回的原始类型名称，如 Postgres 的 CITEXT、int4[]
+    Unknown(String),
 }
 
 impl Display for ColumnType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ColumnType::Bigint => f.write_str("BIGINT"),
-            ColumnType::Binary => f.write_str("BIGINT"),
-            ColumnType::Bit => f.write_str("BIGINT"),
-            ColumnType::Blob => f.write_str("BIGINT"),
-            ColumnType::Char => f.write_str("BIGINT"),
-            ColumnType::Date => f.write_str("BIGINT"),
-            ColumnType::DateTime => f.write_str("BIGINT"),
-            ColumnType::Decimal => f.write_str("BIGINT"),
-            ColumnType::Double => f.write_str("BIGINT"),
-            ColumnType::Enum => f.write_str("BIGINT"),
-            ColumnType::Float => f.write_str("BIGINT"),
-            ColumnType::Geometry => f.write_str("BIGINT"),
-            ColumnType::GeometryCollection => f.write_str("BIGINT"),
-            ColumnType::Int => f.write_str("BIGINT"),
-            ColumnType::Integer => f.write_str("BIGINT"),
-            ColumnType::Json => f.write_str("BIGINT"),
-            ColumnType::LineString => f.write_str("BIGINT"),
-            ColumnType::LongBlob => f.write_str("BIGINT"),
-            ColumnType::LongText => f.write_str("BIGINT"),
-            ColumnType::MediumBlob => f.write_str("BIGINT"),
-            ColumnType::MediumInt => f.write_str("BIGINT"),
-            ColumnType::MediumText => f.write_str("BIGINT"),
-            ColumnType::MultilineString => f.write_str("BIGINT"),
-            ColumnType::MultiPoint => f.write_str("BIGINT"),
-            ColumnType::Numeric => f.write_str("BIGINT"),
-            ColumnType::Point => f.write_str("BIGINT"),
-            ColumnType::Polygon => f.write_str("BIGINT"),
-            ColumnType::Real => f.write_str("BIGINT"),
-            ColumnType::Set => f.write_str("BIGINT"),
-            ColumnType::SmallInt => f.write_str("BIGINT"),
-            ColumnType::Text => f.write_str("BIGINT"),
-            ColumnType::Time => f.write_str("BIGINT"),
-            ColumnType::Timestamp => f.write_str("BIGINT"),
-            ColumnType::TinyBlob => f.write_str("BIGINT"),
-            ColumnType::TinyInt => f.write_str("BIGINT"),
-            ColumnType::TinyText => f.write_str("BIGINT"),
-            ColumnType::Varbinary => f.write_str("BIGINT"),
-            ColumnType::VarChar => f.write_str("BIGINT"),
-            ColumnType::Year => f.write_str("BIGINT"),
+            ColumnType::Binary => f.write_str("BINARY"),
+            ColumnType::Bit => f.write_str("BIT"),
+            ColumnType::Blob => f.write_str("BLOB"),
+            ColumnType::Char => f.write_str("CHAR"),
+            ColumnType::Date => f.write_str("DATE"),
+            ColumnType::DateTime => f.write_str("DATETIME"),
+            ColumnType::Decimal => f.write_str("DECIMAL"),
+            ColumnType::Double => f.write_str("DOUBLE"),
+            ColumnType::Enum => f.write_str("ENUM"),
+            ColumnType::Float => f.write_str("FLOAT"),
+            ColumnType::Geometry => f.write_str("GEOMETRY"),
+            ColumnType::GeometryCollection => f.write_str("GEOMETRYCOLLECTION"),
+            ColumnType::Int => f.write_str("INT"),
+            ColumnType::Integer => f.write_str("INTEGER"),
+            ColumnType::Json => f.write_str("JSON"),
+            ColumnType::LineString => f.write_str("LINESTRING"),
+            ColumnType::LongBlob => f.write_str("LONGBLOB"),
+            ColumnType::LongText => f.write_str("LONGTEXT"),
+            ColumnType::MediumBlob => f.write_str("MEDIUMBLOB"),
+            ColumnType::MediumInt => f.write_str("MEDIUMINT"),
+            ColumnType::MediumText => f.write_str("MEDIUMTEXT"),
+            ColumnType::MultilineString => f.write_str("MULTILINESTRING"),
+            ColumnType::MultiPoint => f.write_str("MULTIPOINT"),
+            ColumnType::Numeric => f.write_str("NUMERIC"),
+            ColumnType::Point => f.write_str("POINT"),
+            ColumnType::Polygon => f.write_str("POLYGON"),
+            ColumnType::Real => f.write_str("REAL"),
+            ColumnType::Set => f.write_str("SET"),
+            ColumnType::SmallInt => f.write_str("SMALLINT"),
+            ColumnType::Text => f.write_str("TEXT"),
+            ColumnType::Time => f.write_str("TIME"),
+            ColumnType::Timestamp => f.write_str("TIMESTAMP"),
+            ColumnType::TinyBlob => f.write_str("TINYBLOB"),
+            ColumnType::TinyInt => f.write_str("TINYINT"),
+            ColumnType::TinyText => f.write_str("TINYTEXT"),
+            ColumnType::Varbinary => f.write_str("VARBINARY"),
+            ColumnType::VarChar => f.write_str("VARCHAR"),
+            ColumnType::Year => f.write_str("YEAR"),
+            ColumnType::Unknown(ty) => f.write_str(ty),
         }
     }
 }
@@ -322,12 +1035,12 @@ impl From<String> for ColumnType {
             "VARBINARY" => Self::Varbinary,
             "VARCHAR" => Self::VarChar,
             "YEAR" => Self::Year,
-            _ => unimplemented!(),
+            _ => Self::Unknown(value.trim().to_string()),
         }
     }
 }
 
-/// 初始化数据库驱动
+/// 初始化数据库驱动，使用 AnyPool（如 AnyMetadata）前需要调用，可重复调用
 pub fn init() {
     sqlx::any::install_default_drivers();
 }
@@ -338,19 +1051,230 @@ pub async fn fetch_table_column(
     schema: &str,
     table_names: &[&str],
 ) -> Result<(Vec<Table>, Vec<Column>)> {
-    let metadata = database_metadata(url).await;
+    let metadata = database_metadata(url).await?;
     let tables = metadata.tables("", schema).await?;
-    let mut columns = Vec::new();
-    if table_names.is_empty() {
-        for table in tables.iter() {
-            columns.push(metadata.columns("", schema, &table.name).await?);
+    let table_names = if table_names.is_empty() {
+        tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>()
+    } else {
+        table_names.to_vec()
+    };
+    let columns = metadata.all_columns("", schema, &table_names).await?;
+    Ok((tables, columns))
+}
+
+/// 获取指定数据库表和列信息，表名列表为 `Vec<String>` 等拥有所有权的形式时使用
+pub async fn fetch_table_column_owned(
+    url: &str,
+    schema: &str,
+    table_names: &[String],
+) -> Result<(Vec<Table>, Vec<Column>)> {
+    let table_names = table_names.iter().map(|t| t.as_str()).collect::<Vec<_>>();
+    fetch_table_column(url, schema, &table_names).await
+}
+
+/// 表及其列信息，可按表名直接获取列
+#[derive(Debug, Default)]
+pub struct TablesWithColumns {
+    pub tables: Vec<Table>,
+    /// 按表名分组排列的列
+    columns: Vec<Column>,
+    /// K：表名，V：该表的列在 columns 中的范围
+    index: HashMap<String, Range<usize>>,
+}
+
+impl TablesWithColumns {
+    pub fn new(tables: Vec<Table>, mut columns: Vec<Column>) -> Self {
+        // 稳定排序，同一表内的列保持原有顺序
+        columns.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+        let mut index = HashMap::new();
+        let mut start = 0;
+        for group in columns.chunk_by(|a, b| a.table_name == b.table_name) {
+            index.insert(group[0].table_name.clone(), start..start + group.len());
+            start += group.len();
+        }
+        Self {
+            tables,
+            columns,
+            index,
+        }
+    }
+
+    /// 获取表的列，表不存在时返回空
+    pub fn columns_of(&self, table: &str) -> &[Column] {
+        self.index
+            .get(table)
+            .map(|range| &self.columns[range.clone()])
+            .unwrap_or_default()
+    }
+
+    /// 所有表的列
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+}
+
+/// 获取表及其列信息，table_names 为空时获取所有表
+pub async fn fetch_tables_with_columns(
+    url: &str,
+    schema: &str,
+    table_names: &[&str],
+) -> Result<TablesWithColumns> {
+    let (tables, columns) = fetch_table_column(url, schema, table_names).await?;
+    Ok(TablesWithColumns::new(tables, columns))
+}
+
+/// 数据库结构快照，包含模式下所有的表及其列、索引和外键
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    pub tables: Vec<TableDetail>,
+}
+
+/// 表及其列、索引和外键
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableDetail {
+    #[serde(flatten)]
+    pub table: Table,
+    pub columns: Vec<Column>,
+    pub indexes: Vec<Index>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+/// 获取模式下所有表的结构快照
+pub async fn schema_snapshot(
+    metadata: &dyn DatabaseMetadata,
+    schema: &str,
+) -> Result<SchemaSnapshot> {
+    let tables = metadata.tables("", schema).await?;
+    let table_names = tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>();
+    let mut columns = metadata.all_columns("", schema, &table_names).await?;
+    let mut snapshot = SchemaSnapshot { tables: vec![] };
+    for table in tables {
+        let (table_columns, rest) = columns
+            .into_iter()
+            .partition(|c: &Column| c.table_name == table.name);
+        columns = rest;
+        let indexes = metadata.indexs("", schema, &table.name).await?;
+        let foreign_keys = metadata.foreign_keys("", schema, &table.name).await?;
+        snapshot.tables.push(TableDetail {
+            table,
+            columns: table_columns,
+            indexes,
+            foreign_keys,
+        });
+    }
+    Ok(snapshot)
+}
+
+/// 导出模式下所有表的结构为格式化的 JSON
+pub async fn export_schema_json(url: &str, schema: &str) -> Result<String> {
+    let metadata = database_metadata(url).await?;
+    let snapshot = schema_snapshot(metadata.as_ref(), schema).await?;
+    Ok(serde_json::to_string_pretty(&snapshot)?)
+}
+
+/// 生成插入或更新（upsert）SQL，没有可用的冲突目标时返回 None
+///
+/// conflict_columns 为空时，依次使用主键列、第一个唯一索引（可以是组合索引）、第一个唯一列作为冲突目标。
+/// Mysql 的冲突目标由表上的唯一索引决定，conflict_columns 仅用于排除不需要更新的列
+///
+/// - Postgres: `INSERT ... ON CONFLICT (...) DO UPDATE SET c = EXCLUDED.c`
+/// - Mysql: `INSERT ... ON DUPLICATE KEY UPDATE c = VALUES(c)`
+/// - Sqlite: `INSERT ... ON CONFLICT (...) DO UPDATE SET c = excluded.c`
+pub fn upsert_sql(
+    table: &str,
+    columns: &[&Column],
+    indexes: &[Index],
+    conflict_columns: &[&str],
+    driver: Driver,
+) -> Option<String> {
+    let conflict_columns = if conflict_columns.is_empty() {
+        let pk = columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        if pk.is_empty() {
+            unique_key(indexes).or_else(|| {
+                columns
+                    .iter()
+                    .find(|c| c.is_unique)
+                    .map(|c| vec![c.name.as_str()])
+            })?
+        } else {
+            pk
         }
     } else {
-        for table_name in table_names {
-            columns.push(metadata.columns("", schema, table_name).await?);
+        conflict_columns.to_vec()
+    };
+    if conflict_columns.is_empty() {
+        return None;
+    }
+    // 生成列不能写入，绑定参数时同样需要跳过
+    let names = columns
+        .iter()
+        .filter(|c| !c.is_generated)
+        .map(|c| c.name.as_str())
+        .collect::<Vec<_>>();
+    let placeholders = (1..=names.len())
+        .map(|i| driver.placeholder(i))
+        .collect::<Vec<_>>();
+    let updates = names
+        .iter()
+        .filter(|n| !conflict_columns.contains(n))
+        .collect::<Vec<_>>();
+
+    let mut sql = format!(
+        "INSERT INTO {table} ({}) VALUES ({})",
+        names.join(", "),
+        placeholders.join(", ")
+    );
+    match driver {
+        Driver::Mysql => {
+            // 没有可更新的列时，更新冲突列自身，使其等价于忽略冲突
+            let updates = if updates.is_empty() {
+                conflict_columns
+                    .iter()
+                    .map(|c| format!("{c} = {c}"))
+                    .collect::<Vec<_>>()
+            } else {
+                updates
+                    .iter()
+                    .map(|c| format!("{c} = VALUES({c})"))
+                    .collect::<Vec<_>>()
+            };
+            sql.push_str(&format!(" ON DUPLICATE KEY UPDATE {}", updates.join(", ")));
+        }
+        Driver::Postgres | Driver::Sqlite => {
+            let excluded = if driver == Driver::Postgres {
+                "EXCLUDED"
+            } else {
+                "excluded"
+            };
+            sql.push_str(&format!(" ON CONFLICT ({})", conflict_columns.join(", ")));
+            if updates.is_empty() {
+                sql.push_str(" DO NOTHING");
+            } else {
+                let updates = updates
+                    .iter()
+                    .map(|c| format!("{c} = {excluded}.{c}"))
+                    .collect::<Vec<_>>();
+                sql.push_str(&format!(" DO UPDATE SET {}", updates.join(", ")));
+            }
         }
     }
-    Ok((tables, columns.into_iter().flatten().collect::<Vec<_>>()))
+    Some(sql)
+}
+
+/// 按名称排序的第一个唯一索引的列，列按在索引中的位置排列
+fn unique_key(indexes: &[Index]) -> Option<Vec<&str>> {
+    let mut keys: BTreeMap<&str, Vec<&Index>> = BTreeMap::new();
+    for index in indexes.iter().filter(|ix| ix.non_unique == 0) {
+        keys.entry(&index.key_name).or_default().push(index);
+    }
+    let (_, mut key) = keys.into_iter().next()?;
+    key.sort_by_key(|ix| ix.seq_in_index);
+    Some(key.iter().map(|ix| ix.column_name.as_str()).collect())
 }
 
 /// 判断字段名称是否是由多个单词组成
@@ -358,11 +1282,889 @@ fn multi_world(name: &str) -> bool {
     name.contains(|c| ['_', '-'].contains(&c))
 }
 
+/// 是否为合法的 Rust 标识符
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// 列名转换为 Rust 字段名
+///
+/// 由多个单词组成或不是合法标识符的列名转换为 snake_case，非法字符替换为 `_`，
+/// 数字开头时添加 `_` 前缀，如 `2fa_enabled` -> `_2fa_enabled`、`order-id` -> `order_id`；
+/// 与关键字冲突时添加 `r#` 前缀
+pub fn field_name(name: &str) -> String {
+    if !multi_world(name) && is_identifier(name) {
+        return escape_keyword(name);
+    }
+    let mut field = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if !field.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        field.insert(0, '_');
+    }
+    escape_keyword(&field)
+}
+
+/// 字段名与列名不同，需要通过 `#[serde(rename)]`、`#[sqlx(rename)]` 映射到原列名
+pub fn needs_rename(name: &str) -> bool {
+    field_name(name).trim_start_matches("r#") != name
+}
+
+/// 是否为Rust关键字
+pub fn is_rust_keyword(name: &str) -> bool {
+    RUST_KEYWORDS.contains(&name)
+}
+
 /// 列名是否为Rust关键字，若为关键字，则需要在其前加 r#
-fn column_keywords(name: &str) -> String {
-    if RUST_KEYWORDS.contains(&name) {
+pub fn escape_keyword(name: &str) -> String {
+    if is_rust_keyword(name) {
         format!("r#{}", name)
     } else {
         name.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use super::*;
+
+    #[test]
+    fn limit_clause_per_driver() {
+        for driver in [Driver::Mysql, Driver::Postgres, Driver::Sqlite] {
+            assert_eq!(driver.limit_clause(10, 20), "LIMIT 10 OFFSET 20");
+        }
+        assert_eq!(
+            Driver::Postgres.limit_clause("$1", "$2"),
+            "LIMIT $1 OFFSET $2"
+        );
+        assert_eq!(Driver::Mysql.limit_clause("?", "?"), "LIMIT ? OFFSET ?");
+    }
+
+    fn commented(comment: &str) -> Column {
+        Column {
+            comment: comment.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn type_hint_from_comment() {
+        assert_eq!(commented("@rust:MyType").type_hint(), Some("MyType"));
+        assert_eq!(
+            commented("用户ID @rust:uuid::Uuid 主键").type_hint(),
+            Some("uuid::Uuid")
+        );
+        assert_eq!(
+            commented("@rust:Vec<my_crate::Tag> 标签").type_hint(),
+            Some("Vec<my_crate::Tag>")
+        );
+        // 不合法的指令被忽略，取第一个合法的
+        assert_eq!(
+            commented("@rust:1abc @rust:Vec<T @rust: @rust:Ok").type_hint(),
+            Some("Ok")
+        );
+        assert_eq!(commented("@rust:a:::b").type_hint(), None);
+        assert_eq!(commented("普通备注").type_hint(), None);
+    }
+
+    fn column(name: &str, is_primary_key: bool) -> Column {
+        Column {
+            name: name.to_string(),
+            is_primary_key,
+            ..Default::default()
+        }
+    }
+
+    fn unique_index(key_name: &str, columns: &[&str]) -> Vec<Index> {
+        columns
+            .iter()
+            .zip(1..)
+            .map(|(column, seq_in_index)| Index {
+                table_name: "users".to_string(),
+                key_name: key_name.to_string(),
+                column_name: column.to_string(),
+                seq_in_index,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn upsert_sql_per_driver() {
+        let id = column("id", true);
+        let name = column("name", false);
+        let email = column("email", false);
+        let columns = [&id, &name, &email];
+        assert_eq!(
+            upsert_sql("users", &columns, &[], &[], Driver::Postgres).unwrap(),
+            "INSERT INTO users (id, name, email) VALUES ($1, $2, $3) \
+             ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name, email = EXCLUDED.email"
+        );
+        assert_eq!(
+            upsert_sql("users", &columns, &[], &[], Driver::Mysql).unwrap(),
+            "INSERT INTO users (id, name, email) VALUES (?, ?, ?) \
+             ON DUPLICATE KEY UPDATE name = VALUES(name), email = VALUES(email)"
+        );
+        assert_eq!(
+            upsert_sql("users", &columns, &[], &[], Driver::Sqlite).unwrap(),
+            "INSERT INTO users (id, name, email) VALUES (?, ?, ?) \
+             ON CONFLICT (id) DO UPDATE SET name = excluded.name, email = excluded.email"
+        );
+        // 指定的冲突列优先于主键
+        assert_eq!(
+            upsert_sql("users", &columns, &[], &["email"], Driver::Postgres).unwrap(),
+            "INSERT INTO users (id, name, email) VALUES ($1, $2, $3) \
+             ON CONFLICT (email) DO UPDATE SET id = EXCLUDED.id, name = EXCLUDED.name"
+        );
+    }
+
+    #[test]
+    fn upsert_sql_conflict_target() {
+        let a = column("a", false);
+        let b = column("b", false);
+        let c = column("c", false);
+        let columns = [&a, &b, &c];
+        // 没有主键和唯一键时无法生成
+        assert_eq!(upsert_sql("t", &columns, &[], &[], Driver::Postgres), None);
+        // 使用组合唯一索引，列按在索引中的位置排列
+        let mut indexes = unique_index("uk_b_a", &["b", "a"]);
+        indexes.reverse();
+        assert_eq!(
+            upsert_sql("t", &columns, &indexes, &[], Driver::Sqlite).unwrap(),
+            "INSERT INTO t (a, b, c) VALUES (?, ?, ?) ON CONFLICT (b, a) DO UPDATE SET c = excluded.c"
+        );
+        // 所有列都是冲突列时忽略冲突
+        let id = column("id", true);
+        assert_eq!(
+            upsert_sql("t", &[&id], &[], &[], Driver::Postgres).unwrap(),
+            "INSERT INTO t (id) VALUES ($1) ON CONFLICT (id) DO NOTHING"
+        );
+        assert_eq!(
+            upsert_sql("t", &[&id], &[], &[], Driver::Mysql).unwrap(),
+            "INSERT INTO t (id) VALUES (?) ON DUPLICATE KEY UPDATE id = id"
+        );
+        // 生成列不写入
+        let total = Column {
+            is_generated: true,
+            ..column("total", false)
+        };
+        assert_eq!(
+            upsert_sql("t", &[&id, &a, &total], &[], &[], Driver::Mysql).unwrap(),
+            "INSERT INTO t (id, a) VALUES (?, ?) ON DUPLICATE KEY UPDATE a = VALUES(a)"
+        );
+    }
+
+    #[tokio::test]
+    async fn upsert_sql_runs_on_sqlite() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE t (a INTEGER, b TEXT, c TEXT, UNIQUE (a, b))")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let a = column("a", false);
+        let b = column("b", false);
+        let c = column("c", false);
+        let indexes = unique_index("uk", &["a", "b"]);
+        let sql = upsert_sql("t", &[&a, &b, &c], &indexes, &[], Driver::Sqlite).unwrap();
+        for value in ["old", "new"] {
+            sqlx::query(&sql)
+                .bind(1)
+                .bind("x")
+                .bind(value)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT c FROM t")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![("new".to_string(),)]);
+    }
+
+    #[test]
+    fn column_type_round_trip() {
+        let types = [
+            ColumnType::Bigint,
+            ColumnType::Binary,
+            ColumnType::Bit,
+            ColumnType::Blob,
+            ColumnType::Char,
+            ColumnType::Date,
+            ColumnType::DateTime,
+            ColumnType::Decimal,
+            ColumnType::Double,
+            ColumnType::Enum,
+            ColumnType::Float,
+            ColumnType::Geometry,
+            ColumnType::GeometryCollection,
+            ColumnType::Int,
+            ColumnType::Integer,
+            ColumnType::Json,
+            ColumnType::LineString,
+            ColumnType::LongBlob,
+            ColumnType::LongText,
+            ColumnType::MediumBlob,
+            ColumnType::MediumInt,
+            ColumnType::MediumText,
+            ColumnType::MultilineString,
+            ColumnType::MultiPoint,
+            ColumnType::Numeric,
+            ColumnType::Point,
+            ColumnType::Polygon,
+            ColumnType::Real,
+            ColumnType::Set,
+            ColumnType::SmallInt,
+            ColumnType::Text,
+            ColumnType::Time,
+            ColumnType::Timestamp,
+            ColumnType::TinyBlob,
+            ColumnType::TinyInt,
+            ColumnType::TinyText,
+            ColumnType::Varbinary,
+            ColumnType::VarChar,
+            ColumnType::Year,
+        ];
+        for ty in types {
+            let name = ty.to_string();
+            assert_eq!(name, name.to_uppercase());
+            assert_eq!(ColumnType::from(name), ty);
+        }
+        assert_eq!(ColumnType::VarChar.to_string(), "VARCHAR");
+        assert_eq!(ColumnType::DateTime.to_string(), "DATETIME");
+        assert_eq!(ColumnType::LongText.to_string(), "LONGTEXT");
+        // 未识别的类型保留原始名称
+        let unknown = ColumnType::Unknown("citext".to_string());
+        assert_eq!(ColumnType::from(unknown.to_string()), unknown);
+    }
+
+    #[tokio::test]
+    async fn socket_overrides_url_host() {
+        // 套接字不存在时连接失败，说明没有使用 url 中的 TCP 地址
+        let opts = ConnectOptions {
+            socket: Some(PathBuf::from("/nonexistent/socket/dir")),
+            acquire_timeout: Some(Duration::from_secs(2)),
+            ..Default::default()
+        };
+        for url in [
+            "postgres://postgres@localhost/test",
+            "mysql://root@localhost/test",
+        ] {
+            let err = database_metadata_with(url, &opts).await.err().unwrap();
+            assert!(
+                matches!(err, Error::Sql(sqlx::Error::Io(_))),
+                "{url}: {err}"
+            );
+        }
+    }
+
+    /// 通过 Unix 域套接字连接 TEST_POSTGRES_URL 指定的数据库，套接字目录由 TEST_POSTGRES_SOCKET
+    /// 指定，默认为 /var/run/postgresql，目录不存在时跳过
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn postgres_socket() {
+        let url = std::env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL");
+        let socket = std::env::var("TEST_POSTGRES_SOCKET")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/var/run/postgresql"));
+        if !socket.exists() {
+            return;
+        }
+        let opts = ConnectOptions {
+            socket: Some(socket),
+            ..Default::default()
+        };
+        let meta = database_metadata_with(&url, &opts).await.unwrap();
+        meta.ping().await.unwrap();
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn postgres_statement_timeout() {
+        let url = std::env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL");
+        let opts = ConnectOptions {
+            statement_timeout: Some(Duration::from_millis(1500)),
+            ..Default::default()
+        };
+        let pool = postgres_pool(&url, &opts).await.unwrap();
+        let (timeout,): (String,) = sqlx::query_as("SHOW statement_timeout")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(timeout, "1500ms");
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn mysql_statement_timeout() {
+        let url = std::env::var("TEST_MYSQL_URL").expect("TEST_MYSQL_URL");
+        let opts = ConnectOptions {
+            statement_timeout: Some(Duration::from_millis(1500)),
+            ..Default::default()
+        };
+        let pool = mysql_pool(&url, &opts).await.unwrap();
+        let (timeout,): (u64,) = sqlx::query_as("SELECT @@SESSION.max_execution_time")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(timeout, 1500);
+    }
+
+    #[tokio::test]
+    async fn malformed_url() {
+        for url in [
+            "",
+            "not a url",
+            "oracle://localhost/test",
+            "postgres://:bad:port/db",
+        ] {
+            assert!(database_metadata(url).await.is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn driver_from_url() {
+        for (url, driver) in [
+            ("mysql://root@localhost/test", Some(Driver::Mysql)),
+            ("mariadb://root@localhost/test", Some(Driver::Mysql)),
+            ("postgres://postgres@localhost/test", Some(Driver::Postgres)),
+            (
+                "postgresql://postgres@localhost/test",
+                Some(Driver::Postgres),
+            ),
+            (
+                "PostgreSQL://postgres@localhost/test",
+                Some(Driver::Postgres),
+            ),
+            ("MariaDB://root@localhost/test", Some(Driver::Mysql)),
+            (
+                "  postgresql://postgres@localhost/test",
+                Some(Driver::Postgres),
+            ),
+            ("\tsqlite://test.db", Some(Driver::Sqlite)),
+            ("sqlite::memory:", Some(Driver::Sqlite)),
+            ("oracle://localhost/test", None),
+            ("", None),
+        ] {
+            assert_eq!(Driver::try_from(url).ok(), driver, "{url}");
+        }
+    }
+
+    /// 在临时目录创建 Sqlite 数据库文件并执行 sql，返回 (文件路径, 连接地址)
+    async fn sqlite_file(name: &str, sql: &str) -> (PathBuf, String) {
+        let path = std::env::temp_dir().join(format!("database_{name}_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        let pool = SqlitePool::connect(&url).await.unwrap();
+        sqlx::raw_sql(sql).execute(&pool).await.unwrap();
+        pool.close().await;
+        (path, url)
+    }
+
+    #[tokio::test]
+    async fn columns_of_returns_table_subset() {
+        let (path, url) = sqlite_file(
+            "columns_of",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+            CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER, total REAL);",
+        )
+        .await;
+
+        let result = fetch_tables_with_columns(&url, "", &[]).await.unwrap();
+        let names = |table: &str| {
+            result
+                .columns_of(table)
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(result.tables.len(), 2);
+        assert_eq!(names("users"), ["id", "name"]);
+        assert_eq!(names("orders"), ["id", "user_id", "total"]);
+        assert!(names("missing").is_empty());
+        assert_eq!(result.columns().len(), 5);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// 记录同时进行的列查询数量的元数据来源，每张表有一个与表同名的列
+    #[derive(Default)]
+    struct MockMetadata {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+    }
+
+    /// 只实现必需的方法，其他方法使用默认实现
+    impl DatabaseMetadata for MockMetadata {
+        fn databases(&self) -> BoxFuture<'_, Result<Vec<Database>>> {
+            Box::pin(async move { Err(Error::E("not supported")) })
+        }
+
+        fn schemas(&self) -> BoxFuture<'_, Result<Vec<Schema>>> {
+            Box::pin(async move { Err(Error::E("not supported")) })
+        }
+
+        fn tables<'a>(
+            &'a self,
+            _database: &'a str,
+            _schema: &'a str,
+        ) -> BoxFuture<'a, Result<Vec<Table>>> {
+            Box::pin(async move { Err(Error::E("not supported")) })
+        }
+
+        fn columns<'a>(
+            &'a self,
+            _database: &'a str,
+            _schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Result<Vec<Column>>> {
+            use std::sync::atomic::Ordering;
+            Box::pin(async move {
+                let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(vec![Column {
+                    table_name: table_name.to_string(),
+                    name: table_name.to_string(),
+                    ..Default::default()
+                }])
+            })
+        }
+
+        fn indexs<'a>(
+            &'a self,
+            _database: &'a str,
+            _schema: &'a str,
+            _table_name: &'a str,
+        ) -> BoxFuture<'a, Result<Vec<Index>>> {
+            Box::pin(async move { Ok(vec![]) })
+        }
+
+        fn create_table_sql<'a>(
+            &'a self,
+            _database: &'a str,
+            _schema: &'a str,
+            _table_name: &'a str,
+        ) -> BoxFuture<'a, Result<String>> {
+            Box::pin(async move { Err(Error::E("not supported")) })
+        }
+    }
+
+    #[tokio::test]
+    async fn optional_methods_not_supported() {
+        let meta = MockMetadata::default();
+        assert!(matches!(meta.ping().await, Err(Error::E("not supported"))));
+        assert!(matches!(
+            meta.current_schema().await,
+            Err(Error::E("not supported"))
+        ));
+        assert!(matches!(
+            meta.foreign_keys("", "", "t").await,
+            Err(Error::E("not supported"))
+        ));
+        assert!(matches!(
+            meta.check_constraints("", "", "t").await,
+            Err(Error::E("not supported"))
+        ));
+        assert!(matches!(
+            meta.routines("", "").await,
+            Err(Error::E("not supported"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn all_columns_bounded_concurrency() {
+        let meta = MockMetadata::default();
+        let names = (0..20).map(|i| format!("t{i}")).collect::<Vec<_>>();
+        let table_names = names.iter().map(String::as_str).collect::<Vec<_>>();
+        let columns = meta.all_columns("", "", &table_names).await.unwrap();
+        // 结果按表的顺序返回
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| c.table_name.as_str())
+                .collect::<Vec<_>>(),
+            table_names
+        );
+        let max = meta.max_in_flight.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(max > 1 && max <= FETCH_COLUMNS_CONCURRENCY, "{max}");
+    }
+
+    #[tokio::test]
+    async fn short_acquire_timeout_fails_fast() {
+        let opts = ConnectOptions {
+            acquire_timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        // 不可路由的地址，连接会一直等待直到超时
+        for url in [
+            "postgres://postgres@10.255.255.1:5432/test",
+            "mysql://root@10.255.255.1:3306/test",
+        ] {
+            let start = std::time::Instant::now();
+            assert!(database_metadata_with(url, &opts).await.is_err(), "{url}");
+            assert!(start.elapsed() < Duration::from_secs(5), "{url}");
+        }
+    }
+
+    #[test]
+    fn datetime_backend_types() {
+        for (ty, time, chrono) in [
+            ("date", "time::Date", "chrono::NaiveDate"),
+            ("time", "time::Time", "chrono::NaiveTime"),
+            (
+                "timestamp",
+                "time::PrimitiveDateTime",
+                "chrono::NaiveDateTime",
+            ),
+            (
+                "timestamptz",
+                "time::OffsetDateTime",
+                "chrono::DateTime<chrono::Utc>",
+            ),
+            ("int4", "i32", "i32"),
+        ] {
+            let rust_type = postgres::rust_type(ty);
+            assert_eq!(DateTimeBackend::Time.rust_type(&rust_type), time, "{ty}");
+            assert_eq!(
+                DateTimeBackend::Chrono.rust_type(&rust_type),
+                chrono,
+                "{ty}"
+            );
+        }
+        assert_eq!(DateTimeBackend::default(), DateTimeBackend::Time);
+    }
+
+    #[test]
+    fn collate_clause_skips_table_default() {
+        let table = |collation: Option<&str>| Table {
+            schema: String::new(),
+            name: "t".to_string(),
+            comment: String::new(),
+            collation: collation.map(String::from),
+            kind: TableKind::BaseTable,
+        };
+        let default = table(Some("utf8mb4_general_ci"));
+        let collated = |charset: Option<&str>, collation: Option<&str>| Column {
+            charset: charset.map(String::from),
+            collation: collation.map(String::from),
+            ..Default::default()
+        };
+        assert_eq!(
+            collated(Some("utf8mb4"), Some("utf8mb4_general_ci")).collate_clause(&default),
+            None
+        );
+        assert_eq!(collated(None, None).collate_clause(&default), None);
+        assert_eq!(
+            collated(Some("utf8mb4"), Some("utf8mb4_bin"))
+                .collate_clause(&default)
+                .as_deref(),
+            Some("CHARACTER SET utf8mb4 COLLATE utf8mb4_bin")
+        );
+        assert_eq!(
+            collated(None, Some("utf8mb4_bin"))
+                .collate_clause(&table(None))
+                .as_deref(),
+            Some("COLLATE utf8mb4_bin")
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn postgres_min_connections() {
+        let url = std::env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL");
+        let opts = ConnectOptions {
+            min_connections: Some(3),
+            ..Default::default()
+        };
+        let pool = postgres_pool(&url, &opts).await.unwrap();
+        // 最小连接由后台任务建立
+        for _ in 0..50 {
+            if pool.num_idle() >= 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(pool.num_idle() >= 3, "{}", pool.num_idle());
+    }
+
+    #[tokio::test]
+    async fn export_json_snapshot() {
+        let (path, url) = sqlite_file(
+            "export_json",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+            CREATE INDEX idx_name ON users (name);",
+        )
+        .await;
+        let json = export_schema_json(&url, "").await.unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let table = &value["tables"][0];
+        assert_eq!(table["name"], "users");
+        let columns = table["columns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, ["id", "name"]);
+        assert_eq!(table["columns"][0]["isPrimaryKey"], true);
+        assert_eq!(table["indexes"][0]["keyName"], "idx_name");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn driver_from_name() {
+        for (name, driver) in [
+            ("mysql", Some(Driver::Mysql)),
+            ("MariaDB", Some(Driver::Mysql)),
+            ("postgres", Some(Driver::Postgres)),
+            (" PostgreSQL ", Some(Driver::Postgres)),
+            ("SQLite", Some(Driver::Sqlite)),
+            ("mysql://root@localhost/test", None),
+            ("oracle", None),
+            ("", None),
+        ] {
+            assert_eq!(name.parse::<Driver>().ok(), driver, "{name}");
+        }
+        // URL 仍通过 TryFrom 按前缀解析
+        assert_eq!(
+            Driver::try_from("mysql://root@localhost/test").ok(),
+            Some(Driver::Mysql)
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_table_column_owned_matches_borrowed() {
+        let (path, url) = sqlite_file(
+            "fetch_owned",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+            CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER);",
+        )
+        .await;
+        let names = vec!["orders".to_string()];
+        let (tables, columns) = fetch_table_column(&url, "", &["orders"]).await.unwrap();
+        let (owned_tables, owned_columns) =
+            fetch_table_column_owned(&url, "", &names).await.unwrap();
+        assert_eq!(format!("{owned_tables:?}"), format!("{tables:?}"));
+        assert_eq!(format!("{owned_columns:?}"), format!("{columns:?}"));
+        assert!(columns.iter().all(|c| c.table_name == "orders"));
+        assert_eq!(columns.len(), 2);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn retry_until_connected() {
+        let mut calls = 0;
+        let result = retry(5, Duration::from_millis(1), || {
+            calls += 1;
+            let attempt = calls;
+            async move {
+                if attempt < 3 {
+                    Err(Error::Io(std::io::ErrorKind::ConnectionRefused.into()))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls, 3);
+
+        // 重试次数用尽时返回最后一次的错误
+        let mut calls = 0;
+        let result = retry(2, Duration::from_millis(1), || {
+            calls += 1;
+            async { Err::<(), _>(Error::Io(std::io::ErrorKind::ConnectionRefused.into())) }
+        })
+        .await;
+        assert!(matches!(result, Err(Error::Io(_))));
+        assert_eq!(calls, 2);
+
+        // 非连接错误不重试
+        let mut calls = 0;
+        let result = retry(5, Duration::from_millis(1), || {
+            calls += 1;
+            async { Err::<(), _>(Error::E("driver not support")) }
+        })
+        .await;
+        assert!(matches!(result, Err(Error::E(_))));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn escape_rust_keywords() {
+        assert_eq!(escape_keyword("gen"), "r#gen");
+        assert_eq!(escape_keyword("async"), "r#async");
+        assert_eq!(escape_keyword("type"), "r#type");
+        assert_eq!(escape_keyword("name"), "name");
+        assert!(is_rust_keyword("gen"));
+        assert!(!is_rust_keyword("Type"));
+    }
+
+    #[test]
+    fn invalid_identifier_field_names() {
+        assert_eq!(field_name("2fa_enabled"), "_2fa_enabled");
+        assert_eq!(field_name("order-id"), "order_id");
+        assert_eq!(field_name("full name"), "full_name");
+        assert_eq!(field_name("type"), "r#type");
+        assert!(needs_rename("2fa_enabled"));
+        assert!(needs_rename("order-id"));
+        assert!(!needs_rename("user_id"));
+        assert!(!needs_rename("type"));
+    }
+
+    #[test]
+    fn decimal_backend_types() {
+        let ty = "bigdecimal::BigDecimal";
+        assert_eq!(DecimalBackend::BigDecimal.rust_type(ty, Some(12)), ty);
+        assert_eq!(
+            DecimalBackend::RustDecimal.rust_type(ty, Some(12)),
+            "rust_decimal::Decimal"
+        );
+        assert_eq!(
+            DecimalBackend::RustDecimal.rust_type(ty, None),
+            "rust_decimal::Decimal"
+        );
+        // 超出 rust_decimal 的精度时保留 bigdecimal
+        assert_eq!(DecimalBackend::RustDecimal.rust_type(ty, Some(40)), ty);
+        assert_eq!(DecimalBackend::RustDecimal.rust_type("f64", None), "f64");
+    }
+
+    #[test]
+    fn ssl_options_override_url() {
+        let opts = ConnectOptions {
+            ssl_mode: Some(SslMode::VerifyFull),
+            ssl_root_cert: Some(PathBuf::from("/etc/ssl/ca.pem")),
+            ..Default::default()
+        };
+        let pg =
+            postgres_connect_options("postgres://postgres@localhost/test?sslmode=disable", &opts)
+                .unwrap();
+        assert!(matches!(pg.get_ssl_mode(), PgSslMode::VerifyFull));
+        assert!(format!("{pg:?}").contains("/etc/ssl/ca.pem"));
+        let mysql =
+            mysql_connect_options("mysql://root@localhost/test?ssl-mode=disabled", &opts).unwrap();
+        assert!(matches!(mysql.get_ssl_mode(), MySqlSslMode::VerifyIdentity));
+        assert!(format!("{mysql:?}").contains("/etc/ssl/ca.pem"));
+
+        // 未设置时保留 url 中的值
+        let opts = ConnectOptions::default();
+        let pg =
+            postgres_connect_options("postgres://postgres@localhost/test?sslmode=require", &opts)
+                .unwrap();
+        assert!(matches!(pg.get_ssl_mode(), PgSslMode::Require));
+        let mysql =
+            mysql_connect_options("mysql://root@localhost/test?ssl-mode=disabled", &opts).unwrap();
+        assert!(matches!(mysql.get_ssl_mode(), MySqlSslMode::Disabled));
+    }
+
+    #[tokio::test]
+    async fn table_detail_bundle() {
+        let (path, url) = sqlite_file(
+            "table_detail",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+            CREATE TABLE orders (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER REFERENCES users (id) ON DELETE CASCADE,
+                total REAL
+            );
+            CREATE INDEX orders_user_id ON orders (user_id);",
+        )
+        .await;
+        let meta = database_metadata(&url).await.unwrap();
+        let detail = meta.table_detail("", "", "orders").await.unwrap();
+        assert_eq!(detail.table.name, "orders");
+        assert_eq!(
+            detail
+                .columns
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            ["id", "user_id", "total"]
+        );
+        assert_eq!(detail.indexes.len(), 1);
+        assert_eq!(detail.indexes[0].key_name, "orders_user_id");
+        assert_eq!(detail.foreign_keys.len(), 1);
+        assert_eq!(detail.foreign_keys[0].columns, ["user_id"]);
+        assert_eq!(detail.foreign_keys[0].referenced_table, "users");
+        assert_eq!(detail.foreign_keys[0].referenced_columns, ["id"]);
+        assert_eq!(detail.foreign_keys[0].on_delete.as_deref(), Some("CASCADE"));
+        assert!(matches!(
+            meta.table_detail("", "", "missing").await,
+            Err(Error::E(_))
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn classify_default_values() {
+        use DefaultValue::{Expression, Literal};
+        for (default, driver, expected) in [
+            (
+                "CURRENT_TIMESTAMP",
+                Driver::Mysql,
+                Expression("CURRENT_TIMESTAMP".to_string()),
+            ),
+            (
+                "CURRENT_TIMESTAMP",
+                Driver::Sqlite,
+                Expression("CURRENT_TIMESTAMP".to_string()),
+            ),
+            (
+                "nextval('users_id_seq'::regclass)",
+                Driver::Postgres,
+                Expression("nextval('users_id_seq'::regclass)".to_string()),
+            ),
+            (
+                "'active'::character varying",
+                Driver::Postgres,
+                Literal("active".to_string()),
+            ),
+            ("'active'", Driver::Sqlite, Literal("active".to_string())),
+            // Mysql 的 COLUMN_DEFAULT 中字符串不带引号
+            ("active", Driver::Mysql, Literal("active".to_string())),
+            ("0", Driver::Postgres, Literal("0".to_string())),
+            ("NULL::integer", Driver::Postgres, DefaultValue::None),
+            ("", Driver::Mysql, DefaultValue::None),
+        ] {
+            assert_eq!(
+                DefaultValue::classify(Some(default), driver),
+                expected,
+                "{default}"
+            );
+        }
+        assert_eq!(
+            DefaultValue::classify(None, Driver::Mysql),
+            DefaultValue::None
+        );
+    }
+
+    #[test]
+    fn unknown_column_types() {
+        for ty in [
+            "citext",
+            "tsvector",
+            "int4[]",
+            " character varying ",
+            "geometry(Point,4326)",
+        ] {
+            assert_eq!(
+                ColumnType::from(ty.to_string()),
+                ColumnType::Unknown(ty.trim().to_string())
+            );
+        }
+        // Postgres 的列同样不会因未识别的类型而失败
+        assert_eq!(postgres::rust_type("citext"), "String");
+        assert_eq!(postgres::rust_type("int4[]"), "Vec<i32>");
+    }
+}
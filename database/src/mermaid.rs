@@ -0,0 +1,163 @@
+//! Mermaid ER 图导出
+//!
+//! 将结构快照渲染为 Mermaid 的 `erDiagram`，每个表为一个实体，外键渲染为实体间的关系
+
+use super::{Column, ForeignKey, SchemaSnapshot, TableDetail};
+
+/// 将结构快照渲染为 Mermaid ER 图
+pub fn to_mermaid_er(snapshot: &SchemaSnapshot) -> String {
+    let mut er = String::from("erDiagram\n");
+    for table in snapshot.tables.iter() {
+        // 没有列的表只声明实体，空的属性块 Mermaid 无法解析
+        if table.columns.is_empty() {
+            er.push_str(&format!("    {}\n", entity(&table.table.name)));
+            continue;
+        }
+        er.push_str(&format!("    {} {{\n", entity(&table.table.name)));
+        for column in table.columns.iter() {
+            er.push_str(&format!(
+                "        {} {}{}{}\n",
+                column_type(column),
+                attribute(&column.name),
+                keys(table, column),
+                comment(&column.comment)
+            ));
+        }
+        er.push_str("    }\n");
+    }
+    for table in snapshot.tables.iter() {
+        for fk in table.foreign_keys.iter() {
+            er.push_str(&relationship(table, fk));
+        }
+    }
+    er
+}
+
+/// 列类型，带小数位的类型只保留类型名，属性类型中不能出现逗号
+fn column_type(column: &Column) -> String {
+    let ty = match column.scale {
+        Some(_) => column
+            .r#type
+            .as_ref()
+            .map(|t| t.to_string())
+            .unwrap_or_default(),
+        None => column.full_type(),
+    };
+    attribute(&ty)
+}
+
+/// 键标记，如 ` PK`、` PK, FK`
+fn keys(table: &TableDetail, column: &Column) -> String {
+    let mut keys = vec![];
+    if column.is_primary_key {
+        keys.push("PK");
+    }
+    if table
+        .foreign_keys
+        .iter()
+        .any(|fk| fk.columns.contains(&column.name))
+    {
+        keys.push("FK");
+    }
+    if keys.is_empty() {
+        return String::new();
+    }
+    format!(" {}", keys.join(", "))
+}
+
+fn comment(comment: &str) -> String {
+    if comment.is_empty() {
+        return String::new();
+    }
+    format!(" \"{}\"", comment.replace('"', "'").replace('\n', " "))
+}
+
+/// 外键关系，如 `orders }o--|| customer : "fk_order_customer"`
+///
+/// 外键列唯一时为一对一，否则为多对一；外键列可为空时被引用的一方为零或一
+fn relationship(table: &TableDetail, fk: &ForeignKey) -> String {
+    let columns = table
+        .columns
+        .iter()
+        .filter(|c| fk.columns.contains(&c.name))
+        .collect::<Vec<_>>();
+    let pk = table
+        .columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .map(|c| &c.name)
+        .collect::<Vec<_>>();
+    let is_unique = match columns.as_slice() {
+        [column] => column.is_unique || (column.is_primary_key && pk.len() == 1),
+        columns => pk.len() == columns.len() && columns.iter().all(|c| c.is_primary_key),
+    };
+    let is_null = columns.iter().any(|c| c.is_null);
+    format!(
+        "    {} {}--{} {} : \"{}\"\n",
+        entity(&fk.table_name),
+        if is_unique { "|o" } else { "}o" },
+        if is_null { "o|" } else { "||" },
+        entity(&fk.referenced_table),
+        fk.name.replace('"', "'")
+    )
+}
+
+/// 实体名只能包含字母、数字、下划线及连字符，其他字符替换为下划线
+fn entity(name: &str) -> String {
+    sanitize(name, &['_', '-'])
+}
+
+/// 属性名及类型还可以包含括号，如 `VARCHAR(255)`、`int4[]`
+fn attribute(name: &str) -> String {
+    sanitize(name, &['_', '-', '(', ')', '[', ']'])
+}
+
+fn sanitize(name: &str, allowed: &[char]) -> String {
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || allowed.contains(&c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    // 需以字母或下划线开头
+    if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name
+    } else {
+        format!("_{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Driver, DumpMetadata, schema_snapshot};
+
+    #[tokio::test]
+    async fn customer_order_golden() {
+        let meta = DumpMetadata::new(
+            "CREATE TABLE customer (
+                id bigserial PRIMARY KEY,
+                name varchar(50) NOT NULL
+            );
+            CREATE TABLE orders (
+                id bigserial PRIMARY KEY,
+                customer_id bigint NOT NULL REFERENCES customer (id),
+                parent_id bigint REFERENCES orders (id),
+                amount numeric(10, 2) NOT NULL
+            );
+            COMMENT ON COLUMN orders.amount IS '订单金额';
+            CREATE TABLE audit ();",
+            Driver::Postgres,
+        )
+        .unwrap();
+        let snapshot = schema_snapshot(&meta, "").await.unwrap();
+        assert_eq!(
+            to_mermaid_er(&snapshot),
+            include_str!("../testdata/customer_order.mmd")
+        );
+    }
+}
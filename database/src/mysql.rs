@@ -1,17 +1,35 @@
 use serde::{Deserialize, Serialize};
-use sqlx::{AnyPool, FromRow, MySqlPool, Row, any::AnyRow, mysql::MySqlRow};
+use sqlx::{
+    FromRow, MySqlPool, Row,
+    mysql::{MySqlDatabaseError, MySqlRow},
+};
 
 use super::{ColumnType, DatabaseMetadata, Result};
 
-const SHOW_DATABASES: &str = "SHOW DATABASES";
+/// 与 SHOW DATABASES 相同的数据库列表，?1 为 false 时排除系统数据库
+const SELECT_SCHEMATA: &str = "SELECT SCHEMA_NAME FROM information_schema.SCHEMATA WHERE ? OR SCHEMA_NAME NOT IN ('information_schema', 'mysql', 'performance_schema', 'sys') ORDER BY SCHEMA_NAME";
 const SHOW_COLUMNS: &str = "SHOW FULL COLUMNS FROM ? FROM ?";
-const SHOW_TABLES: &str = "SELECT table_schema, table_name, CAST(TABLE_TYPE AS CHAR) TABLE_TYPE, table_comment FROM information_schema.`TABLES` WHERE TABLE_SCHEMA = ?";
+/// 前 9 列与 SHOW FULL COLUMNS 的列顺序一致，之后是表名、列的位置和字符集
+const SELECT_COLUMNS: &str = "SELECT COLUMN_NAME, COLUMN_TYPE, COLLATION_NAME, IS_NULLABLE, COLUMN_KEY, COLUMN_DEFAULT, EXTRA, `PRIVILEGES`, COLUMN_COMMENT, TABLE_NAME, CAST(ORDINAL_POSITION AS UNSIGNED), CHARACTER_SET_NAME FROM information_schema.`COLUMNS` WHERE TABLE_SCHEMA = COALESCE(NULLIF(?, ''), DATABASE())";
+const SHOW_TABLES: &str = "SELECT table_schema, table_name, CAST(TABLE_TYPE AS CHAR) TABLE_TYPE, table_comment, table_collation FROM information_schema.`TABLES` WHERE TABLE_SCHEMA = ?";
+/// 存储过程和函数及其参数，ORDINAL_POSITION 为 0 的参数是函数的返回值
+const SELECT_ROUTINES: &str = "SELECT r.ROUTINE_SCHEMA AS routine_schema, r.ROUTINE_NAME AS routine_name, r.ROUTINE_TYPE AS routine_type, r.DTD_IDENTIFIER AS return_type, p.PARAMETER_NAME AS parameter_name, p.PARAMETER_MODE AS parameter_mode, p.DTD_IDENTIFIER AS parameter_type FROM information_schema.ROUTINES r LEFT JOIN information_schema.PARAMETERS p ON p.SPECIFIC_SCHEMA = r.ROUTINE_SCHEMA AND p.SPECIFIC_NAME = r.SPECIFIC_NAME AND p.ORDINAL_POSITION > 0 WHERE r.ROUTINE_SCHEMA = COALESCE(NULLIF(?, ''), DATABASE()) ORDER BY r.ROUTINE_NAME, r.ROUTINE_TYPE, p.ORDINAL_POSITION";
+/// 表的外键，组合外键每列一行，删除、更新动作来自 REFERENTIAL_CONSTRAINTS
+const SELECT_FOREIGN_KEYS: &str = "SELECT k.TABLE_NAME AS table_name, k.CONSTRAINT_NAME AS name, k.COLUMN_NAME AS column_name, k.REFERENCED_TABLE_NAME AS referenced_table, k.REFERENCED_COLUMN_NAME AS referenced_column, rc.DELETE_RULE AS on_delete, rc.UPDATE_RULE AS on_update FROM information_schema.KEY_COLUMN_USAGE k JOIN information_schema.REFERENTIAL_CONSTRAINTS rc ON rc.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA AND rc.TABLE_NAME = k.TABLE_NAME AND rc.CONSTRAINT_NAME = k.CONSTRAINT_NAME WHERE k.TABLE_SCHEMA = COALESCE(NULLIF(?, ''), DATABASE()) AND k.TABLE_NAME = ? AND k.REFERENCED_TABLE_NAME IS NOT NULL ORDER BY k.CONSTRAINT_NAME, k.ORDINAL_POSITION";
+/// 表的检查约束，需要 Mysql 8.0.16 及以上版本
+const SELECT_CHECK_CONSTRAINTS: &str = "SELECT tc.TABLE_NAME AS table_name, tc.CONSTRAINT_NAME AS name, CAST(cc.CHECK_CLAUSE AS CHAR) AS expression FROM information_schema.TABLE_CONSTRAINTS tc JOIN information_schema.CHECK_CONSTRAINTS cc ON cc.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA AND cc.CONSTRAINT_NAME = tc.CONSTRAINT_NAME WHERE tc.CONSTRAINT_TYPE = 'CHECK' AND tc.TABLE_SCHEMA = COALESCE(NULLIF(?, ''), DATABASE()) AND tc.TABLE_NAME = ? ORDER BY tc.CONSTRAINT_NAME";
 const SHOW_INDEX: &str = "SHOW INDEX FROM ? FROM ?";
 const SHOW_CREATE_TABLE: &str = "SHOW CREATE TABLE ?";
 const WORD_UNSIGNED: &str = "unsigned";
+const WORD_ZEROFILL: &str = "zerofill";
 const WORD_PRIMARY: &str = "PRIMARY";
 
-pub struct MysqlMetadata(MySqlPool);
+/// Mysql 中模式与数据库是同一概念，`databases` 与 `schemas` 返回相同的数据库列表
+pub struct MysqlMetadata {
+    pool: MySqlPool,
+    /// 是否包含 information_schema、mysql、performance_schema、sys 系统数据库
+    include_system_schemas: bool,
+}
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -47,6 +65,9 @@ struct Table {
     table_type: String,
     #[sqlx(rename = "TABLE_COMMENT")]
     table_comment: String,
+    /// 视图没有排序规则
+    #[sqlx(rename = "TABLE_COLLATION")]
+    table_collation: Option<String>,
 }
 
 impl From<Table> for super::Table {
@@ -55,6 +76,13 @@ impl From<Table> for super::Table {
             schema: t.table_schema,
             name: t.table_name,
             comment: t.table_comment,
+            collation: t.table_collation,
+            // TABLE_TYPE 为 BASE TABLE、VIEW 或 SYSTEM VIEW
+            kind: if t.table_type.ends_with("VIEW") {
+                super::TableKind::View
+            } else {
+                super::TableKind::BaseTable
+            },
         }
     }
 }
@@ -68,6 +96,8 @@ struct Column {
     table_name: String,
     /// 字段名
     name: String,
+    /// 列在表中的位置，从 1 开始
+    ordinal_position: i32,
     /// 字段类型
     r#type: Option<ColumnType>,
     /// 字段长度，可以为空
@@ -76,6 +106,8 @@ struct Column {
     scale: Option<i32>,
     /// 默认值
     default: Option<String>,
+    /// 默认值的分类
+    default_value: super::DefaultValue,
     /// 枚举值列表
     enum_values: Option<Vec<String>>,
     /// 备注
@@ -90,6 +122,12 @@ struct Column {
     is_primary_key: bool,
     /// 是否无符号
     is_unsigned: bool,
+    /// 是否为生成列
+    is_generated: bool,
+    /// 字符集
+    charset: Option<String>,
+    /// 排序规则
+    collation: Option<String>,
 }
 
 impl Column {
@@ -98,25 +136,26 @@ impl Column {
             return Ok(());
         }
 
-        self.is_unsigned = r#type.contains(WORD_UNSIGNED);
-        let column_type = r#type.replace(WORD_UNSIGNED, "");
+        // UNSIGNED、ZEROFILL 位于类型及括号之后，如 `int(10) unsigned zerofill`，ZEROFILL 隐含 UNSIGNED
+        let (column_type, attrs) = match r#type.rfind(')') {
+            Some(end) => r#type.split_at(end + 1),
+            None => r#type.split_once(' ').unwrap_or((r#type, "")),
+        };
+        let attrs = attrs.to_lowercase();
+        self.is_unsigned = attrs.contains(WORD_UNSIGNED) || attrs.contains(WORD_ZEROFILL);
+        let column_type = column_type.trim().to_string();
         let mut meta_type = String::new();
         let mut meta_length = String::new();
         let mut scale = String::new();
         let mut enum_values = vec![];
 
-        if let Some(pos) = column_type.find('(') {
-            meta_type = column_type[0..pos].to_string();
-            meta_length = column_type[pos + 1..column_type.len() - 1].to_string();
-
-            if meta_length.contains("enum") {
-                // enum('1','2','3')
-                enum_values = meta_length
-                    .replace("enum", "")
-                    .replace(['(', ')'], "")
-                    .split(",")
-                    .map(|v| v.replace("'", ""))
-                    .collect::<Vec<_>>();
+        if let (Some(start), Some(end)) = (column_type.find('('), column_type.rfind(')')) {
+            meta_type = column_type[..start].to_string();
+            meta_length = column_type[start + 1..end].to_string();
+
+            if meta_type.eq_ignore_ascii_case("enum") || meta_type.eq_ignore_ascii_case("set") {
+                // enum('1','2','3')、set('a','b')
+                enum_values = parse_enum_values(&meta_length);
             } else {
                 // double(11,8)
                 let ml = meta_length.clone();
@@ -154,12 +193,59 @@ impl Column {
         Ok(())
     }
 
+    /// 从 SELECT_COLUMNS 的结果行构建列信息
+    fn from_row(row: &MySqlRow, schema: &str) -> Self {
+        let field = row.get(0);
+        let r#type: Vec<u8> = row.get(1);
+        let r#type = String::from_utf8_lossy(&r#type).to_string();
+        let null = row.get(3);
+        let key: String = row.get(4);
+        let default: Option<Vec<u8>> = row.get(5);
+        let default = default.map(|d| String::from_utf8_lossy(&d).to_string());
+        let extra: &str = row.get(6);
+        // 8.0 的表达式默认值（包括 CURRENT_TIMESTAMP）在 EXTRA 中标记为 DEFAULT_GENERATED
+        let default_value = if extra.to_lowercase().contains("default_generated") {
+            default
+                .clone()
+                .map_or(super::DefaultValue::None, super::DefaultValue::Expression)
+        } else {
+            super::DefaultValue::classify(default.as_deref(), super::Driver::Mysql)
+        };
+        let comment: Vec<u8> = row.get(8);
+        let comment = String::from_utf8_lossy(&comment).to_string();
+        let table_name: String = row.get(9);
+        let ordinal_position: u64 = row.get(10);
+        let collation: Option<Vec<u8>> = row.get(2);
+        let collation = collation.map(|c| String::from_utf8_lossy(&c).to_string());
+        let charset: Option<Vec<u8>> = row.get(11);
+        let charset = charset.map(|c| String::from_utf8_lossy(&c).to_string());
+
+        let mut coloumn = Column {
+            schema: schema.into(),
+            table_name,
+            name: field,
+            ordinal_position: ordinal_position as i32,
+            default,
+            default_value,
+            comment,
+            charset,
+            collation,
+            ..Default::default()
+        };
+        coloumn.handle_column_as_type(&r#type).is_ok();
+        coloumn.handle_primary_key(&key);
+        coloumn.handle_is_null(null);
+        coloumn.handle_is_auto_incr(extra);
+        coloumn.handle_is_generated(extra);
+        coloumn
+    }
+
     fn handle_primary_key(&mut self, key: &str) {
         self.is_primary_key = "PRI".eq(key);
     }
 
     fn handle_is_null(&mut self, null: &str) {
-        match null {
+        match null.to_lowercase().as_str() {
             "yes" => self.is_null = true,
             "no" => self.is_null = false,
             _ => {}
@@ -169,20 +255,34 @@ impl Column {
     fn handle_is_auto_incr(&mut self, auto_incr: &str) {
         self.is_auto_incr = auto_incr.to_lowercase().contains("auto_increment");
     }
+
+    /// 生成列的 EXTRA 为 VIRTUAL GENERATED 或 STORED GENERATED，
+    /// 表达式默认值的 DEFAULT_GENERATED 不是生成列
+    fn handle_is_generated(&mut self, extra: &str) {
+        let extra = extra.to_lowercase();
+        self.is_generated =
+            extra.contains("virtual generated") || extra.contains("stored generated");
+    }
 }
 
 impl From<Column> for super::Column {
     fn from(col: Column) -> Self {
-        let rust_type = t2t(&format!("{}", col.r#type.unwrap())).into();
+        let rust_type = col
+            .r#type
+            .as_ref()
+            .map(|ty| rust_type(ty, col.length, col.is_unsigned))
+            .unwrap_or_default();
         Self {
             database: col.schema.clone(),
             schema: col.schema,
             table_name: col.table_name,
             name: col.name,
+            ordinal_position: col.ordinal_position,
             r#type: col.r#type,
             length: col.length,
             scale: col.scale,
             default: col.default,
+            default_value: col.default_value,
             enum_values: col.enum_values,
             comment: col.comment,
             is_null: col.is_null,
@@ -190,12 +290,57 @@ impl From<Column> for super::Column {
             is_unique: col.is_unique,
             is_primary_key: col.is_primary_key,
             is_unsigned: col.is_unsigned,
+            is_generated: col.is_generated,
+            owned_sequence: None,
+            charset: col.charset,
+            collation: col.collation,
 
             rust_type,
         }
     }
 }
 
+/// 8.0.16 之前没有 CHECK_CONSTRAINTS 表，查询报 1109 Unknown table，视为不支持检查约束
+fn check_constraints_error(e: sqlx::Error) -> super::Error {
+    let unknown_table = e
+        .as_database_error()
+        .and_then(|e| e.try_downcast_ref::<MySqlDatabaseError>())
+        .is_some_and(|e| e.number() == 1109);
+    if unknown_table {
+        super::Error::E("not supported")
+    } else {
+        e.into()
+    }
+}
+
+/// 解析 ENUM、SET 的取值列表，如 `'a','it''s','b,c'`
+///
+/// 值中的单引号以 `''` 或 `\'` 转义，逗号只在引号外作为分隔符
+pub(crate) fn parse_enum_values(values: &str) -> Vec<String> {
+    let mut res = vec![];
+    let mut chars = values.chars().peekable();
+    while let Some(c) = chars.next() {
+        // 跳过引号外的逗号和空白
+        if c != '\'' {
+            continue;
+        }
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => value.extend(chars.next()),
+                '\'' if chars.peek() == Some(&'\'') => {
+                    chars.next();
+                    value.push('\'');
+                }
+                '\'' => break,
+                c => value.push(c),
+            }
+        }
+        res.push(value);
+    }
+    res
+}
+
 /// Rust type             MySQL type(s)
 /// bool                    TINYINT(1), BOOLEAN
 /// i8                      TINYINT
@@ -216,16 +361,16 @@ impl From<Column> for super::Column {
 /// time::Date              DATE
 /// time::Time              TIME
 ///
-/// bigdecimal::BigDecimal  DECIMAL
+/// bigdecimal::BigDecimal  DECIMAL, NUMERIC
 ///
 /// uuid::Uuid              BYTE(16), VARCHAR, CHAR, TEXT
 /// uuid::fmt::Hyphenated   CHAR(36)
 /// uuid::fmt::Simple       CHAR(32)
 ///
-/// serde_json::JsonValue  JSON
+/// serde_json::Value       JSON
 ///
 /// Mysql 类型转换为Rust对应类型
-fn t2t(ty: &str) -> &str {
+pub(crate) fn t2t(ty: &str) -> &'static str {
     match ty.to_uppercase().as_str() {
         "TINYINT(1)" | "BOOLEAN" => "bool",
         "TINYINT" => "i8",
@@ -237,19 +382,52 @@ fn t2t(ty: &str) -> &str {
         "BIGINT" => "i64",
         "BIGINT UNSIGNED" => "u64",
         "FLOAT" => "f32",
-        "DOUBLE" | "NUMERIC" => "f64",
+        "DOUBLE" => "f64",
         "VARBINARY" | "BINARY" | "BLOB" => "Vec<u8>",
         "YEAR" => "time::Date",
         "DATE" => "time::Date",
         "TIME" => "time::Time",
         "DATETIME" => "time::PrimitiveDateTime",
-        "TIMESTAMP" => "time::offsetDateTime",
-        "DECIMAL" => "bigdecimal::BigDecimal",
-        "JSON" => "serde_json:JsonValue",
+        "TIMESTAMP" => "time::OffsetDateTime",
+        "DECIMAL" | "NUMERIC" => "bigdecimal::BigDecimal",
+        "JSON" => "serde_json::Value",
         _ => "String",
     }
 }
 
+/// 列类型转换为Rust类型
+///
+/// TINYINT(1) 视为布尔值，BIT(n) 按位数转换，无符号整数映射为对应的无符号类型，
+/// 如 `INT UNSIGNED` -> `u32`，其他类型忽略 UNSIGNED
+pub(crate) fn rust_type(ty: &ColumnType, length: Option<i32>, is_unsigned: bool) -> String {
+    let is_integer = matches!(
+        ty,
+        ColumnType::TinyInt
+            | ColumnType::SmallInt
+            | ColumnType::MediumInt
+            | ColumnType::Int
+            | ColumnType::Bigint
+    );
+    let ty = match (ty, length, is_unsigned) {
+        (ColumnType::Bit, ..) => return bit_type(length).to_string(),
+        (ColumnType::TinyInt, Some(1), false) => "TINYINT(1)".to_string(),
+        (ty, _, true) if is_integer => format!("{ty} UNSIGNED"),
+        (ty, ..) => ty.to_string(),
+    };
+    t2t(&ty).to_string()
+}
+
+/// BIT(n) 按位数转换为能容纳的最小无符号整数，BIT(1) 视为布尔值
+pub(crate) fn bit_type(length: Option<i32>) -> &'static str {
+    match length.unwrap_or(1) {
+        1 => "bool",
+        2..=8 => "u8",
+        9..=16 => "u16",
+        17..=32 => "u32",
+        _ => "u64",
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
 struct Index {
@@ -294,34 +472,152 @@ impl From<Index> for super::Index {
     }
 }
 
+#[derive(Debug, Default, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct ForeignKey {
+    /// 表名
+    table_name: String,
+    /// 外键约束名
+    name: String,
+    /// 外键列
+    column_name: String,
+    /// 引用的表名
+    referenced_table: String,
+    /// 引用的列
+    referenced_column: String,
+    /// 删除动作
+    on_delete: Option<String>,
+    /// 更新动作
+    on_update: Option<String>,
+}
+
+impl From<ForeignKey> for super::ForeignKey {
+    fn from(fk: ForeignKey) -> Self {
+        Self {
+            table_name: fk.table_name,
+            name: fk.name,
+            columns: vec![fk.column_name],
+            referenced_table: fk.referenced_table,
+            referenced_columns: vec![fk.referenced_column],
+            on_delete: fk.on_delete,
+            on_update: fk.on_update,
+        }
+    }
+}
+
+/// 检查约束
+#[derive(Debug, FromRow)]
+struct CheckConstraint {
+    table_name: String,
+    name: String,
+    expression: String,
+}
+
+impl From<CheckConstraint> for super::CheckConstraint {
+    fn from(c: CheckConstraint) -> Self {
+        Self {
+            table_name: c.table_name,
+            name: c.name,
+            expression: c.expression,
+        }
+    }
+}
+
+/// 存储过程或函数，每个参数一行，没有参数时参数列为空
+#[derive(Debug, FromRow)]
+struct Routine {
+    routine_schema: String,
+    routine_name: String,
+    /// PROCEDURE 或 FUNCTION
+    routine_type: String,
+    return_type: Option<String>,
+    parameter_name: Option<String>,
+    /// 函数的参数没有模式，均为 IN
+    parameter_mode: Option<String>,
+    parameter_type: Option<String>,
+}
+
+impl From<Routine> for super::Routine {
+    fn from(r: Routine) -> Self {
+        let parameters = r
+            .parameter_type
+            .map(|ty| super::RoutineParameter {
+                name: r.parameter_name.unwrap_or_default(),
+                mode: r.parameter_mode.unwrap_or_else(|| "IN".to_string()),
+                r#type: ty,
+            })
+            .into_iter()
+            .collect();
+        Self {
+            schema: r.routine_schema,
+            name: r.routine_name,
+            kind: if r.routine_type.eq_ignore_ascii_case("procedure") {
+                super::RoutineKind::Procedure
+            } else {
+                super::RoutineKind::Function
+            },
+            return_type: r.return_type,
+            parameters,
+        }
+    }
+}
+
 impl MysqlMetadata {
     pub fn new(pool: MySqlPool) -> Self {
-        Self(pool)
+        Self {
+            pool,
+            include_system_schemas: false,
+        }
+    }
+
+    /// 设置 `databases`、`schemas` 是否包含系统数据库，默认不包含
+    pub fn include_system_schemas(mut self, include: bool) -> Self {
+        self.include_system_schemas = include;
+        self
     }
 }
 
 impl DatabaseMetadata for MysqlMetadata {
+    fn ping(&self) -> super::BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            sqlx::query("SELECT 1").execute(&self.pool).await?;
+            Ok(())
+        })
+    }
+
     fn databases(&self) -> super::BoxFuture<'_, Result<Vec<super::Database>>> {
         Box::pin(async move {
-            let rows = sqlx::query(SHOW_DATABASES)
+            let rows = sqlx::query(SELECT_SCHEMATA)
+                .bind(self.include_system_schemas)
                 .map(|row: MySqlRow| Database { name: row.get(0) })
                 .map(|row| row.into())
-                .fetch_all(&self.0)
+                .fetch_all(&self.pool)
                 .await?;
             Ok(rows)
         })
     }
     fn schemas(&self) -> super::BoxFuture<'_, Result<Vec<super::Schema>>> {
         Box::pin(async move {
-            let rows = sqlx::query(SHOW_DATABASES)
+            let rows = sqlx::query(SELECT_SCHEMATA)
+                .bind(self.include_system_schemas)
                 .map(|row: MySqlRow| Schema { name: row.get(0) })
                 .map(|row| row.into())
-                .fetch_all(&self.0)
+                .fetch_all(&self.pool)
                 .await?;
             Ok(rows)
         })
     }
 
+    fn current_schema(&self) -> super::BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            // 未选择数据库时 DATABASE() 返回 NULL
+            let (schema,): (Option<String>,) = sqlx::query_as("SELECT DATABASE()")
+                .fetch_one(&self.pool)
+                .await?;
+            Ok(schema.unwrap_or_default())
+        })
+    }
+
     fn tables<'a>(
         &'a self,
         database: &'a str,
@@ -330,7 +626,7 @@ impl DatabaseMetadata for MysqlMetadata {
         Box::pin(async move {
             let rows: Vec<Table> = sqlx::query_as(SHOW_TABLES)
                 .bind(schema)
-                .fetch_all(&self.0)
+                .fetch_all(&self.pool)
                 .await?;
             Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
         })
@@ -344,42 +640,44 @@ impl DatabaseMetadata for MysqlMetadata {
     ) -> super::BoxFuture<'a, Result<Vec<super::Column>>> {
         Box::pin(async move {
             let rows: Vec<Column> = sqlx::query(&format!(
-                "SHOW FULL COLUMNS FROM {table_name} FROM {schema}"
+                "{SELECT_COLUMNS} AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION"
             ))
-            .bind(table_name)
             .bind(schema)
-            .map(|row: MySqlRow| {
-                let field = row.get(0);
-                let r#type: Vec<u8> = row.get(1);
-                let r#type = String::from_utf8_lossy(&r#type).to_string();
-                let null = row.get(3);
-                let key: String = row.get(4);
-                let default: Option<Vec<u8>> = row.get(5);
-                let default = default.map(|d| String::from_utf8_lossy(&d).to_string());
-                let extra = row.get(6);
-                let comment: Vec<u8> = row.get(8);
-                let comment = String::from_utf8_lossy(&comment).to_string();
-
-                let mut coloumn = Column {
-                    schema: schema.into(),
-                    table_name: table_name.into(),
-                    name: field,
-                    default,
-                    comment,
-                    ..Default::default()
-                };
-                coloumn.handle_column_as_type(&r#type).is_ok();
-                coloumn.handle_primary_key(&key);
-                coloumn.handle_is_null(null);
-                coloumn.handle_is_auto_incr(extra);
-                coloumn
-            })
-            .fetch_all(&self.0)
+            .bind(table_name)
+            .map(|row: MySqlRow| Column::from_row(&row, schema))
+            .fetch_all(&self.pool)
             .await?;
             Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
         })
     }
 
+    fn all_columns<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+        table_names: &'a [&'a str],
+    ) -> super::BoxFuture<'a, Result<Vec<super::Column>>> {
+        Box::pin(async move {
+            if table_names.is_empty() {
+                return Ok(vec![]);
+            }
+            let placeholders = vec!["?"; table_names.len()].join(", ");
+            // 按 table_names 的顺序返回，同一表内按列的位置排序
+            let sql = format!(
+                "{SELECT_COLUMNS} AND TABLE_NAME IN ({placeholders}) ORDER BY FIELD(TABLE_NAME, {placeholders}), ORDINAL_POSITION"
+            );
+            let mut query = sqlx::query(&sql).bind(schema);
+            for table_name in table_names.iter().chain(table_names) {
+                query = query.bind(table_name);
+            }
+            let rows: Vec<Column> = query
+                .map(|row: MySqlRow| Column::from_row(&row, schema))
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
+    }
+
     fn indexs<'a>(
         &'a self,
         database: &'a str,
@@ -412,12 +710,30 @@ impl DatabaseMetadata for MysqlMetadata {
                             index_comment,
                         }
                     })
-                    .fetch_all(&self.0)
+                    .fetch_all(&self.pool)
                     .await?;
             Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
         })
     }
 
+    fn foreign_keys<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::ForeignKey>>> {
+        Box::pin(async move {
+            let rows: Vec<ForeignKey> = sqlx::query_as(SELECT_FOREIGN_KEYS)
+                .bind(schema)
+                .bind(table_name)
+                .fetch_all(&self.pool)
+                .await?;
+            Ok(super::merge_foreign_keys(
+                rows.into_iter().map(|row| row.into()).collect(),
+            ))
+        })
+    }
+
     fn create_table_sql<'a>(
         &'a self,
         database: &'a str,
@@ -429,9 +745,427 @@ impl DatabaseMetadata for MysqlMetadata {
                 .bind(schema)
                 .bind(table_name)
                 .map(|row: MySqlRow| row.get(1))
-                .fetch_one(&self.0)
+                .fetch_one(&self.pool)
                 .await?;
             Ok(rows)
         })
     }
+
+    fn check_constraints<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::CheckConstraint>>> {
+        Box::pin(async move {
+            let rows: Vec<CheckConstraint> = sqlx::query_as(SELECT_CHECK_CONSTRAINTS)
+                .bind(schema)
+                .bind(table_name)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(check_constraints_error)?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
+    }
+
+    fn routines<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::Routine>>> {
+        Box::pin(async move {
+            let rows: Vec<Routine> = sqlx::query_as(SELECT_ROUTINES)
+                .bind(schema)
+                .fetch_all(&self.pool)
+                .await?;
+            // 每个参数查询出一行，Mysql 不支持重载，但存储过程和函数可以同名，按名称和类型合并
+            let mut routines: Vec<super::Routine> = vec![];
+            for row in rows {
+                let routine = super::Routine::from(row);
+                match routines.last_mut() {
+                    Some(r) if r.name == routine.name && r.kind == routine.kind => {
+                        r.parameters.extend(routine.parameters)
+                    }
+                    _ => routines.push(routine),
+                }
+            }
+            Ok(routines)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 连接 TEST_MYSQL_URL 指定的数据库
+    #[cfg(feature = "integration")]
+    async fn connect() -> MysqlMetadata {
+        let url = std::env::var("TEST_MYSQL_URL").expect("TEST_MYSQL_URL");
+        MysqlMetadata::new(MySqlPool::connect(&url).await.unwrap())
+    }
+
+    /// 解析 SHOW FULL COLUMNS 中的类型
+    fn column(r#type: &str) -> crate::Column {
+        let mut column = Column::default();
+        column.handle_column_as_type(r#type).unwrap();
+        column.into()
+    }
+
+    #[test]
+    fn unknown_type() {
+        let column = column("vector(3)");
+        assert_eq!(
+            column.r#type,
+            Some(ColumnType::Unknown("vector".to_string()))
+        );
+        assert_eq!(column.length, Some(3));
+        assert_eq!(column.rust_type, "String");
+    }
+
+    #[test]
+    fn bit_types() {
+        for (ty, rust_type) in [
+            ("bit(1)", "bool"),
+            ("bit(8)", "u8"),
+            ("bit(16)", "u16"),
+            ("bit(20)", "u32"),
+            ("bit(64)", "u64"),
+        ] {
+            let column = column(ty);
+            assert_eq!(column.r#type, Some(ColumnType::Bit), "{ty}");
+            assert_eq!(column.rust_type, rust_type, "{ty}");
+        }
+    }
+
+    #[test]
+    fn enum_and_set_values() {
+        let status = column("enum('active','inactive')");
+        assert_eq!(status.r#type, Some(ColumnType::Enum));
+        assert_eq!(
+            status.enum_values,
+            Some(vec!["active".to_string(), "inactive".to_string()])
+        );
+        let tags = column("set('a','b','c')");
+        assert_eq!(tags.r#type, Some(ColumnType::Set));
+        assert_eq!(
+            tags.enum_values,
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        assert_eq!(column("int(11)").enum_values, None);
+        // 取值中的逗号和转义的引号
+        assert_eq!(
+            parse_enum_values("'a,b','it''s','x\\'y'"),
+            ["a,b", "it's", "x'y"]
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn current_schema() {
+        let meta = connect().await;
+        let (database,): (String,) = sqlx::query_as("SELECT DATABASE()")
+            .fetch_one(&meta.pool)
+            .await
+            .unwrap();
+        assert_eq!(meta.current_schema().await.unwrap(), database);
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn view_kind() {
+        let meta = connect().await;
+        sqlx::raw_sql(
+            "DROP VIEW IF EXISTS test_view_kind_v;
+            DROP TABLE IF EXISTS test_view_kind_t;
+            CREATE TABLE test_view_kind_t (id int);
+            CREATE VIEW test_view_kind_v AS SELECT id FROM test_view_kind_t;",
+        )
+        .execute(&meta.pool)
+        .await
+        .unwrap();
+        let schema = meta.current_schema().await.unwrap();
+        let tables = meta.tables("", &schema).await.unwrap();
+        let kind = |name: &str| tables.iter().find(|t| t.name == name).map(|t| t.kind);
+        assert_eq!(kind("test_view_kind_t"), Some(crate::TableKind::BaseTable));
+        assert_eq!(kind("test_view_kind_v"), Some(crate::TableKind::View));
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn foreign_key_on_delete_cascade() {
+        let meta = connect().await;
+        sqlx::raw_sql(
+            "DROP TABLE IF EXISTS test_fk_child;
+            DROP TABLE IF EXISTS test_fk_parent;
+            CREATE TABLE test_fk_parent (a INT, b INT, PRIMARY KEY (a, b)) ENGINE = InnoDB;
+            CREATE TABLE test_fk_child (
+                id INT PRIMARY KEY,
+                pa INT,
+                pb INT,
+                CONSTRAINT fk_parent FOREIGN KEY (pa, pb) REFERENCES test_fk_parent (a, b)
+                    ON DELETE CASCADE
+            ) ENGINE = InnoDB;",
+        )
+        .execute(&meta.pool)
+        .await
+        .unwrap();
+        let schema = meta.current_schema().await.unwrap();
+        let foreign_keys = meta
+            .foreign_keys("", &schema, "test_fk_child")
+            .await
+            .unwrap();
+        assert_eq!(foreign_keys.len(), 1);
+        let fk = &foreign_keys[0];
+        assert_eq!(fk.name, "fk_parent");
+        assert_eq!(fk.columns, ["pa", "pb"]);
+        assert_eq!(fk.referenced_table, "test_fk_parent");
+        assert_eq!(fk.referenced_columns, ["a", "b"]);
+        assert_eq!(fk.on_delete.as_deref(), Some("CASCADE"));
+        assert!(
+            meta.foreign_keys("", &schema, "test_fk_parent")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn decimal_precision_and_scale() {
+        let price = column("decimal(10,2)");
+        assert_eq!(price.r#type, Some(ColumnType::Decimal));
+        assert_eq!((price.length, price.scale), (Some(10), Some(2)));
+        assert_eq!(price.rust_type, "bigdecimal::BigDecimal");
+        assert_eq!(price.full_type(), "DECIMAL(10,2)");
+    }
+
+    #[test]
+    fn unsigned_integers() {
+        for (ty, rust_type, is_unsigned) in [
+            ("int unsigned", "u32", true),
+            ("int(10) unsigned", "u32", true),
+            ("bigint unsigned", "u64", true),
+            ("tinyint unsigned", "u8", true),
+            ("tinyint", "i8", false),
+            ("int", "i32", false),
+            ("decimal(10,2) unsigned", "bigdecimal::BigDecimal", true),
+        ] {
+            let column = column(ty);
+            assert_eq!(column.rust_type, rust_type, "{ty}");
+            assert_eq!(column.is_unsigned, is_unsigned, "{ty}");
+        }
+    }
+
+    #[test]
+    fn tinyint1_as_bool() {
+        assert_eq!(column("tinyint(1)").rust_type, "bool");
+        assert_eq!(column("tinyint(3)").rust_type, "i8");
+        assert_eq!(column("tinyint(1) unsigned").rust_type, "u8");
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn system_schemas_excluded() {
+        let meta = connect().await;
+        let current = meta.current_schema().await.unwrap();
+        let system = ["information_schema", "mysql", "performance_schema", "sys"];
+
+        let databases = meta
+            .databases()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| d.name)
+            .collect::<Vec<_>>();
+        let schemas = meta
+            .schemas()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect::<Vec<_>>();
+        assert_eq!(databases, schemas);
+        assert!(databases.contains(&current));
+        assert!(!databases.iter().any(|d| system.contains(&d.as_str())));
+
+        let meta = meta.include_system_schemas(true);
+        let schemas = meta
+            .schemas()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect::<Vec<_>>();
+        assert!(schemas.contains(&current));
+        assert!(schemas.iter().any(|s| s == "information_schema"));
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn ordinal_position_starts_at_one() {
+        let meta = connect().await;
+        sqlx::raw_sql(
+            "DROP TABLE IF EXISTS test_ordinal_position;
+            CREATE TABLE test_ordinal_position (a INT, b TEXT);",
+        )
+        .execute(&meta.pool)
+        .await
+        .unwrap();
+        let schema = meta.current_schema().await.unwrap();
+        let columns = meta
+            .columns("", &schema, "test_ordinal_position")
+            .await
+            .unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| c.ordinal_position)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        let columns = meta
+            .all_columns("", &schema, &["test_ordinal_position"])
+            .await
+            .unwrap();
+        assert_eq!(columns[0].ordinal_position, 1);
+    }
+
+    #[test]
+    fn generated_from_extra() {
+        for (extra, is_generated) in [
+            ("STORED GENERATED", true),
+            ("VIRTUAL GENERATED", true),
+            ("DEFAULT_GENERATED", false),
+            ("DEFAULT_GENERATED on update CURRENT_TIMESTAMP", false),
+            ("auto_increment", false),
+            ("", false),
+        ] {
+            let mut column = Column::default();
+            column.handle_is_generated(extra);
+            assert_eq!(column.is_generated, is_generated, "{extra}");
+        }
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn stored_generated_column() {
+        let meta = connect().await;
+        sqlx::raw_sql(
+            "DROP TABLE IF EXISTS test_generated;
+            CREATE TABLE test_generated (
+                price INT,
+                total INT GENERATED ALWAYS AS (price * 2) STORED,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );",
+        )
+        .execute(&meta.pool)
+        .await
+        .unwrap();
+        let schema = meta.current_schema().await.unwrap();
+        let columns = meta.columns("", &schema, "test_generated").await.unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| (c.name.as_str(), c.is_generated))
+                .collect::<Vec<_>>(),
+            [("price", false), ("total", true), ("created_at", false)]
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn function_and_procedure() {
+        let meta = connect().await;
+        sqlx::raw_sql(
+            "DROP FUNCTION IF EXISTS test_add_one;
+            DROP PROCEDURE IF EXISTS test_reset;
+            CREATE FUNCTION test_add_one(n INT) RETURNS INT DETERMINISTIC RETURN n + 1;
+            CREATE PROCEDURE test_reset(IN id INT, OUT total INT) SET total = id;",
+        )
+        .execute(&meta.pool)
+        .await
+        .unwrap();
+        let routines = meta.routines("", "").await.unwrap();
+
+        let add_one = routines.iter().find(|r| r.name == "test_add_one").unwrap();
+        assert_eq!(add_one.kind, crate::RoutineKind::Function);
+        assert_eq!(add_one.return_type.as_deref(), Some("int"));
+        let parameters = add_one
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.r#type.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(parameters, [("n", "int")]);
+
+        let reset = routines.iter().find(|r| r.name == "test_reset").unwrap();
+        assert_eq!(reset.kind, crate::RoutineKind::Procedure);
+        assert_eq!(reset.return_type, None);
+        let modes = reset
+            .parameters
+            .iter()
+            .map(|p| p.mode.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(modes, ["IN", "OUT"]);
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn varchar_collation() {
+        let meta = connect().await;
+        sqlx::raw_sql(
+            "DROP TABLE IF EXISTS test_collation;
+            CREATE TABLE test_collation (
+                id INT,
+                code VARCHAR(32) CHARACTER SET utf8mb4 COLLATE utf8mb4_bin
+            );",
+        )
+        .execute(&meta.pool)
+        .await
+        .unwrap();
+        let schema = meta.current_schema().await.unwrap();
+        let columns = meta.columns("", &schema, "test_collation").await.unwrap();
+        assert_eq!(columns[0].collation, None);
+        assert_eq!(columns[0].charset, None);
+        assert_eq!(columns[1].collation.as_deref(), Some("utf8mb4_bin"));
+        assert_eq!(columns[1].charset.as_deref(), Some("utf8mb4"));
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn all_columns_single_query() {
+        let names = (0..50)
+            .map(|i| format!("test_all_columns_{i}"))
+            .collect::<Vec<_>>();
+        let sql = names
+            .iter()
+            .map(|t| format!("DROP TABLE IF EXISTS {t}; CREATE TABLE {t} (id INT, name TEXT);"))
+            .collect::<String>();
+        sqlx::raw_sql(&sql)
+            .execute(&connect().await.pool)
+            .await
+            .unwrap();
+        let table_names = names.iter().map(String::as_str).collect::<Vec<_>>();
+
+        // 单个连接上执行，预备语句的执行次数即查询次数，读取状态使用文本协议，不计入其中
+        let url = std::env::var("TEST_MYSQL_URL").expect("TEST_MYSQL_URL");
+        let pool = sqlx::mysql::MySqlPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .unwrap();
+        let executions = async || {
+            let row = sqlx::raw_sql("SHOW SESSION STATUS LIKE 'Com_stmt_execute'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            row.get::<String, _>(1).parse::<i64>().unwrap()
+        };
+        let before = executions().await;
+        let meta = MysqlMetadata::new(pool.clone());
+        let columns = meta.all_columns("", "", &table_names).await.unwrap();
+        assert_eq!(executions().await - before, 1);
+        assert_eq!(columns.len(), 100);
+        assert_eq!(columns[0].table_name, "test_all_columns_0");
+        assert_eq!(columns[99].table_name, "test_all_columns_49");
+    }
 }
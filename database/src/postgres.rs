@@ -1,7 +1,68 @@
 use serde::{Deserialize, Serialize};
-use sqlx::{AnyPool, FromRow, PgPool, Row, any::AnyRow, postgres::PgRow};
+use sqlx::{FromRow, PgPool, Row, postgres::PgRow};
 
-use super::{DatabaseMetadata, Result};
+use super::{ColumnType, DatabaseMetadata, Error, Result};
+
+/// 查询列信息，$1 为库名，$2 为模式名，调用方追加表名条件和排序
+///
+/// information_schema.columns 不包含物化视图的列，直接查询 pg_attribute，各列的取值与
+/// information_schema.columns 一致，类型为域时使用其基础类型
+const SELECT_COLUMNS: &str = "
+SELECT
+	current_database() AS table_catalog,
+	n.nspname AS table_schema,
+	C.relname AS TABLE_NAME,
+	a.attname AS COLUMN_NAME,
+	a.attnum::int AS ordinal_position,
+	CASE WHEN a.attgenerated = '' THEN pg_get_expr(ad.adbin, ad.adrelid) END AS column_default,
+	CASE WHEN a.attnotnull OR (T.typtype = 'd' AND T.typnotnull) THEN 'NO' ELSE 'YES' END AS is_nullable,
+	a.attnotnull,
+	COALESCE(bt.typname, T.typname) AS data_type,
+	information_schema._pg_char_max_length(tt.typid, tt.typmod) AS character_maximum_length,
+	CASE WHEN tt.typid = 'numeric'::regtype THEN
+		information_schema._pg_numeric_precision(tt.typid, tt.typmod)
+	END AS numeric_precision,
+	CASE WHEN tt.typid = 'numeric'::regtype THEN
+		information_schema._pg_numeric_scale(tt.typid, tt.typmod)
+	END AS numeric_scale,
+	CASE WHEN a.attidentity IN ('a', 'd') THEN 'YES' ELSE 'NO' END AS is_identity,
+	CASE WHEN a.attgenerated = '' THEN 'NEVER' ELSE 'ALWAYS' END AS is_generated,
+	CASE WHEN co.collname <> 'default' THEN co.collname END AS collation_name,
+	EXISTS (
+		SELECT 1
+		FROM pg_index i
+		WHERE i.indrelid = C.OID AND i.indisprimary AND a.attnum = ANY (i.indkey)
+	) AS is_primary_key,
+	d.description,
+	CASE WHEN a.attidentity = '' THEN
+		pg_get_serial_sequence(quote_ident(n.nspname) || '.' || quote_ident(C.relname), a.attname)
+	END AS owned_sequence,
+	(
+		SELECT array_agg(e.enumlabel::text ORDER BY e.enumsortorder)
+		FROM pg_enum e
+		WHERE e.enumtypid = a.atttypid
+	) AS enum_values
+FROM
+	pg_attribute a
+	JOIN pg_class C ON C.OID = a.attrelid
+	JOIN pg_namespace n ON n.OID = C.relnamespace
+	JOIN pg_type T ON T.OID = a.atttypid
+	LEFT JOIN pg_type bt ON T.typtype = 'd' AND bt.OID = T.typbasetype
+	CROSS JOIN LATERAL (
+		SELECT
+			information_schema._pg_truetypid(a.*, T.*) AS typid,
+			information_schema._pg_truetypmod(a.*, T.*) AS typmod
+	) tt
+	LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+	LEFT JOIN pg_collation co ON co.OID = a.attcollation
+	LEFT JOIN pg_description d ON d.objoid = C.OID AND d.objsubid = a.attnum
+WHERE
+	C.relkind IN ('r', 'p', 'v', 'm', 'f')
+	AND a.attnum > 0
+	AND NOT a.attisdropped
+	AND current_database() = COALESCE(NULLIF($1, ''), current_database())
+	AND n.nspname = COALESCE(NULLIF($2, ''), current_schema())
+";
 
 pub struct PostgresMetadata(PgPool);
 
@@ -39,13 +100,13 @@ struct Table {
     /// 表的名称
     // #[sqlx(rename = "table_name")]
     table_name: String,
-    // /// 表的类型
-    // /// - 'BASE TABLE': 普通表
-    // /// - 'VIEW': 视图
-    // /// - 'FOREIGN TABLE': 外部表
-    // /// - 'LOCAL TEMPORARY': 临时表
-    // // #[sqlx(rename = "table_type")]
-    // table_type: String,
+    /// pg_class.relkind
+    /// - 'r': 普通表
+    /// - 'p': 分区表
+    /// - 'v': 视图
+    /// - 'm': 物化视图
+    /// - 'f': 外部表
+    relkind: String,
     // /// 自引用列的名称 (通常为 NULL)
     // // #[sqlx(rename = "self_referencing_column_name")]
     // self_referencing_column_name: Option<String>,
@@ -78,8 +139,15 @@ impl From<Table> for super::Table {
     fn from(t: Table) -> Self {
         Self {
             schema: t.table_schema,
-            name: t.table_name.clone(),
-            comment: t.description.unwrap_or(t.table_name),
+            name: t.table_name,
+            comment: t.description.unwrap_or_default(),
+            collation: None,
+            kind: match t.relkind.as_str() {
+                "v" => super::TableKind::View,
+                "m" => super::TableKind::MaterializedView,
+                "f" => super::TableKind::ForeignTable,
+                _ => super::TableKind::BaseTable,
+            },
         }
     }
 }
@@ -101,45 +169,81 @@ struct Column {
     column_default: Option<String>,
     /// 列是否允许 NULL 值
     is_nullable: String, // 'YES' or 'NO'
+    /// pg_attribute 中的非空标记，比 is_nullable 更准确
+    attnotnull: bool,
     /// 列的标准SQL数据类型
     data_type: String,
     /// 字符类型列的最大长度
     character_maximum_length: Option<i32>,
+    /// NUMERIC 列的精度，未声明精度时为空
+    numeric_precision: Option<i32>,
+    /// NUMERIC 列的小数位数
+    numeric_scale: Option<i32>,
+    /// 是否为标识列 (GENERATED ... AS IDENTITY)
+    is_identity: String, // 'YES' or 'NO'
+    /// 是否为生成列 (GENERATED ALWAYS AS (...) STORED)
+    is_generated: String, // 'ALWAYS' or 'NEVER'
+    /// 显式指定的排序规则，使用类型默认排序规则时为空
+    collation_name: Option<String>,
+    /// 是否为主键列
+    is_primary_key: bool,
+    /// 列的注释
+    description: Option<String>,
+    /// 拥有该列的序列，标识列的内部序列不计入
+    owned_sequence: Option<String>,
+    /// 自定义枚举类型（CREATE TYPE ... AS ENUM）的取值，按定义顺序排列
+    enum_values: Option<Vec<String>>,
     // /// 字符类型列的最大字节长度
     // character_octet_length: Option<i32>,
-    // /// 数值类型列的精度
-    // numeric_precision: Option<i32>,
     // /// 数值精度的基数 (2=二进制, 10=十进制)
     // numeric_precision_radix: Option<i32>,
-    // /// 数值类型列的小数位数
-    // numeric_scale: Option<i32>,
 }
 
 impl From<Column> for super::Column {
     fn from(c: Column) -> Self {
-        let ty = t2t(&c.data_type.clone().to_uppercase()).to_string();
+        // 自定义枚举类型以类型名的大驼峰形式作为 Rust 类型，如 mood -> Mood
+        let ty = match c.enum_values {
+            Some(_) => enum_type_name(&c.data_type),
+            None => rust_type(&c.data_type),
+        };
         Self {
             database: c.table_catalog,
             schema: c.table_schema,
             table_name: c.table_name,
             name: c.column_name,
-            // r#type: Some(ty),
-            length: c.character_maximum_length,
-            default: c.column_default,
+            ordinal_position: c.ordinal_position,
+            r#type: Some(ColumnType::from(c.data_type)),
+            // NUMERIC(p,s) 的精度作为长度，小数位数作为精度
+            length: c.character_maximum_length.or(c.numeric_precision),
+            scale: c.numeric_scale,
+            default: c.column_default.clone(),
+            default_value: super::DefaultValue::classify(
+                c.column_default.as_deref(),
+                super::Driver::Postgres,
+            ),
+            // 以 pg_attribute.attnotnull 为准，两者都允许为空时才认为可为空
+            is_null: c.is_nullable.eq_ignore_ascii_case("yes") && !c.attnotnull,
+            // serial 类型的默认值为 nextval('序列名'::regclass)
+            is_auto_incr: c.is_identity.eq_ignore_ascii_case("yes")
+                || is_serial_default(c.column_default.as_deref()),
+            is_primary_key: c.is_primary_key,
+            is_generated: c.is_generated.eq_ignore_ascii_case("always"),
+            comment: c.description.unwrap_or_default(),
+            owned_sequence: c.owned_sequence,
+            // Postgres 的字符集是数据库级别的，列上只有排序规则
+            collation: c.collation_name,
+            enum_values: c.enum_values,
             // enum_values: todo!(),
-            // comment: todo!(),
-            // is_null: todo!(),
-            // is_auto_incr: todo!(),
             // is_unique: todo!(),
-            // is_primary_key: todo!(),
             // is_unsigned: todo!(),
-            // rust_type: todo!(),
+            // 可为空字段由模板按 nullable_wrapper 包装，这里只保存基础类型
+            rust_type: ty,
             ..Default::default()
         }
         // Self {
         //     schema: Some(c.table_schema.clone()),
         //     table_name: Some(c.table_name.clone()),
-        //     name: Some(super::column_keywords(c.column_name.clone().as_str())),
+        //     name: Some(super::escape_keyword(c.column_name.clone().as_str())),
         //     default: c.column_default.clone(),
         //     is_nullable: {
         //         if ty.contains("Time") {
@@ -163,6 +267,223 @@ impl From<Column> for super::Column {
     }
 }
 
+#[derive(Debug, Default, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct Index {
+    /// 表名
+    table_name: String,
+    /// 唯一索引为0，否则为1
+    non_unique: i32,
+    /// 索引的名称
+    key_name: String,
+    /// 列在索引中的位置 (从1开始)
+    seq_in_index: i32,
+    /// 列名
+    column_name: String,
+    /// 索引方法（BTREE, HASH, GIST, GIN, BRIN ...）
+    index_type: String,
+    /// 索引的注释
+    index_comment: String,
+}
+
+impl From<Index> for super::Index {
+    fn from(ix: Index) -> Self {
+        Self {
+            table_name: ix.table_name,
+            non_unique: ix.non_unique,
+            key_name: ix.key_name,
+            seq_in_index: ix.seq_in_index as u32,
+            column_name: ix.column_name,
+            sub_part: None,
+            index_type: ix.index_type,
+            index_comment: ix.index_comment,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct ForeignKey {
+    /// 表名
+    table_name: String,
+    /// 外键约束名
+    name: String,
+    /// 外键列
+    column_name: String,
+    /// 引用的表名
+    referenced_table: String,
+    /// 引用的列
+    referenced_column: String,
+    /// 删除动作
+    on_delete: Option<String>,
+    /// 更新动作
+    on_update: Option<String>,
+}
+
+/// 检查约束
+#[derive(Debug, FromRow)]
+struct CheckConstraint {
+    table_name: String,
+    name: String,
+    expression: String,
+}
+
+impl From<CheckConstraint> for super::CheckConstraint {
+    fn from(c: CheckConstraint) -> Self {
+        Self {
+            table_name: c.table_name,
+            name: c.name,
+            expression: c.expression,
+        }
+    }
+}
+
+/// pg_proc 中的存储过程或函数
+#[derive(Debug, FromRow)]
+struct Routine {
+    schema: String,
+    name: String,
+    /// f 为函数，p 为存储过程
+    kind: String,
+    return_type: Option<String>,
+    /// 参数类型，包括 OUT 参数
+    arg_types: Vec<String>,
+    /// 参数名，与 arg_types 一一对应，都未命名时为空
+    arg_names: Vec<String>,
+    /// 参数模式：i、o、b（INOUT）、v（VARIADIC）、t（RETURNS TABLE 的列），只有 IN 参数时为空
+    arg_modes: Vec<String>,
+}
+
+impl From<Routine> for super::Routine {
+    fn from(r: Routine) -> Self {
+        let parameters = r
+            .arg_types
+            .into_iter()
+            .enumerate()
+            .map(|(i, ty)| super::RoutineParameter {
+                name: r.arg_names.get(i).cloned().unwrap_or_default(),
+                mode: match r.arg_modes.get(i).map(String::as_str) {
+                    Some("o" | "t") => "OUT",
+                    Some("b") => "INOUT",
+                    Some("v") => "VARIADIC",
+                    _ => "IN",
+                }
+                .to_string(),
+                r#type: ty,
+            })
+            .collect();
+        Self {
+            schema: r.schema,
+            name: r.name,
+            kind: if r.kind == "p" {
+                super::RoutineKind::Procedure
+            } else {
+                super::RoutineKind::Function
+            },
+            return_type: r.return_type,
+            parameters,
+        }
+    }
+}
+
+impl From<ForeignKey> for super::ForeignKey {
+    fn from(fk: ForeignKey) -> Self {
+        Self {
+            table_name: fk.table_name,
+            name: fk.name,
+            columns: vec![fk.column_name],
+            referenced_table: fk.referenced_table,
+            referenced_columns: vec![fk.referenced_column],
+            on_delete: fk.on_delete,
+            on_update: fk.on_update,
+        }
+    }
+}
+
+/// 默认值是否来自序列
+fn is_serial_default(default: Option<&str>) -> bool {
+    default.is_some_and(|d| d.trim_start().to_lowercase().starts_with("nextval("))
+}
+
+/// 用于重建建表语句的列定义
+#[derive(Debug, FromRow)]
+struct ColumnDefinition {
+    /// 列名，已按需加引号
+    name: String,
+    /// 完整的类型，包含长度、精度，如 character varying(50)、numeric(10,2)
+    data_type: String,
+    /// 是否非空
+    not_null: bool,
+    /// 默认值表达式，生成列为生成表达式
+    default_value: Option<String>,
+    /// pg_attribute.attgenerated，`s` 为存储的生成列，普通列为空
+    generated: String,
+    /// pg_attribute.attidentity，`a` 为 GENERATED ALWAYS，`d` 为 GENERATED BY DEFAULT，普通列为空
+    identity: String,
+    /// 与类型默认值不同的排序规则，已按需加引号，如 "C"
+    collation: Option<String>,
+    /// 拥有该列的序列，标识列的内部序列不计入
+    owned_sequence: Option<String>,
+}
+
+impl std::fmt::Display for ColumnDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        if let Some(collation) = &self.collation {
+            write!(f, " COLLATE {collation}")?;
+        }
+        if self.not_null {
+            f.write_str(" NOT NULL")?;
+        }
+        match (self.generated.as_str(), self.identity.as_str()) {
+            ("s", _) => {
+                if let Some(expr) = &self.default_value {
+                    write!(f, " GENERATED ALWAYS AS ({expr}) STORED")?;
+                }
+            }
+            (_, "a") => f.write_str(" GENERATED ALWAYS AS IDENTITY")?,
+            (_, "d") => f.write_str(" GENERATED BY DEFAULT AS IDENTITY")?,
+            _ => {
+                if let Some(default) = &self.default_value {
+                    write!(f, " DEFAULT {default}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 自定义枚举类型名转换为大驼峰形式的 Rust 类型名，如 `order_status` -> `OrderStatus`
+fn enum_type_name(udt_name: &str) -> String {
+    udt_name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// PostgreSQL 类型转换为Rust对应类型，数组类型映射为元素类型的 Vec
+///
+/// - udt_name 以 `_` 开头的数组类型，如 `_int4` -> `Vec<i32>`、`_text` -> `Vec<String>`。
+///   udt_name 不区分数组的维数，多维数组同样映射为 `Vec<T>`
+/// - 以 `[]` 结尾的类型，如 `int4[]`，按 `[]` 的个数嵌套 Vec，`int4[][]` -> `Vec<Vec<i32>>`
+pub(crate) fn rust_type(ty: &str) -> String {
+    let ty = ty.trim();
+    if let Some(element) = ty.strip_suffix("[]") {
+        return format!("Vec<{}>", rust_type(element));
+    }
+    match ty.strip_prefix('_') {
+        Some(element) => format!("Vec<{}>", t2t(element)),
+        None => t2t(ty).to_string(),
+    }
+}
+
 /// Rust type            Postgres type(s)
 /// bool                    BOOL
 /// i8                      “CHAR”
@@ -200,12 +521,12 @@ impl From<Column> for super::Column {
 /// serde_json::Value       JSON, JSONB
 ///
 /// PostgreSQL 类型转换为Rust对应类型
-fn t2t(ty: &str) -> &str {
+pub(crate) fn t2t(ty: &str) -> &'static str {
     match ty.to_uppercase().as_str() {
-        "BOOL" => "bool",
+        "BOOL" | "BOOLEAN" => "bool",
         "CHAR" => "i8",
         "SMALLINT" | "SMALLSERIAL" | "INT2" => "i16",
-        "INT" | "SERIAL" | "INT4" => "i32",
+        "INT" | "INTEGER" | "SERIAL" | "INT4" => "i32",
         "BIGINT" | "BIGSERIAL" | "INT8" => "i64",
         "REAL" | "FLOAT4" => "f32",
         "DOUBLE PRECISION" | "FLOAT8" => "f64",
@@ -220,12 +541,12 @@ fn t2t(ty: &str) -> &str {
         "LQUERY" => "sqlx_postgres::types::PgLQuery",
         "YEAR" => "time::Date",
         "DATE" => "time::Date",
-        "TIME" => "time::Time",
-        "TIMESTAMP" => "time::PrimitiveDateTime",
-        "TIMESTAMPTZ" => "time::OffsetDateTime",
-        "TIMETZ" => "sqlx_postgres::types::PgTimeTz",
-        "NUMERIC" => "bigdecimal::BigDecimal",
-        "JSON" | "JSONB" => "serde_json:JsonValue",
+        "TIME" | "TIME WITHOUT TIME ZONE" => "time::Time",
+        "TIMESTAMP" | "TIMESTAMP WITHOUT TIME ZONE" => "time::PrimitiveDateTime",
+        "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => "time::OffsetDateTime",
+        "TIMETZ" | "TIME WITH TIME ZONE" => "sqlx_postgres::types::PgTimeTz",
+        "NUMERIC" | "DECIMAL" => "bigdecimal::BigDecimal",
+        "JSON" | "JSONB" => "serde_json::Value",
         "UUID" => "uuid::Uuid",
         "INET" | "CIDR" => "std::net::IpAddr",
         "MACADDR" => "mac_address::MacAddress",
@@ -241,11 +562,43 @@ impl PostgresMetadata {
 }
 
 impl DatabaseMetadata for PostgresMetadata {
+    fn ping(&self) -> super::BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            sqlx::query("SELECT 1").execute(&self.0).await?;
+            Ok(())
+        })
+    }
+
     fn databases(&self) -> super::BoxFuture<'_, Result<Vec<super::Database>>> {
-        todo!()
+        Box::pin(async move {
+            let rows: Vec<Database> = sqlx::query_as(
+                "SELECT datname AS name FROM pg_database WHERE datistemplate = false ORDER BY datname",
+            )
+            .fetch_all(&self.0)
+            .await?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
     }
     fn schemas(&self) -> super::BoxFuture<'_, Result<Vec<super::Schema>>> {
-        todo!()
+        Box::pin(async move {
+            // 排除系统模式：pg_catalog、information_schema 以及 pg_ 开头的模式（如 pg_toast）
+            let rows: Vec<Schema> = sqlx::query_as(
+                "SELECT nspname AS name FROM pg_namespace WHERE nspname <> 'information_schema' AND left(nspname, 3) <> 'pg_' ORDER BY nspname",
+            )
+            .fetch_all(&self.0)
+            .await?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
+    }
+
+    fn current_schema(&self) -> super::BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            // search_path 中没有有效模式时 current_schema() 返回 NULL
+            let (schema,): (Option<String>,) = sqlx::query_as("SELECT current_schema()")
+                .fetch_one(&self.0)
+                .await?;
+            Ok(schema.unwrap_or_default())
+        })
     }
 
     fn tables<'a>(
@@ -253,16 +606,27 @@ impl DatabaseMetadata for PostgresMetadata {
         database: &'a str,
         schema: &'a str,
     ) -> super::BoxFuture<'a, Result<Vec<super::Table>>> {
-        let mut sql = "SELECT tb.table_catalog, tb.table_schema, tb.TABLE_NAME, d.description FROM information_schema.tables tb JOIN pg_class C ON C.relname = tb. TABLE_NAME LEFT JOIN pg_description d ON d.objoid = C.OID  AND d.objsubid = '0' where ".to_string();
-        if database.is_empty() {
-            sql.push_str(" tb.table_catalog = current_database()");
-        } else {
-            sql.push_str(" tb.table_catalog = $1");
-        }
-        sql.push_str(" and tb.table_schema = $2");
+        // information_schema.tables 不包含物化视图，直接查询 pg_class
+        let sql = "
+        SELECT
+        	current_database() AS table_catalog,
+        	n.nspname AS table_schema,
+        	c.relname AS table_name,
+        	c.relkind::text AS relkind,
+        	d.description
+        FROM
+        	pg_class c
+        	JOIN pg_namespace n ON n.OID = c.relnamespace
+        	LEFT JOIN pg_description d ON d.objoid = c.OID AND d.objsubid = 0
+        WHERE
+        	c.relkind IN ('r', 'p', 'v', 'm', 'f')
+        	AND current_database() = COALESCE(NULLIF($1, ''), current_database())
+        	AND n.nspname = COALESCE(NULLIF($2, ''), current_schema())
+        ORDER BY c.OID
+        ";
 
         Box::pin(async move {
-            let rows: Vec<Table> = sqlx::query_as(&sql)
+            let rows: Vec<Table> = sqlx::query_as(sql)
                 .bind(database)
                 .bind(schema)
                 .fetch_all(&self.0)
@@ -277,39 +641,7 @@ impl DatabaseMetadata for PostgresMetadata {
         schema: &'a str,
         table_name: &'a str,
     ) -> super::BoxFuture<'a, Result<Vec<super::Column>>> {
-        let mut sql = "
-        SELECT
-        	col.table_catalog,
-        	col.table_schema,
-        	col.TABLE_NAME,
-        	col.COLUMN_NAME,
-        	col.ordinal_position,
-        	col.column_default,
-        	col.is_nullable,
-        	col.udt_name as data_type,
-        	col.character_maximum_length,
-        	d.description
-        FROM
-        	information_schema.COLUMNS col
-        	JOIN pg_class C ON C.relname = col.
-        	TABLE_NAME LEFT JOIN pg_description d ON d.objoid = C.OID
-        	AND d.objsubid = col.ordinal_position
-        WHERE
-       "
-        .to_string();
-
-        if database.is_empty() {
-            sql.push_str(" col.table_catalog = current_database() ");
-        } else {
-            sql.push_str(" col.table_catalog = $1 ");
-        }
-        if schema.is_empty() {
-            sql.push_str(" and col.table_schema = current_schema() ");
-        } else {
-            sql.push_str(" and col.table_schema = $2 ");
-        }
-        sql.push_str(" and col.TABLE_NAME = $3 ");
-        sql.push_str(" ORDER BY col.TABLE_NAME, col.ordinal_position ");
+        let sql = format!("{SELECT_COLUMNS} AND C.relname = $3 ORDER BY a.attnum");
 
         Box::pin(async move {
             let rows: Vec<Column> = sqlx::query_as(&sql)
@@ -322,13 +654,109 @@ impl DatabaseMetadata for PostgresMetadata {
         })
     }
 
+    fn all_columns<'a>(
+        &'a self,
+        database: &'a str,
+        schema: &'a str,
+        table_names: &'a [&'a str],
+    ) -> super::BoxFuture<'a, Result<Vec<super::Column>>> {
+        // 按 table_names 的顺序返回，同一表内按列的位置排序
+        let sql = format!(
+            "{SELECT_COLUMNS} AND C.relname = ANY($3) ORDER BY array_position($3, C.relname::text), a.attnum"
+        );
+
+        Box::pin(async move {
+            let rows: Vec<Column> = sqlx::query_as(&sql)
+                .bind(database)
+                .bind(schema)
+                .bind(table_names)
+                .fetch_all(&self.0)
+                .await?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
+    }
+
     fn indexs<'a>(
         &'a self,
         database: &'a str,
         schema: &'a str,
         table_name: &'a str,
     ) -> super::BoxFuture<'a, Result<Vec<super::Index>>> {
-        todo!()
+        // 多列索引每列返回一行，表达式索引中的表达式列（attnum = 0）不返回
+        let sql = "
+        SELECT
+        	t.relname AS table_name,
+        	CASE WHEN ix.indisunique THEN 0 ELSE 1 END AS non_unique,
+        	i.relname AS key_name,
+        	k.ord::int4 AS seq_in_index,
+        	a.attname AS column_name,
+        	upper(am.amname) AS index_type,
+        	COALESCE(obj_description(i.OID, 'pg_class'), '') AS index_comment
+        FROM
+        	pg_index ix
+        	JOIN pg_class t ON t.OID = ix.indrelid
+        	JOIN pg_class i ON i.OID = ix.indexrelid
+        	JOIN pg_namespace n ON n.OID = t.relnamespace
+        	JOIN pg_am am ON am.OID = i.relam
+        	CROSS JOIN LATERAL unnest(ix.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord)
+        	JOIN pg_attribute a ON a.attrelid = t.OID AND a.attnum = k.attnum
+        WHERE
+        	n.nspname = COALESCE(NULLIF($1, ''), current_schema())
+        	AND t.relname = $2
+        ORDER BY i.relname, k.ord
+        ";
+
+        Box::pin(async move {
+            let rows: Vec<Index> = sqlx::query_as(sql)
+                .bind(schema)
+                .bind(table_name)
+                .fetch_all(&self.0)
+                .await?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
+    }
+
+    fn foreign_keys<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::ForeignKey>>> {
+        // 组合外键每列返回一行，conkey 与 confkey 按位置一一对应
+        let sql = "
+        SELECT
+        	t.relname AS table_name,
+        	con.conname AS name,
+        	a.attname AS column_name,
+        	r.relname AS referenced_table,
+        	ra.attname AS referenced_column,
+        	CASE con.confdeltype WHEN 'a' THEN 'NO ACTION' WHEN 'r' THEN 'RESTRICT' WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT' END AS on_delete,
+        	CASE con.confupdtype WHEN 'a' THEN 'NO ACTION' WHEN 'r' THEN 'RESTRICT' WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT' END AS on_update
+        FROM
+        	pg_constraint con
+        	JOIN pg_class t ON t.OID = con.conrelid
+        	JOIN pg_class r ON r.OID = con.confrelid
+        	JOIN pg_namespace n ON n.OID = t.relnamespace
+        	CROSS JOIN LATERAL unnest(con.conkey, con.confkey) WITH ORDINALITY AS k(attnum, refattnum, ord)
+        	JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+        	JOIN pg_attribute ra ON ra.attrelid = con.confrelid AND ra.attnum = k.refattnum
+        WHERE
+        	con.contype = 'f'
+        	AND n.nspname = COALESCE(NULLIF($1, ''), current_schema())
+        	AND t.relname = $2
+        ORDER BY con.conname, k.ord
+        ";
+
+        Box::pin(async move {
+            let rows: Vec<ForeignKey> = sqlx::query_as(sql)
+                .bind(schema)
+                .bind(table_name)
+                .fetch_all(&self.0)
+                .await?;
+            Ok(super::merge_foreign_keys(
+                rows.into_iter().map(|row| row.into()).collect(),
+            ))
+        })
     }
 
     fn create_table_sql<'a>(
@@ -337,6 +765,717 @@ impl DatabaseMetadata for PostgresMetadata {
         schema: &'a str,
         table_name: &'a str,
     ) -> super::BoxFuture<'a, Result<String>> {
-        todo!()
+        // Postgres 没有 SHOW CREATE TABLE，根据系统表重建表的基本结构：列、类型、排序规则、非空、默认值、
+        // 生成列、标识列和主键
+        // 列按 attnum 排序，与 information_schema.columns.ordinal_position 一致
+        let columns_sql = "
+        SELECT
+        	quote_ident(a.attname) AS name,
+        	format_type(a.atttypid, a.atttypmod) AS data_type,
+        	a.attnotnull AS not_null,
+        	pg_get_expr(ad.adbin, ad.adrelid) AS default_value,
+        	a.attgenerated::text AS generated,
+        	a.attidentity::text AS identity,
+        	CASE WHEN a.attcollation <> T.typcollation THEN
+        		quote_ident(cn.nspname) || '.' || quote_ident(coll.collname)
+        	END AS collation,
+        	CASE WHEN a.attidentity = '' THEN
+        		pg_get_serial_sequence(quote_ident(n.nspname) || '.' || quote_ident(C.relname), a.attname)
+        	END AS owned_sequence
+        FROM
+        	pg_attribute a
+        	JOIN pg_class C ON C.OID = a.attrelid
+        	JOIN pg_namespace n ON n.OID = C.relnamespace
+        	JOIN pg_type T ON T.OID = a.atttypid
+        	LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+        	LEFT JOIN pg_collation coll ON coll.OID = a.attcollation
+        	LEFT JOIN pg_namespace cn ON cn.OID = coll.collnamespace
+        WHERE
+        	n.nspname = COALESCE(NULLIF($1, ''), current_schema())
+        	AND C.relname = $2
+        	AND a.attnum > 0
+        	AND NOT a.attisdropped
+        ORDER BY a.attnum
+        ";
+        let primary_key_sql = "
+        SELECT
+        	'CONSTRAINT ' || quote_ident(con.conname) || ' ' || pg_get_constraintdef(con.OID)
+        FROM
+        	pg_constraint con
+        	JOIN pg_class C ON C.OID = con.conrelid
+        	JOIN pg_namespace n ON n.OID = C.relnamespace
+        WHERE
+        	n.nspname = COALESCE(NULLIF($1, ''), current_schema())
+        	AND C.relname = $2
+        	AND con.contype = 'p'
+        ";
+
+        Box::pin(async move {
+            let columns: Vec<ColumnDefinition> = sqlx::query_as(columns_sql)
+                .bind(schema)
+                .bind(table_name)
+                .fetch_all(&self.0)
+                .await?;
+            if columns.is_empty() {
+                return Err(Error::E("table not found"));
+            }
+            let primary_key: Option<String> = sqlx::query_scalar(primary_key_sql)
+                .bind(schema)
+                .bind(table_name)
+                .fetch_optional(&self.0)
+                .await?;
+            // 序列名带有模式，表名同样带上模式
+            let table: String = sqlx::query_scalar(
+                "SELECT quote_ident(COALESCE(NULLIF($1, ''), current_schema())) || '.' || quote_ident($2)",
+            )
+            .bind(schema)
+            .bind(table_name)
+            .fetch_one(&self.0)
+            .await?;
+
+            let definitions = columns
+                .iter()
+                .map(|c| c.to_string())
+                .chain(primary_key)
+                .collect::<Vec<_>>();
+            let mut sql = String::new();
+            // serial 列依赖的序列需要先于表创建，建表后再将序列归属到列上
+            for sequence in columns.iter().filter_map(|c| c.owned_sequence.as_ref()) {
+                sql.push_str(&format!("CREATE SEQUENCE IF NOT EXISTS {sequence};\n"));
+            }
+            sql.push_str(&format!(
+                "CREATE TABLE {table} (\n    {}\n);",
+                definitions.join(",\n    ")
+            ));
+            for c in columns.iter() {
+                if let Some(sequence) = &c.owned_sequence {
+                    sql.push_str(&format!(
+                        "\nALTER SEQUENCE {sequence} OWNED BY {table}.{};",
+                        c.name
+                    ));
+                }
+            }
+            Ok(sql)
+        })
+    }
+
+    fn check_constraints<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::CheckConstraint>>> {
+        // 非空约束在 pg_constraint 中不是检查约束，pg_get_expr 返回带括号的表达式，如 `(age >= 0)`
+        let sql = "
+        SELECT
+        	t.relname AS table_name,
+        	con.conname AS name,
+        	pg_get_expr(con.conbin, con.conrelid) AS expression
+        FROM
+        	pg_constraint con
+        	JOIN pg_class t ON t.OID = con.conrelid
+        	JOIN pg_namespace n ON n.OID = t.relnamespace
+        WHERE
+        	con.contype = 'c'
+        	AND n.nspname = COALESCE(NULLIF($1, ''), current_schema())
+        	AND t.relname = $2
+        ORDER BY con.conname
+        ";
+
+        Box::pin(async move {
+            let rows: Vec<CheckConstraint> = sqlx::query_as(sql)
+                .bind(schema)
+                .bind(table_name)
+                .fetch_all(&self.0)
+                .await?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
+    }
+
+    fn routines<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::Routine>>> {
+        // proallargtypes 包含 OUT 参数，只有 IN 参数时为 NULL，使用 proargtypes；
+        // 聚合函数和窗口函数不是存储过程或函数，排除
+        let sql = "
+        SELECT
+        	n.nspname AS schema,
+        	p.proname AS name,
+        	p.prokind::text AS kind,
+        	CASE WHEN p.prokind = 'f' THEN pg_get_function_result(p.OID) END AS return_type,
+        	ARRAY(
+        		SELECT format_type(t.oid, NULL)
+        		FROM unnest(COALESCE(p.proallargtypes, p.proargtypes::oid[])) WITH ORDINALITY AS t(oid, ord)
+        		ORDER BY t.ord
+        	) AS arg_types,
+        	COALESCE(p.proargnames, ARRAY[]::text[]) AS arg_names,
+        	COALESCE(p.proargmodes::text[], ARRAY[]::text[]) AS arg_modes
+        FROM
+        	pg_proc p
+        	JOIN pg_namespace n ON n.OID = p.pronamespace
+        WHERE
+        	p.prokind IN ('f', 'p')
+        	AND n.nspname = COALESCE(NULLIF($1, ''), current_schema())
+        ORDER BY p.proname, p.OID
+        ";
+
+        Box::pin(async move {
+            let rows: Vec<Routine> = sqlx::query_as(sql).bind(schema).fetch_all(&self.0).await?;
+            Ok(rows.into_iter().map(|row| row.into()).collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 连接 TEST_POSTGRES_URL 指定的数据库
+    #[cfg(feature = "integration")]
+    async fn connect() -> PostgresMetadata {
+        let url = std::env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL");
+        PostgresMetadata::new(PgPool::connect(&url).await.unwrap())
+    }
+
+    /// 重建模式 schema 后执行 sql，各测试使用独立的模式，sql 中的对象需要带上模式名
+    #[cfg(feature = "integration")]
+    async fn setup(schema: &str, sql: &str) -> PostgresMetadata {
+        let meta = connect().await;
+        sqlx::raw_sql(&format!(
+            "DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema}; {sql}"
+        ))
+        .execute(&meta.0)
+        .await
+        .unwrap();
+        meta
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn databases() {
+        let meta = connect().await;
+        let (current,): (String,) = sqlx::query_as("SELECT current_database()")
+            .fetch_one(&meta.0)
+            .await
+            .unwrap();
+        let databases = meta.databases().await.unwrap();
+        assert!(databases.iter().any(|d| d.name == current));
+        assert!(!databases.iter().any(|d| d.name == "template0"));
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn schemas() {
+        let schemas = connect().await.schemas().await.unwrap();
+        let names = schemas.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert!(names.contains(&"public"));
+        for system in ["pg_toast", "pg_catalog", "information_schema"] {
+            assert!(!names.contains(&system));
+        }
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn not_null_from_constraints() {
+        let meta = setup(
+            "test_not_null",
+            "CREATE TABLE test_not_null.t (id int PRIMARY KEY, name text CONSTRAINT name_nn NOT NULL, note text);
+            ALTER TABLE test_not_null.t ALTER COLUMN note SET NOT NULL;
+            ALTER TABLE test_not_null.t ADD COLUMN memo text;",
+        )
+        .await;
+        let columns = meta.columns("", "test_not_null", "t").await.unwrap();
+        let nullable = columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_null))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nullable,
+            [
+                ("id", false),
+                ("name", false),
+                ("note", false),
+                ("memo", true)
+            ]
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn composite_unique_index() {
+        let meta = setup(
+            "test_indexs",
+            "CREATE TABLE test_indexs.t (a int, b text, c int);
+            CREATE UNIQUE INDEX t_b_a ON test_indexs.t (b, a);
+            CREATE INDEX t_c ON test_indexs.t USING hash (c);",
+        )
+        .await;
+        let indexs = meta.indexs("", "test_indexs", "t").await.unwrap();
+        let rows = indexs
+            .iter()
+            .map(|ix| {
+                (
+                    ix.key_name.as_str(),
+                    ix.column_name.as_str(),
+                    ix.seq_in_index,
+                    ix.non_unique,
+                    ix.index_type.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                ("t_b_a", "b", 1, 0, "BTREE"),
+                ("t_b_a", "a", 2, 0, "BTREE"),
+                ("t_c", "c", 1, 1, "HASH"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn create_table_sql_round_trip() {
+        let meta = setup(
+            "test_ddl",
+            r#"DROP SCHEMA IF EXISTS test_ddl_copy CASCADE;
+            CREATE SCHEMA test_ddl_copy;
+            CREATE TABLE test_ddl."order" (
+                id serial PRIMARY KEY,
+                "user" varchar(20) COLLATE "C" NOT NULL,
+                "select" int NOT NULL DEFAULT 1,
+                price numeric(10,2),
+                total numeric GENERATED ALWAYS AS (price * 2) STORED,
+                seq bigint GENERATED BY DEFAULT AS IDENTITY,
+                tags text[]
+            );"#,
+        )
+        .await;
+        let sql = meta
+            .create_table_sql("", "test_ddl", "order")
+            .await
+            .unwrap();
+        // 在另一个模式中重建表
+        sqlx::raw_sql(&sql.replace("test_ddl.", "test_ddl_copy."))
+            .execute(&meta.0)
+            .await
+            .unwrap();
+
+        let signature = |columns: Vec<crate::Column>| {
+            columns
+                .into_iter()
+                .map(|c| {
+                    (
+                        c.name,
+                        c.r#type,
+                        c.length,
+                        c.scale,
+                        c.is_null,
+                        c.is_primary_key,
+                        c.is_auto_incr,
+                        c.is_generated,
+                        c.collation,
+                        c.default.map(|d| d.replace("test_ddl_copy.", "test_ddl.")),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let original = signature(meta.columns("", "test_ddl", "order").await.unwrap());
+        let copy = signature(meta.columns("", "test_ddl_copy", "order").await.unwrap());
+        assert_eq!(original.len(), 7);
+        assert_eq!(original, copy);
+        sqlx::raw_sql("DROP SCHEMA test_ddl_copy CASCADE")
+            .execute(&meta.0)
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn serial_primary_key() {
+        let meta = setup(
+            "test_serial",
+            "CREATE TABLE test_serial.t (id serial PRIMARY KEY, name text NOT NULL, note text);",
+        )
+        .await;
+        let columns = meta.columns("", "test_serial", "t").await.unwrap();
+        let flags = columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_primary_key, c.is_auto_incr, c.is_null))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flags,
+            [
+                ("id", true, true, false),
+                ("name", false, false, false),
+                ("note", false, false, true)
+            ]
+        );
+    }
+
+    fn column(data_type: &str, is_nullable: &str) -> crate::Column {
+        Column {
+            column_name: "c".to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: is_nullable.to_string(),
+            is_identity: "NO".to_string(),
+            is_generated: "NEVER".to_string(),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    #[test]
+    fn rust_types() {
+        let c = column("int4", "NO");
+        assert_eq!((c.rust_type.as_str(), c.is_null), ("i32", false));
+        let c = column("text", "NO");
+        assert_eq!((c.rust_type.as_str(), c.is_null), ("String", false));
+        // 可为空字段由模板包装为 Option<T>
+        let c = column("timestamptz", "YES");
+        assert_eq!(
+            (c.rust_type.as_str(), c.is_null),
+            ("time::OffsetDateTime", true)
+        );
+    }
+
+    #[test]
+    fn array_types() {
+        for (ty, expected) in [
+            ("_int4", "Vec<i32>"),
+            ("int4[]", "Vec<i32>"),
+            ("_text", "Vec<String>"),
+            ("text[]", "Vec<String>"),
+            ("_uuid", "Vec<uuid::Uuid>"),
+            ("uuid[]", "Vec<uuid::Uuid>"),
+            // 多维数组按嵌套的 Vec 映射
+            ("int4[][]", "Vec<Vec<i32>>"),
+        ] {
+            assert_eq!(rust_type(ty), expected, "{ty}");
+        }
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn serial_owned_sequence() {
+        let meta = setup(
+            "test_seq",
+            r#"CREATE TABLE test_seq.t (
+                id serial PRIMARY KEY,
+                "order" bigserial,
+                seq int GENERATED ALWAYS AS IDENTITY,
+                n int
+            );"#,
+        )
+        .await;
+        let columns = meta.columns("", "test_seq", "t").await.unwrap();
+        let sequences = columns
+            .iter()
+            .map(|c| c.owned_sequence.as_deref())
+            .collect::<Vec<_>>();
+        // 标识列的内部序列不计入
+        assert_eq!(
+            sequences,
+            [
+                Some("test_seq.t_id_seq"),
+                Some("test_seq.t_order_seq"),
+                None,
+                None
+            ]
+        );
+
+        let sql = meta.create_table_sql("", "test_seq", "t").await.unwrap();
+        assert!(sql.starts_with(
+            "CREATE SEQUENCE IF NOT EXISTS test_seq.t_id_seq;\nCREATE SEQUENCE IF NOT EXISTS test_seq.t_order_seq;\n"
+        ));
+        assert!(sql.contains("ALTER SEQUENCE test_seq.t_id_seq OWNED BY test_seq.t.id;"));
+        assert!(
+            sql.contains(r#"ALTER SEQUENCE test_seq.t_order_seq OWNED BY test_seq.t."order";"#)
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn current_schema() {
+        let meta = connect().await;
+        assert_eq!(meta.current_schema().await.unwrap(), "public");
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn all_columns_single_query() {
+        let sql = (0..50)
+            .map(|i| format!("CREATE TABLE test_all_columns.t{i} (id int, name text);"))
+            .collect::<String>();
+        setup("test_all_columns", &sql).await;
+        let names = (0..50).map(|i| format!("t{i}")).collect::<Vec<_>>();
+        let table_names = names.iter().map(String::as_str).collect::<Vec<_>>();
+
+        // 单个连接上执行，预备语句的执行次数即查询次数
+        let url = std::env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL");
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .unwrap();
+        let meta = PostgresMetadata::new(pool);
+        let columns = meta
+            .all_columns("", "test_all_columns", &table_names)
+            .await
+            .unwrap();
+        assert_eq!(columns.len(), 100);
+        assert_eq!(columns[0].table_name, "t0");
+        assert_eq!(columns[99].table_name, "t49");
+        let (executions,): (i64,) = sqlx::query_as(
+            "SELECT sum(generic_plans + custom_plans)::int8 FROM pg_prepared_statements
+            WHERE statement NOT LIKE '%pg_prepared_statements%'",
+        )
+        .fetch_one(&meta.0)
+        .await
+        .unwrap();
+        assert_eq!(executions, 1);
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn array_columns() {
+        let meta = setup(
+            "test_arrays",
+            "CREATE TABLE test_arrays.t (ids int4[], tags text[], keys uuid[] NOT NULL);",
+        )
+        .await;
+        let columns = meta.columns("", "test_arrays", "t").await.unwrap();
+        let types = columns
+            .iter()
+            .map(|c| c.rust_type.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(types, ["Vec<i32>", "Vec<String>", "Vec<uuid::Uuid>"]);
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn enum_labels() {
+        let meta = setup(
+            "test_enum",
+            "CREATE TYPE test_enum.order_state AS ENUM ('new', 'paid', 'shipped');
+            ALTER TYPE test_enum.order_state ADD VALUE 'pending' BEFORE 'paid';
+            CREATE TABLE test_enum.t (state test_enum.order_state NOT NULL, note text);",
+        )
+        .await;
+        let columns = meta.columns("", "test_enum", "t").await.unwrap();
+        assert_eq!(
+            columns[0].enum_values,
+            Some(
+                ["new", "pending", "paid", "shipped"]
+                    .map(String::from)
+                    .to_vec()
+            )
+        );
+        assert_eq!(columns[0].rust_type, "OrderState");
+        assert_eq!(columns[1].enum_values, None);
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn table_kinds() {
+        let meta = setup(
+            "test_table_kinds",
+            "CREATE TABLE test_table_kinds.t (id int);
+            CREATE VIEW test_table_kinds.v AS SELECT id FROM test_table_kinds.t;
+            CREATE MATERIALIZED VIEW test_table_kinds.m AS SELECT id FROM test_table_kinds.t;",
+        )
+        .await;
+        let mut kinds = meta
+            .tables("", "test_table_kinds")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.name, t.kind))
+            .collect::<Vec<_>>();
+        kinds.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            kinds,
+            [
+                ("m".to_string(), crate::TableKind::MaterializedView),
+                ("t".to_string(), crate::TableKind::BaseTable),
+                ("v".to_string(), crate::TableKind::View),
+            ]
+        );
+    }
+
+    #[test]
+    fn numeric_precision_and_scale() {
+        let price: crate::Column = Column {
+            column_name: "price".to_string(),
+            data_type: "numeric".to_string(),
+            is_nullable: "NO".to_string(),
+            is_identity: "NO".to_string(),
+            is_generated: "NEVER".to_string(),
+            numeric_precision: Some(10),
+            numeric_scale: Some(2),
+            ..Default::default()
+        }
+        .into();
+        assert_eq!((price.length, price.scale), (Some(10), Some(2)));
+        assert_eq!(price.rust_type, "bigdecimal::BigDecimal");
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn numeric_columns() {
+        let meta = setup(
+            "test_numeric",
+            "CREATE TABLE test_numeric.t (price decimal(10,2), amount numeric);",
+        )
+        .await;
+        let columns = meta.columns("", "test_numeric", "t").await.unwrap();
+        let numeric = columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.length, c.scale, c.rust_type.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            numeric,
+            [
+                ("price", Some(10), Some(2), "bigdecimal::BigDecimal"),
+                ("amount", None, None, "bigdecimal::BigDecimal"),
+            ]
+        );
+    }
+
+    #[test]
+    fn table_without_comment() {
+        let table: crate::Table = Table {
+            table_name: "users".to_string(),
+            relkind: "r".to_string(),
+            description: None,
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(table.comment, "");
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn table_comments() {
+        let meta = setup(
+            "test_table_comments",
+            "CREATE TABLE test_table_comments.a (id int);
+            CREATE TABLE test_table_comments.b (id int);
+            COMMENT ON TABLE test_table_comments.b IS '订单';",
+        )
+        .await;
+        let mut comments = meta
+            .tables("", "test_table_comments")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.name, t.comment))
+            .collect::<Vec<_>>();
+        comments.sort();
+        assert_eq!(
+            comments,
+            [
+                ("a".to_string(), String::new()),
+                ("b".to_string(), "订单".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn ordinal_position_starts_at_one() {
+        let meta = setup(
+            "test_ordinal_position",
+            "CREATE TABLE test_ordinal_position.t (a int, b text);",
+        )
+        .await;
+        let columns = meta
+            .columns("", "test_ordinal_position", "t")
+            .await
+            .unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| c.ordinal_position)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        let columns = meta
+            .all_columns("", "test_ordinal_position", &["t"])
+            .await
+            .unwrap();
+        assert_eq!(columns[0].ordinal_position, 1);
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn stored_generated_column() {
+        let meta = setup(
+            "test_generated",
+            "CREATE TABLE test_generated.t (
+                price int,
+                total int GENERATED ALWAYS AS (price * 2) STORED,
+                seq int GENERATED ALWAYS AS IDENTITY
+            );",
+        )
+        .await;
+        let columns = meta.columns("", "test_generated", "t").await.unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| (c.name.as_str(), c.is_generated))
+                .collect::<Vec<_>>(),
+            [("price", false), ("total", true), ("seq", false)]
+        );
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn function_and_procedure() {
+        let meta = setup(
+            "test_routines",
+            "CREATE FUNCTION test_routines.add_one(n integer) RETURNS integer
+                LANGUAGE sql AS 'SELECT n + 1';
+            CREATE PROCEDURE test_routines.reset(id integer, INOUT total integer)
+                LANGUAGE sql AS 'SELECT id';
+            CREATE AGGREGATE test_routines.total(integer) (SFUNC = int4pl, STYPE = integer);",
+        )
+        .await;
+        let routines = meta.routines("", "test_routines").await.unwrap();
+        let names = routines.iter().map(|r| r.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["add_one", "reset"]);
+
+        assert_eq!(routines[0].kind, crate::RoutineKind::Function);
+        assert_eq!(routines[0].return_type.as_deref(), Some("integer"));
+        let parameters = routines[0]
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.mode.as_str(), p.r#type.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(parameters, [("n", "IN", "integer")]);
+
+        assert_eq!(routines[1].kind, crate::RoutineKind::Procedure);
+        assert_eq!(routines[1].return_type, None);
+        let modes = routines[1]
+            .parameters
+            .iter()
+            .map(|p| p.mode.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(modes, ["IN", "INOUT"]);
+    }
+
+    #[cfg(feature = "integration")]
+    #[tokio::test]
+    async fn column_collation() {
+        let meta = setup(
+            "test_collation",
+            r#"CREATE TABLE test_collation.t (name varchar(32), code varchar(32) COLLATE "C");"#,
+        )
+        .await;
+        let columns = meta.columns("", "test_collation", "t").await.unwrap();
+        assert_eq!(columns[0].collation, None);
+        assert_eq!(columns[1].collation.as_deref(), Some("C"));
+    }
+}
@@ -1,8 +1,20 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use sqlx::{AnyPool, FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool};
 
-use super::{DatabaseMetadata, Result};
+use super::{ColumnType, DatabaseMetadata, Error, Result};
+
+/// 表和视图，排除 sqlite_ 开头的内部表
+const SELECT_TABLES: &str = "SELECT type, name, tbl_name, rootpage, sql FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' ORDER BY name";
+/// 表的索引，每个索引列一行；表达式索引中的表达式列没有列名，不返回
+const SELECT_INDEXS: &str = "SELECT il.name AS key_name, il.\"unique\" AS is_unique, il.origin, ii.seqno, ii.name AS column_name FROM pragma_index_list(?1, COALESCE(NULLIF(?2, ''), 'main')) il JOIN pragma_index_info(il.name, COALESCE(NULLIF(?2, ''), 'main')) ii WHERE ii.name IS NOT NULL ORDER BY il.name, ii.seqno";
+/// 表的列，pk 为列在主键中的位置（从1开始），非主键列为0
+/// pragma_table_xinfo 比 pragma_table_info 多出生成列，排除虚拟表的隐藏列
+pub(crate) const SELECT_COLUMNS: &str = "SELECT cid, name, type, \"notnull\", dflt_value, pk, hidden FROM pragma_table_xinfo(?1, COALESCE(NULLIF(?2, ''), 'main')) WHERE hidden <> 1 ORDER BY cid";
+/// 表的外键，组合外键每列一行，未指定引用列时 to 为 NULL，引用主键
+const SELECT_FOREIGN_KEYS: &str = "SELECT id, \"table\" AS referenced_table, \"from\" AS column_name, \"to\" AS referenced_column, on_delete, on_update FROM pragma_foreign_key_list(?1, COALESCE(NULLIF(?2, ''), 'main')) ORDER BY id, seq";
+/// 主键索引名称，与 Mysql 一致
+const PRIMARY_KEY_NAME: &str = "PRIMARY";
 
 pub struct SqliteMetadata(SqlitePool);
 
@@ -22,33 +34,136 @@ struct Table {
     sql: Option<String>,
 }
 
+impl Table {
+    fn into_table(self, schema: &str) -> super::Table {
+        super::Table {
+            schema: schema.to_string(),
+            name: self.name,
+            // Sqlite 不支持表注释
+            comment: String::new(),
+            collation: None,
+            kind: if self.r#type == "view" {
+                super::TableKind::View
+            } else {
+                super::TableKind::BaseTable
+            },
+        }
+    }
+}
+
 /// 表列信息
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
-struct TableColumn {
+pub(crate) struct TableColumn {
     /// 列ID
-    cid: Option<u32>,
+    pub(crate) cid: Option<i32>,
     /// 列名
-    name: String,
+    pub(crate) name: String,
     /// 类型：如：varchar(50)  int
-    r#type: Option<String>,
+    pub(crate) r#type: Option<String>,
     /// 是否为空：1-不为空，0-为空
-    notnull: Option<u8>,
-    dflt_value: Option<String>,
+    pub(crate) notnull: Option<u8>,
+    pub(crate) dflt_value: Option<String>,
     /// 是否为主键：1-主键，0-非主键
-    pk: Option<u8>,
+    pub(crate) pk: Option<u8>,
+    /// 0-普通列，1-虚拟表的隐藏列，2-虚拟生成列，3-存储生成列
+    pub(crate) hidden: Option<u8>,
+}
+
+/// 索引信息来自 PRAGMA index_list 和 index_info
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct Index {
+    /// 索引的名称，约束自动创建的索引名为 sqlite_autoindex_表名_序号
+    key_name: String,
+    /// 是否唯一：1-唯一，0-不唯一
+    is_unique: i64,
+    /// 索引的来源：c-CREATE INDEX，u-UNIQUE 约束，pk-PRIMARY KEY 约束
+    origin: String,
+    /// 列在索引中的位置 (从0开始)
+    seqno: i64,
+    /// 列名
+    column_name: String,
+}
+
+/// 外键信息来自 PRAGMA foreign_key_list
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct ForeignKey {
+    /// 外键序号，组合外键的各列序号相同
+    id: i64,
+    /// 引用的表名
+    referenced_table: String,
+    /// 外键列
+    column_name: String,
+    /// 引用的列
+    referenced_column: Option<String>,
+    /// 删除动作
+    on_delete: String,
+    /// 更新动作
+    on_update: String,
+}
+
+impl Index {
+    fn into_index(self, table_name: &str) -> super::Index {
+        // 主键约束自动创建的索引统一命名为 PRIMARY，以便与其他数据库一致地识别主键
+        let key_name = if self.origin == "pk" {
+            PRIMARY_KEY_NAME.to_string()
+        } else {
+            self.key_name
+        };
+        super::Index {
+            table_name: table_name.to_string(),
+            non_unique: (self.is_unique == 0) as i32,
+            key_name,
+            seq_in_index: self.seqno as u32 + 1,
+            column_name: self.column_name,
+            sub_part: None,
+            // Sqlite 的索引均为 B 树
+            index_type: "BTREE".to_string(),
+            index_comment: String::new(),
+        }
+    }
+}
+
+impl TableColumn {
+    /// autoincrement 为建表语句中是否声明了 AUTOINCREMENT
+    pub(crate) fn into_column(
+        self,
+        schema: &str,
+        table_name: &str,
+        autoincrement: bool,
+    ) -> super::Column {
+        let (ty, length) = sqlite_type(self.r#type.as_deref().unwrap_or_default());
+        let is_primary_key = self.pk.unwrap_or_default() > 0;
+        super::Column {
+            database: schema.to_string(),
+            schema: schema.to_string(),
+            table_name: table_name.to_string(),
+            name: self.name,
+            // cid 从 0 开始
+            ordinal_position: self.cid.unwrap_or_default() + 1,
+            rust_type: t2t(&ty).to_string(),
+            r#type: Some(ColumnType::from(ty)),
+            length: length.map(i32::from),
+            default_value: super::DefaultValue::classify(
+                self.dflt_value.as_deref(),
+                super::Driver::Sqlite,
+            ),
+            default: self.dflt_value,
+            is_null: self.notnull.unwrap_or_default() == 0 && !is_primary_key,
+            // 只有 INTEGER PRIMARY KEY 可以声明 AUTOINCREMENT
+            is_auto_incr: autoincrement && is_primary_key,
+            is_primary_key,
+            is_generated: matches!(self.hidden, Some(2 | 3)),
+            ..Default::default()
+        }
+    }
 }
 
 /// Rust type             SQLite type(s)
 /// bool                    BOOLEAN
-/// i8                      INTEGER
-/// i16                     INTEGER
-/// i32                     INTEGER
-/// i64                     BIGINT, INT8
-/// u8                      INTEGER
-/// u16                     INTEGER
-/// u32                     INTEGER
-/// f32                     REAL
+/// i64                     INTEGER, BIGINT, INT8
 /// f64                     REAL
 /// &str, String            TEXT
 /// &[u8], Vec<u8>          BLOB
@@ -59,17 +174,27 @@ struct TableColumn {
 /// time::Time              TIME
 ///
 /// Sqlite类型转换为Rust类型
-fn t2t(ty: &str) -> &str {
-    match ty.to_uppercase().as_str() {
-        "BOOLEAN" => "bool",
-        "INTEGER" => "i32",
-        "BIGINT" | "INT8" => "i64",
-        "REAL" => "f64",
-        "BLOB" => "Vec<u8>",
+///
+/// 除布尔和日期时间类型外，按 Sqlite 的类型亲和性规则依次判断声明的类型：
+/// - 包含 INT：INTEGER 亲和性，i64
+/// - 包含 CHAR、CLOB 或 TEXT：TEXT 亲和性，String
+/// - 包含 BLOB 或未声明类型：BLOB 亲和性，Vec<u8>
+/// - 包含 REAL、FLOA 或 DOUB：REAL 亲和性，f64
+/// - 其他：NUMERIC 亲和性，f64
+///
+/// 可为空的列由模板根据 is_null 包装为 Option
+pub(crate) fn t2t(ty: &str) -> &'static str {
+    let ty = ty.to_uppercase();
+    match ty.trim() {
+        "BOOLEAN" | "BOOL" => "bool",
         "DATE" => "time::Date",
         "TIME" => "time::Time",
         "DATETIME" => "time::OffsetDateTime",
-        _ => "String",
+        ty if ty.contains("INT") => "i64",
+        ty if ["CHAR", "CLOB", "TEXT"].iter().any(|t| ty.contains(t)) => "String",
+        ty if ty.is_empty() || ty.contains("BLOB") => "Vec<u8>",
+        ty if ["REAL", "FLOA", "DOUB"].iter().any(|t| ty.contains(t)) => "f64",
+        _ => "f64",
     }
 }
 
@@ -93,9 +218,34 @@ impl SqliteMetadata {
     pub fn new(pool: SqlitePool) -> Self {
         Self(pool)
     }
+
+    /// 主键列的定义中是否声明了 AUTOINCREMENT，解析建表语句，注释、字符串和列名中的 AUTOINCREMENT 不算
+    async fn declares_autoincrement(
+        &self,
+        database: &str,
+        schema: &str,
+        table_name: &str,
+    ) -> Result<bool> {
+        let sql = self.create_table_sql(database, schema, table_name).await?;
+        // 方言不支持的建表语句无法解析，视为未声明
+        let Ok(dump) = super::DumpMetadata::new(&sql, super::Driver::Sqlite) else {
+            return Ok(false);
+        };
+        Ok(dump
+            .columns(database, schema, table_name)
+            .await
+            .is_ok_and(|columns| columns.iter().any(|c| c.is_primary_key && c.is_auto_incr)))
+    }
 }
 
 impl DatabaseMetadata for SqliteMetadata {
+    fn ping(&self) -> super::BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            sqlx::query("SELECT 1").execute(&self.0).await?;
+            Ok(())
+        })
+    }
+
     fn databases(&self) -> super::BoxFuture<'_, Result<Vec<super::Database>>> {
         todo!()
     }
@@ -104,12 +254,23 @@ impl DatabaseMetadata for SqliteMetadata {
         todo!()
     }
 
+    fn current_schema(&self) -> super::BoxFuture<'_, Result<String>> {
+        Box::pin(async move { Ok("main".to_string()) })
+    }
+
     fn tables<'a>(
         &'a self,
-        database: &'a str,
-        schema: &'a str,
+        _database: &'a str,
+        _schema: &'a str,
     ) -> super::BoxFuture<'a, Result<Vec<super::Table>>> {
-        todo!()
+        Box::pin(async move {
+            // 只查询 main 中的表，附加数据库的表不包含在内
+            let rows: Vec<Table> = sqlx::query_as(SELECT_TABLES).fetch_all(&self.0).await?;
+            Ok(rows
+                .into_iter()
+                .map(|row| row.into_table("main"))
+                .collect::<Vec<_>>())
+        })
     }
 
     fn columns<'a>(
@@ -118,7 +279,31 @@ impl DatabaseMetadata for SqliteMetadata {
         schema: &'a str,
         table_name: &'a str,
     ) -> super::BoxFuture<'a, Result<Vec<super::Column>>> {
-        todo!()
+        Box::pin(async move {
+            let rows: Vec<TableColumn> = sqlx::query_as(SELECT_COLUMNS)
+                .bind(table_name)
+                .bind(schema)
+                .fetch_all(&self.0)
+                .await?;
+            let schema = if schema.is_empty() { "main" } else { schema };
+            // 只有单列的 INTEGER PRIMARY KEY 可以声明 AUTOINCREMENT，其他表不需要读取建表语句
+            let mut pks = rows.iter().filter(|c| c.pk.unwrap_or_default() > 0);
+            let integer_pk = match (pks.next(), pks.next()) {
+                (Some(pk), None) => pk
+                    .r#type
+                    .as_deref()
+                    .is_some_and(|t| t.eq_ignore_ascii_case("INTEGER")),
+                _ => false,
+            };
+            let autoincrement = integer_pk
+                && self
+                    .declares_autoincrement(database, schema, table_name)
+                    .await?;
+            Ok(rows
+                .into_iter()
+                .map(|row| row.into_column(schema, table_name, autoincrement))
+                .collect::<Vec<_>>())
+        })
     }
 
     fn indexs<'a>(
@@ -127,16 +312,115 @@ impl DatabaseMetadata for SqliteMetadata {
         schema: &'a str,
         table_name: &'a str,
     ) -> super::BoxFuture<'a, Result<Vec<super::Index>>> {
-        todo!()
+        Box::pin(async move {
+            let rows: Vec<Index> = sqlx::query_as(SELECT_INDEXS)
+                .bind(table_name)
+                .bind(schema)
+                .fetch_all(&self.0)
+                .await?;
+            Ok(rows
+                .into_iter()
+                .map(|row| row.into_index(table_name))
+                .collect::<Vec<_>>())
+        })
     }
 
-    fn create_table_sql<'a>(
+    fn foreign_keys<'a>(
         &'a self,
         database: &'a str,
         schema: &'a str,
         table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::ForeignKey>>> {
+        Box::pin(async move {
+            let rows: Vec<ForeignKey> = sqlx::query_as(SELECT_FOREIGN_KEYS)
+                .bind(table_name)
+                .bind(schema)
+                .fetch_all(&self.0)
+                .await?;
+            let mut foreign_keys: Vec<super::ForeignKey> = vec![];
+            for row in rows {
+                // 未指定引用列时引用表的主键，按列在外键中的位置取主键列
+                let referenced_column = match row.referenced_column {
+                    Some(column) => column,
+                    None => {
+                        let position = foreign_keys
+                            .iter()
+                            .filter(|fk| fk.name == row.id.to_string())
+                            .count();
+                        self.columns(database, schema, &row.referenced_table)
+                            .await?
+                            .into_iter()
+                            .filter(|c| c.is_primary_key)
+                            .nth(position)
+                            .map(|c| c.name)
+                            .unwrap_or_default()
+                    }
+                };
+                foreign_keys.push(super::ForeignKey {
+                    table_name: table_name.to_string(),
+                    // 先以序号合并组合外键
+                    name: row.id.to_string(),
+                    columns: vec![row.column_name],
+                    referenced_table: row.referenced_table,
+                    referenced_columns: vec![referenced_column],
+                    on_delete: Some(row.on_delete),
+                    on_update: Some(row.on_update),
+                });
+            }
+            // Sqlite 的外键没有名称，使用 Postgres 的默认命名 表名_列名_fkey
+            Ok(super::merge_foreign_keys(foreign_keys)
+                .into_iter()
+                .map(|mut fk| {
+                    fk.name = format!("{table_name}_{}_fkey", fk.columns.join("_"));
+                    fk
+                })
+                .collect())
+        })
+    }
+
+    fn create_table_sql<'a>(
+        &'a self,
+        _database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
     ) -> super::BoxFuture<'a, Result<String>> {
-        todo!()
+        Box::pin(async move {
+            // 各模式（main、temp 及附加的数据库）的 sqlite_master 保存建表时的原始语句
+            let schema = if schema.is_empty() { "main" } else { schema };
+            let sql: Option<(Option<String>,)> = sqlx::query_as(&format!(
+                "SELECT sql FROM \"{}\".sqlite_master WHERE type = 'table' AND name = ?",
+                schema.replace('"', "\"\"")
+            ))
+            .bind(table_name)
+            .fetch_optional(&self.0)
+            .await?;
+            sql.and_then(|(sql,)| sql)
+                .ok_or(Error::E("table not found"))
+        })
+    }
+
+    fn check_constraints<'a>(
+        &'a self,
+        database: &'a str,
+        schema: &'a str,
+        table_name: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::CheckConstraint>>> {
+        // Sqlite 没有检查约束的系统表，解析建表语句获取
+        Box::pin(async move {
+            let sql = self.create_table_sql(database, schema, table_name).await?;
+            super::DumpMetadata::new(&sql, super::Driver::Sqlite)?
+                .check_constraints(database, schema, table_name)
+                .await
+        })
+    }
+
+    fn routines<'a>(
+        &'a self,
+        _database: &'a str,
+        _schema: &'a str,
+    ) -> super::BoxFuture<'a, Result<Vec<super::Routine>>> {
+        // Sqlite 没有存储过程和函数
+        Box::pin(async move { Ok(vec![]) })
     }
 }
 
@@ -187,3 +471,288 @@ impl DatabaseMetadata for SqliteMetadata {
 //     }
 //     Ok(cols)
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// 在内存数据库中执行 sql
+    async fn setup(sql: &str) -> SqliteMetadata {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(sql).execute(&pool).await.unwrap();
+        SqliteMetadata::new(pool)
+    }
+
+    #[tokio::test]
+    async fn current_schema() {
+        let meta = setup("").await;
+        assert_eq!(meta.current_schema().await.unwrap(), "main");
+    }
+
+    #[tokio::test]
+    async fn composite_unique_index() {
+        let meta = setup(
+            "CREATE TABLE t (a INTEGER, b TEXT, c TEXT, d TEXT PRIMARY KEY, UNIQUE (c));
+            CREATE UNIQUE INDEX t_b_a ON t (b, a);
+            CREATE INDEX t_c ON t (c);",
+        )
+        .await;
+        let indexs = meta.indexs("", "", "t").await.unwrap();
+        let rows = indexs
+            .iter()
+            .map(|i| {
+                (
+                    i.key_name.as_str(),
+                    i.column_name.as_str(),
+                    i.seq_in_index,
+                    i.non_unique,
+                    i.index_type.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // 主键约束创建的索引命名为 PRIMARY，UNIQUE 约束的索引保留自动生成的名称
+        assert_eq!(
+            rows,
+            [
+                ("PRIMARY", "d", 1, 0, "BTREE"),
+                ("sqlite_autoindex_t_2", "c", 1, 0, "BTREE"),
+                ("t_b_a", "b", 1, 0, "BTREE"),
+                ("t_b_a", "a", 2, 0, "BTREE"),
+                ("t_c", "c", 1, 1, "BTREE"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn create_table_sql_round_trip() {
+        let ddl = "CREATE TABLE t (\n    id INTEGER PRIMARY KEY AUTOINCREMENT,\n    name VARCHAR(10) NOT NULL DEFAULT 'x'\n)";
+        let meta = setup(ddl).await;
+        let sql = meta.create_table_sql("", "", "t").await.unwrap();
+        assert_eq!(sql, ddl);
+        let before = format!("{:?}", meta.columns("", "", "t").await.unwrap());
+        sqlx::raw_sql(&format!("DROP TABLE t; {sql}"))
+            .execute(&meta.0)
+            .await
+            .unwrap();
+        let after = format!("{:?}", meta.columns("", "", "t").await.unwrap());
+        assert_eq!(after, before);
+        assert!(meta.create_table_sql("", "", "missing").await.is_err());
+    }
+
+    /// 附加内存数据库 aux 后执行 sql，附加只对当前连接有效，连接池只保留一个连接
+    async fn setup_attached(sql: &str) -> SqliteMetadata {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(&format!("ATTACH DATABASE ':memory:' AS aux; {sql}"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        SqliteMetadata::new(pool)
+    }
+
+    #[tokio::test]
+    async fn create_table_sql_in_attached_schema() {
+        let meta = setup_attached(
+            "CREATE TABLE aux.t (id INTEGER PRIMARY KEY, note TEXT);
+            CREATE TABLE t (id INTEGER PRIMARY KEY);",
+        )
+        .await;
+        let sql = meta.create_table_sql("", "aux", "t").await.unwrap();
+        assert!(sql.contains("note TEXT"));
+        let sql = meta.create_table_sql("", "", "t").await.unwrap();
+        assert!(!sql.contains("note TEXT"));
+        assert!(meta.create_table_sql("", "aux", "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn autoincrement_primary_key() {
+        let meta = setup(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL DEFAULT 'x', note TEXT)",
+        )
+        .await;
+        let columns = meta.columns("", "", "t").await.unwrap();
+        let flags = columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.is_primary_key,
+                    c.is_auto_incr,
+                    c.is_null,
+                    c.default.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flags,
+            [
+                ("id", true, true, false, None),
+                ("name", false, false, false, Some("'x'")),
+                ("note", false, false, true, None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn autoincrement_in_attached_schema() {
+        let meta = setup_attached(
+            "CREATE TABLE aux.t (id INTEGER PRIMARY KEY AUTOINCREMENT);
+            CREATE TABLE t (id INTEGER PRIMARY KEY);",
+        )
+        .await;
+        let columns = meta.columns("", "aux", "t").await.unwrap();
+        assert!(columns[0].is_auto_incr);
+        let columns = meta.columns("", "", "t").await.unwrap();
+        assert!(!columns[0].is_auto_incr);
+    }
+
+    #[tokio::test]
+    async fn autoincrement_only_as_keyword() {
+        let meta = setup(
+            "CREATE TABLE t (
+                -- 不使用 AUTOINCREMENT
+                id INTEGER PRIMARY KEY,
+                \"autoincrement\" TEXT DEFAULT 'AUTOINCREMENT'
+            )",
+        )
+        .await;
+        let columns = meta.columns("", "", "t").await.unwrap();
+        assert!(!columns[0].is_auto_incr);
+    }
+
+    #[tokio::test]
+    async fn composite_primary_key() {
+        let meta = setup("CREATE TABLE t (a INTEGER, b TEXT, c TEXT, PRIMARY KEY (b, a))").await;
+        let columns = meta.columns("", "", "t").await.unwrap();
+        let flags = columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_primary_key, c.is_auto_incr, c.is_null))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flags,
+            [
+                ("a", true, false, false),
+                ("b", true, false, false),
+                ("c", false, false, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn type_affinity() {
+        for (ty, rust_type) in [
+            ("VARCHAR", "String"),
+            ("BIGINT", "i64"),
+            ("UNSIGNED BIG INT", "i64"),
+            ("DOUBLE", "f64"),
+            ("FLOAT", "f64"),
+            ("BOOLEAN", "bool"),
+            ("CLOB", "String"),
+            ("BLOB", "Vec<u8>"),
+            ("", "Vec<u8>"),
+            ("NUMERIC", "f64"),
+        ] {
+            assert_eq!(t2t(ty), rust_type, "{ty}");
+        }
+    }
+
+    #[tokio::test]
+    async fn declared_types() {
+        let meta = setup(
+            "CREATE TABLE t (name VARCHAR(10) NOT NULL, n BIGINT, d DOUBLE NOT NULL, b BOOLEAN)",
+        )
+        .await;
+        let columns = meta.columns("", "", "t").await.unwrap();
+        let types = columns
+            .iter()
+            .map(|c| (c.rust_type.as_str(), c.length, c.is_null))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                ("String", Some(10), false),
+                ("i64", None, true),
+                ("f64", None, false),
+                ("bool", None, true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn view_kind() {
+        let meta = setup("CREATE TABLE t (id INTEGER); CREATE VIEW v AS SELECT id FROM t;").await;
+        let kinds = meta
+            .tables("", "")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.name, t.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                ("t".to_string(), crate::TableKind::BaseTable),
+                ("v".to_string(), crate::TableKind::View),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn ordinal_position_starts_at_one() {
+        let meta = setup("CREATE TABLE t (a INTEGER, b TEXT)").await;
+        let columns = meta.columns("", "", "t").await.unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| c.ordinal_position)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn generated_columns() {
+        let meta = setup(
+            "CREATE TABLE t (
+                price INTEGER,
+                total INTEGER GENERATED ALWAYS AS (price * 2) STORED,
+                half INTEGER AS (price / 2)
+            )",
+        )
+        .await;
+        let columns = meta.columns("", "", "t").await.unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| (c.name.as_str(), c.is_generated))
+                .collect::<Vec<_>>(),
+            [("price", false), ("total", true), ("half", true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_routines() {
+        let meta = setup("CREATE TABLE t (id INTEGER)").await;
+        assert!(meta.routines("", "").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn ping_closed_pool() {
+        let meta = setup("").await;
+        meta.ping().await.unwrap();
+        meta.0.close().await;
+        assert!(meta.ping().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn no_collation() {
+        let meta = setup("CREATE TABLE t (name TEXT COLLATE NOCASE)").await;
+        let columns = meta.columns("", "", "t").await.unwrap();
+        assert_eq!(columns[0].charset, None);
+        assert_eq!(columns[0].collation, None);
+    }
+}
@@ -0,0 +1,63 @@
+//! 列类型别名
+//!
+//! 多个表中同名且类型相同的列（如各表的主键 id）生成 `pub type Id = i64;` 形式的别名，
+//! 模型中的字段引用别名，修改类型时只需修改一处
+
+use std::collections::BTreeMap;
+
+use database::Column;
+use heck::ToUpperCamelCase as _;
+use serde::Serialize;
+
+/// 类型别名
+#[derive(Debug, Serialize)]
+pub struct TypeAlias {
+    /// 别名，列名的大驼峰形式
+    pub name: String,
+    /// 实际的 Rust 类型
+    pub rust_type: String,
+    /// 使用该别名的列，格式为 表名.列名
+    pub columns: Vec<String>,
+}
+
+/// 找出在至少两个表中出现、且 Rust 类型相同的列，生成类型别名
+///
+/// String 类型不生成别名；与模型结构体同名的别名会被跳过，避免命名冲突
+pub fn type_aliases(columns: &[Column], struct_names: &[String]) -> Vec<TypeAlias> {
+    let mut groups: BTreeMap<&str, Vec<&Column>> = BTreeMap::new();
+    for column in columns.iter() {
+        groups.entry(&column.name).or_default().push(column);
+    }
+
+    groups
+        .into_iter()
+        .filter_map(|(name, columns)| {
+            let rust_type = &columns.first()?.rust_type;
+            let alias = name.to_upper_camel_case();
+            // 枚举列的类型是生成的枚举，不生成别名
+            let repeated = columns.len() > 1
+                && rust_type != "String"
+                && !rust_type.is_empty()
+                && columns.iter().all(|c| c.enum_values.is_none())
+                && columns.iter().all(|c| &c.rust_type == rust_type);
+            (repeated && !struct_names.contains(&alias)).then(|| TypeAlias {
+                name: alias,
+                rust_type: rust_type.clone(),
+                columns: columns
+                    .iter()
+                    .map(|c| format!("{}.{}", c.table_name, c.name))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// 将列的 Rust 类型替换为对应的别名
+pub fn apply(aliases: &[TypeAlias], columns: &mut [Column]) {
+    for column in columns.iter_mut() {
+        let key = format!("{}.{}", column.table_name, column.name);
+        if let Some(alias) = aliases.iter().find(|a| a.columns.contains(&key)) {
+            column.rust_type = alias.name.clone();
+        }
+    }
+}
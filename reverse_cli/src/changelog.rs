@@ -0,0 +1,97 @@
+//! 结构变更日志
+//!
+//! 将数据库当前结构与生成清单中记录的结构之间的差异渲染为 Markdown，可作为 CHANGELOG.md 的片段
+
+use database::{Column, Table};
+
+use crate::{
+    drift::{self, ColumnChange},
+    manifest::{ColumnSignature, Manifest},
+};
+
+/// 当前结构相对生成清单的变化，每项为一行 Markdown 列表
+pub fn changes(manifest: &Manifest, tables: &[Table], columns: &[Column]) -> Vec<String> {
+    let mut lines = vec![];
+    for table in tables
+        .iter()
+        .filter(|t| !manifest.tables.contains_key(&t.name))
+    {
+        lines.push(format!("- 新增表 `{}`", table.name));
+    }
+    for drift in drift::detect(manifest, columns) {
+        if !tables.iter().any(|t| t.name == drift.table) {
+            lines.push(format!("- 删除表 `{}`", drift.table));
+            continue;
+        }
+        for column in drift.added.iter() {
+            lines.push(format!("- 表 `{}` 新增列 `{column}`", drift.table));
+        }
+        for column in drift.removed.iter() {
+            lines.push(format!("- 表 `{}` 删除列 `{column}`", drift.table));
+        }
+        for change in drift.changed.iter() {
+            lines.push(format!(
+                "- 列 `{}.{}` {}",
+                drift.table,
+                change.new.name,
+                describe(change)
+            ));
+        }
+    }
+    lines
+}
+
+/// 生成变更日志，没有变化时返回 None
+///
+/// sections 为 (模式名, 变化)，模式名为空时不输出模式标题
+pub fn markdown(sections: &[(String, Vec<String>)]) -> Option<String> {
+    let sections = sections
+        .iter()
+        .filter(|(_, lines)| !lines.is_empty())
+        .map(|(schema, lines)| match schema.as_str() {
+            "" => lines.join("\n"),
+            schema => format!("### `{schema}`\n\n{}", lines.join("\n")),
+        })
+        .collect::<Vec<_>>();
+    if sections.is_empty() {
+        return None;
+    }
+    Some(format!("## 数据库结构变更\n\n{}\n", sections.join("\n\n")))
+}
+
+/// 描述列的变化，如 `类型由 INT 变更为 BIGINT，改为可为空`
+fn describe(change: &ColumnChange) -> String {
+    let ColumnChange { old, new } = change;
+    let mut parts = vec![];
+    if old.r#type != new.r#type || old.length != new.length {
+        parts.push(format!(
+            "类型由 `{}` 变更为 `{}`",
+            type_name(old),
+            type_name(new)
+        ));
+    }
+    if old.is_null != new.is_null {
+        let change = if new.is_null {
+            "改为可为空"
+        } else {
+            "改为非空"
+        };
+        parts.push(change.to_string());
+    }
+    if old.is_primary_key != new.is_primary_key {
+        let change = if new.is_primary_key {
+            "设为主键"
+        } else {
+            "取消主键"
+        };
+        parts.push(change.to_string());
+    }
+    parts.join("，")
+}
+
+fn type_name(column: &ColumnSignature) -> String {
+    match column.length {
+        Some(length) => format!("{}({length})", column.r#type),
+        None => column.r#type.clone(),
+    }
+}
@@ -3,17 +3,31 @@ use std::{
     fs::{self, File},
     io::Write,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use anyhow::anyhow;
 use clap::{Parser, Subcommand};
-use database::{Column, Driver, Table, database_metadata};
+use database::{
+    Column, ConnectOptions, DatabaseMetadata, DateTimeBackend, DecimalBackend, Driver,
+    DumpMetadata, SslMode, Table, database_metadata_retry_with,
+};
 use heck::ToUpperCamelCase as _;
 use rust_embed::Embed;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use tera::Tera;
 
-use crate::template::{ERROR_TEMPLATE, RESULT_TEMPLATE};
+use crate::{
+    inflect,
+    template::{ERROR_TEMPLATE, RESULT_TEMPLATE},
+};
+
+/// 连接重试的初始等待时间，之后每次翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// database_url 引用环境变量的前缀，如 `env:DATABASE_URL`
+const ENV_PREFIX: &str = "env:";
+/// database_url 为空时读取的环境变量
+const DATABASE_URL_ENV: &str = "DATABASE_URL";
 
 /// 支持的编程语言
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,23 +35,94 @@ use crate::template::{ERROR_TEMPLATE, RESULT_TEMPLATE};
 pub enum Language {
     Rust,
     Java,
+    /// sea-orm 实体
+    SeaOrm,
+    /// diesel 的 schema.rs
+    Diesel,
+    /// protobuf 消息
+    Proto,
+}
+
+impl Language {
+    /// 生成文件的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::Rust | Language::SeaOrm | Language::Diesel => "rs",
+            Language::Java => "java",
+            Language::Proto => "proto",
+        }
+    }
+
+    /// 根据文件名（不含扩展名）生成完整的文件名
+    pub fn file_name(&self, stem: &str) -> String {
+        format!("{stem}.{}", self.extension())
+    }
 }
 
 /// 代码生成器配置
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GeneratorConfig {
-    /// 数据源
+    /// 数据源，`env:变量名` 时从环境变量读取，为空时读取环境变量 DATABASE_URL
+    #[serde(default)]
     pub database_url: String,
-    /// 数据库模式
+    /// Unix 域套接字路径，设置后通过套接字连接 Mysql 或 Postgres
+    #[serde(default)]
+    pub socket: Option<PathBuf>,
+    /// 单条查询的超时时间（毫秒），由数据库服务端中止超时的查询，Sqlite 不支持
+    #[serde(default)]
+    pub statement_timeout: Option<u64>,
+    /// 连接池的最大连接数
+    #[serde(default)]
+    pub max_connections: Option<u32>,
+    /// 连接池保持的最小连接数，连接池创建后会预先建立这些连接
+    #[serde(default)]
+    pub min_connections: Option<u32>,
+    /// 获取数据库连接的超时时间（毫秒），数据库不可达时在该时间后报错
+    #[serde(default)]
+    pub acquire_timeout: Option<u64>,
+    /// TLS 连接模式，设置后覆盖 database_url 中的 sslmode 或 ssl-mode
+    #[serde(default)]
+    pub ssl_mode: Option<SslMode>,
+    /// 校验服务端证书使用的 CA 证书路径
+    #[serde(default)]
+    pub ssl_root_cert: Option<PathBuf>,
+    /// 连接失败时的重试次数，按指数退避等待，数据库尚未就绪时使用
+    #[serde(default)]
+    pub retry_attempts: u32,
+    /// SQL 结构转储文件，设置后从文件中解析表结构，不再连接数据库，
+    /// SQL 方言由 database_url 的协议决定
+    #[serde(default)]
+    pub dump_file: Option<PathBuf>,
+    /// 数据库模式，可以配置多个，多个模式时每个模式的代码生成到 path 下以模式名命名的子目录，
+    /// 未配置时使用连接的当前模式
+    #[serde(default, rename = "schema", deserialize_with = "one_or_many")]
+    pub schemas: Vec<String>,
+    /// 当前处理的模式，为 schemas 中的一个
+    #[serde(skip)]
     pub schema: String,
     /// 编程语言
     pub language: Language,
     /// 指定要生成代码的表名，为空表示全部
     pub table_names: Vec<String>,
+    /// 表名列表文件，每行一个表名，忽略空行和 # 开头的注释，与 table_names 合并
+    #[serde(default)]
+    pub table_names_file: Option<PathBuf>,
     /// 忽略的表名
     pub ignore_tables: Vec<String>,
     /// 忽略表名前缀
     pub ignore_table_prefix: Option<String>,
+    /// 是否为视图、物化视图及外部表生成代码，默认开启
+    #[serde(default = "default_true")]
+    pub include_views: bool,
+    /// 生成结构体名时去掉的表名前缀，如 `t_` 时表 `t_user` 生成 `User`
+    #[serde(default)]
+    pub strip_table_prefix: Option<String>,
+    /// 生成结构体名时是否将表名转为单数，如表 `users` 生成 `User`，SQL 中的表名不变
+    #[serde(default)]
+    pub singularize_struct_names: bool,
+    /// 结构体名和模块名（文件名）的最大长度，超出时截断并追加表名的哈希，避免截断后重名
+    #[serde(default)]
+    pub max_name_length: Option<usize>,
     /// 代码生成的路径
     pub path: PathBuf,
     /// 是否覆盖
@@ -57,19 +142,191 @@ pub struct GeneratorConfig {
     pub gen_service: bool,
     /// 是否生成 Controller 文件
     pub gen_controller: bool,
+    /// 是否生成新增数据使用的 New 模型，非空且有默认值的字段在 New 模型中可为空
+    #[serde(default)]
+    pub gen_new_model: bool,
+    /// 是否为有主键的表生成按主键查询、新增、修改、删除的方法，占位符按驱动生成
+    #[serde(default)]
+    pub gen_crud: bool,
+    /// 是否为有主键或唯一键的表生成 upsert 方法，冲突目标取主键或唯一键
+    #[serde(default)]
+    pub gen_upsert: bool,
+    /// 是否在外键字段的注释中标注引用的表和列
+    #[serde(default)]
+    pub foreign_key_hints: bool,
+    /// 是否在模型结构体的注释中列出表的索引
+    #[serde(default)]
+    pub index_docs: bool,
+    /// 是否根据字段约束生成 validator 校验注解：字符串长度生成 length，检查约束中列与数值的比较生成 range
+    #[serde(default)]
+    pub gen_validator: bool,
+    /// 是否为多个表中重复的列类型生成 types.rs 类型别名，如 `pub type Id = i64;`
+    #[serde(default)]
+    pub gen_type_aliases: bool,
+    /// 是否生成 deps.toml，列出生成代码所需的依赖及 sqlx 特性 (Rust 项目适用)
+    #[serde(default)]
+    pub gen_deps: bool,
+    /// Mysql 的 BIT(1) 是否生成 bool，关闭时生成 u8，默认开启
+    #[serde(default = "default_true")]
+    pub bit_as_bool: bool,
+    /// Mysql 的 TINYINT(1) 是否生成 bool，关闭时生成 i8，默认开启
+    #[serde(default = "default_true")]
+    pub tinyint1_as_bool: bool,
+    /// 日期时间类型使用的库，time 或 chrono，默认 time
+    #[serde(default)]
+    pub datetime_backend: DateTimeBackend,
+    /// 定点数类型使用的库，bigdecimal 或 rust_decimal，默认 bigdecimal
+    #[serde(default)]
+    pub decimal_backend: DecimalBackend,
+    /// 生成的 Default 实现中时间字段取当前时间时使用的时区，utc 或 local，默认 utc
+    #[serde(default)]
+    pub timestamp_default: UtcOrLocal,
+    /// 可为空字段的包装类型，默认为 Option，如设置为 MaybeNull 时生成 MaybeNull<T>
+    #[serde(default)]
+    pub nullable_wrapper: Option<String>,
+    /// 额外添加到每个 Rust 模型文件的导入，如 `my_crate::MyType`，用于类型提示等引入的自定义类型
+    #[serde(default)]
+    pub rust_imports: Vec<String>,
+    /// 模型结构体的派生宏，如 `["Debug", "Clone", "Serialize", "Deserialize"]`
+    #[serde(default = "default_derives")]
+    pub derives: Vec<String>,
+    /// 是否采用字段备注中的类型提示（`@rust:<类型>`）覆盖推导出的 Rust 类型
+    #[serde(default)]
+    pub honor_comment_hints: bool,
+    /// 自定义模板目录，目录结构与内置模板相同（如 `rust/model.html`），目录中不存在的模板使用内置模板
+    #[serde(default)]
+    pub template_dir: Option<PathBuf>,
+
+    /// Java 包名配置
+    #[serde(default)]
+    pub packages: Packages,
+
+    /// 各生成目标的输出子目录
+    #[serde(default)]
+    pub out_dirs: OutDirs,
 
+    /// 自定义 Rust 类型，K：SQL 类型（不区分大小写）或 表名.列名，V：Rust 类型
+    #[serde(default)]
+    pub type_overrides: HashMap<String, String>,
+}
+
+/// 生成时间字段默认值时使用的时区
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UtcOrLocal {
+    #[default]
+    Utc,
+    /// 本地时区，获取本地偏移失败时回退为 UTC
+    Local,
+}
+
+impl UtcOrLocal {
+    /// 生成时间类型取当前时间的表达式，非时间类型返回 None
+    pub fn now_expr(&self, rust_type: &str) -> Option<String> {
+        let now = match self {
+            UtcOrLocal::Utc => "time::OffsetDateTime::now_utc()",
+            UtcOrLocal::Local => {
+                "time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc())"
+            }
+        };
+        let chrono_now = match self {
+            UtcOrLocal::Utc => "chrono::Utc::now().naive_utc()",
+            UtcOrLocal::Local => "chrono::Local::now().naive_local()",
+        };
+        match rust_type {
+            "chrono::DateTime<chrono::Utc>" => Some("chrono::Utc::now()".to_string()),
+            "chrono::NaiveDateTime" => Some(chrono_now.to_string()),
+            "chrono::NaiveDate" => Some(format!("{chrono_now}.date()")),
+            "chrono::NaiveTime" => Some(format!("{chrono_now}.time()")),
+            "time::OffsetDateTime" => Some(now.to_string()),
+            "time::PrimitiveDateTime" => Some(format!(
+                "{{ let now = {now}; time::PrimitiveDateTime::new(now.date(), now.time()) }}"
+            )),
+            "time::Date" => Some(format!("{now}.date()")),
+            "time::Time" => Some(format!("{now}.time()")),
+            _ => None,
+        }
+    }
+}
+
+/// Java 包名配置，对应配置文件中的 [packages]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Packages {
     /// entity的包名
-    pub entity_package_name: Option<String>,
+    pub entity: Option<String>,
     /// mapper的包名
-    pub mapper_package_name: Option<String>,
+    pub mapper: Option<String>,
     /// mapperXml的包名
-    pub mapper_xml_package_name: Option<String>,
+    pub mapper_xml: Option<String>,
     /// service的包名
-    pub service_package_name: Option<String>,
+    pub service: Option<String>,
     /// serviceImpl的包名
-    pub service_impl_package_name: Option<String>,
+    pub service_impl: Option<String>,
     /// controller的包名
-    pub controller_package_name: Option<String>,
+    pub controller: Option<String>,
+}
+
+/// 各生成目标相对于 path 的输出子目录，对应配置文件中的 [out_dirs]，未配置时直接输出到 path
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OutDirs {
+    /// Rust 模型模块（mod.rs、error.rs、types.rs 及各表模型）的目录
+    pub models: Option<PathBuf>,
+    /// Java 源文件的目录，包名对应的目录在其下创建
+    pub java: Option<PathBuf>,
+    /// MyBatis Mapper.xml 文件的目录
+    pub mapper_xml: Option<PathBuf>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 派生宏的名称，即路径的最后一段，如 `sqlx::FromRow` -> `FromRow`
+fn derive_name(derive: &str) -> &str {
+    derive.rsplit("::").next().unwrap_or(derive).trim()
+}
+
+fn default_derives() -> Vec<String> {
+    [
+        "Debug",
+        "Default",
+        "Clone",
+        "PartialEq",
+        "Eq",
+        "PartialOrd",
+        "Ord",
+        "Hash",
+        "Serialize",
+        "Deserialize",
+        "FromRow",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// FNV-1a 哈希，不依赖标准库哈希的实现，不同版本的 Rust 生成的名称保持一致
+fn name_hash(name: &str) -> u32 {
+    name.bytes().fold(0x811c9dc5, |hash, b| {
+        (hash ^ b as u32).wrapping_mul(0x01000193)
+    })
+}
+
+/// 反序列化字符串或字符串数组
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
 }
 
 impl TryFrom<&str> for GeneratorConfig {
@@ -85,19 +342,359 @@ impl GeneratorConfig {
     /// 从配置文件创建 GeneratorConfig
     pub fn new<P: AsRef<Path>>(config_path: P) -> anyhow::Result<Self> {
         let data = fs::read_to_string(config_path)?;
-        let config = GeneratorConfig::try_from(data.as_str())?;
+        let mut config = GeneratorConfig::try_from(data.as_str())?;
+        // 未配置模式时使用连接的当前模式
+        if config.schemas.is_empty() {
+            config.schemas.push(String::new());
+        }
+        config.schema = config.schemas[0].clone();
+        config.resolve_database_url(|name| std::env::var(name).ok())?;
+        config.merge_table_names_file()?;
         Ok(config)
     }
-    
+
+    /// 解析数据源中引用的环境变量，避免在配置文件中保存密码，var 根据变量名读取环境变量
+    fn resolve_database_url(&mut self, var: impl Fn(&str) -> Option<String>) -> anyhow::Result<()> {
+        let name = match self.database_url.trim() {
+            "" => DATABASE_URL_ENV,
+            url => match url.strip_prefix(ENV_PREFIX) {
+                Some(name) => name.trim(),
+                None => return Ok(()),
+            },
+        };
+        self.database_url = var(name)
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| anyhow!("环境变量 {name} 未设置，无法获取数据库连接地址"))?;
+        Ok(())
+    }
+
+    /// 将表名列表文件中的表名合并到 table_names
+    fn merge_table_names_file(&mut self) -> anyhow::Result<()> {
+        let Some(path) = &self.table_names_file else {
+            return Ok(());
+        };
+        let data = fs::read_to_string(path)
+            .map_err(|e| anyhow!("读取表名列表文件 {} 错误，{e}", path.display()))?;
+        for name in data
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        {
+            if !self.table_names.iter().any(|t| t == name) {
+                self.table_names.push(name.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// 数据库连接选项
+    pub fn connect_options(&self) -> ConnectOptions {
+        ConnectOptions {
+            socket: self.socket.clone(),
+            statement_timeout: self.statement_timeout.map(Duration::from_millis),
+            max_connections: self.max_connections,
+            min_connections: self.min_connections,
+            acquire_timeout: self.acquire_timeout.map(Duration::from_millis),
+            ssl_mode: self.ssl_mode,
+            ssl_root_cert: self.ssl_root_cert.clone(),
+        }
+    }
+
+    /// 获取元数据来源，配置了转储文件时从文件解析，否则连接数据库
+    ///
+    /// 每次调用都会新建连接池并检查连接，一次命令只应调用一次
+    pub async fn metadata(&self) -> anyhow::Result<Box<dyn DatabaseMetadata>> {
+        Ok(match &self.dump_file {
+            Some(dump_file) => Box::new(DumpMetadata::from_file(dump_file, self.driver()?)?),
+            None => {
+                let meta = database_metadata_retry_with(
+                    &self.database_url,
+                    &self.connect_options(),
+                    self.retry_attempts + 1,
+                    RETRY_BASE_DELAY,
+                )
+                .await
+                .map_err(|e| anyhow!("数据库连接失败，{e}"))?;
+                meta.ping()
+                    .await
+                    .map_err(|e| anyhow!("数据库不可用，{e}"))?;
+                meta
+            }
+        })
+    }
+
+    /// 将未配置的模式替换为连接的当前模式，Mysql 等数据源不接受空的模式名
+    pub async fn resolve_schemas(&mut self, meta: &dyn DatabaseMetadata) -> anyhow::Result<()> {
+        if !self.schemas.iter().any(String::is_empty) {
+            return Ok(());
+        }
+        let current = meta.current_schema().await?;
+        if current.is_empty() && self.dump_file.is_none() && self.driver()? == Driver::Mysql {
+            return Err(anyhow!("未配置模式，且数据库连接未选择数据库"));
+        }
+        for schema in self.schemas.iter_mut().filter(|s| s.is_empty()) {
+            *schema = current.clone();
+        }
+        if self.schema.is_empty() {
+            self.schema = current;
+        }
+        Ok(())
+    }
+
+    /// 根据表名生成结构体名，先去掉 strip_table_prefix，开启 singularize_struct_names 时转为单数，
+    /// 再转为大驼峰
+    ///
+    /// 表名不以前缀开头，或去掉前缀后为空时保留完整表名
+    pub fn struct_name(&self, table_name: &str) -> String {
+        let name = self
+            .strip_table_prefix
+            .as_deref()
+            .and_then(|prefix| table_name.strip_prefix(prefix))
+            .filter(|name| !name.is_empty())
+            .unwrap_or(table_name);
+        let name = if self.singularize_struct_names {
+            inflect::singularize(name).to_upper_camel_case()
+        } else {
+            name.to_upper_camel_case()
+        };
+        self.truncate_name(name, table_name, "")
+    }
+
+    /// 表对应的模块名，即模型文件名（不含扩展名），超出 max_name_length 时截断
+    pub fn module_name(&self, table_name: &str) -> String {
+        self.truncate_name(table_name.to_string(), table_name, "_")
+    }
+
+    /// 名称超出 max_name_length 时截断，并以 separator 连接表名的 8 位十六进制哈希，
+    /// 前缀相同的不同表截断后仍不会重名
+    fn truncate_name(&self, name: String, table_name: &str, separator: &str) -> String {
+        let Some(max) = self.max_name_length else {
+            return name;
+        };
+        if name.chars().count() <= max {
+            return name;
+        }
+        let suffix = format!("{separator}{:08x}", name_hash(table_name));
+        let keep = max.saturating_sub(suffix.len()).max(1);
+        let head = name.chars().take(keep).collect::<String>();
+        // 避免截断后以分隔符结尾，如 `order__1a2b3c4d`
+        format!("{}{suffix}", head.trim_end_matches('_'))
+    }
+
+    /// 表是否被忽略，表名在 ignore_tables 中或以 ignore_table_prefix 开头
+    pub fn is_ignored(&self, table_name: &str) -> bool {
+        self.ignore_tables.iter().any(|t| t == table_name)
+            || self
+                .ignore_table_prefix
+                .as_deref()
+                .is_some_and(|prefix| !prefix.is_empty() && table_name.starts_with(prefix))
+    }
+
     /// 获取数据库驱动类型
     pub fn driver(&self) -> anyhow::Result<Driver> {
         Driver::try_from(self.database_url.as_str()).map_err(|_| anyhow!("数据库驱动类型不支持"))
     }
 
-    ///  处理路径，当路径不以 / 结尾时，自动添加 /
+    /// 列的自定义 Rust 类型，表名.列名 的配置优先于 SQL 类型的配置
+    pub fn type_override(&self, column: &Column) -> Option<&str> {
+        if let Some(ty) = self
+            .type_overrides
+            .get(&format!("{}.{}", column.table_name, column.name))
+        {
+            return Some(ty);
+        }
+        let sql_type = column.r#type.as_ref()?.to_string();
+        self.type_overrides
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(&sql_type))
+            .map(|(_, ty)| ty.as_str())
+    }
+
+    /// 去重后的额外导入路径，兼容写成 `use my_crate::MyType;` 的形式
+    pub fn rust_imports(&self) -> Vec<&str> {
+        let mut imports = Vec::with_capacity(self.rust_imports.len());
+        for import in self.rust_imports.iter() {
+            let import = import.trim();
+            let import = import.strip_prefix("use ").unwrap_or(import);
+            let import = import.trim_end_matches(';').trim();
+            if !import.is_empty() && !imports.contains(&import) {
+                imports.push(import);
+            }
+        }
+        imports
+    }
+
+    /// 去重后的模型派生宏，忽略空项
+    ///
+    /// 生成的查询方法依赖 FromRow，未配置时自动添加；开启校验时添加 Validate；
+    /// default_impl 为模型是否生成了 Default 实现，此时不能再派生 Default
+    pub fn derives(&self, default_impl: bool) -> Vec<&str> {
+        let mut derives: Vec<&str> = Vec::with_capacity(self.derives.len() + 2);
+        let required = ["FromRow"]
+            .into_iter()
+            .chain(self.gen_validator.then_some("Validate"));
+        for derive in self.derives.iter().map(|d| d.trim()).chain(required) {
+            // 按路径的最后一段判断重复，如 sqlx::FromRow 与 FromRow
+            if derive.is_empty()
+                || derives
+                    .iter()
+                    .any(|d| derive_name(d) == derive_name(derive))
+                || (default_impl && derive_name(derive) == "Default")
+            {
+                continue;
+            }
+            derives.push(derive);
+        }
+        derives
+    }
+
+    /// 生成目标的输出目录，out_dir 为 out_dirs 中对应目标的配置
+    pub fn out_dir(&self, out_dir: Option<&Path>) -> PathBuf {
+        out_dir.map_or_else(|| self.path.clone(), |dir| self.path.join(dir))
+    }
+
+    /// 使用命令行参数覆盖配置文件中的表和模式，参数为空时保留配置文件的值
+    pub fn with_overrides(&mut self, tables: &[String], schema: Option<&str>) -> &mut Self {
+        if !tables.is_empty() {
+            self.table_names = tables.to_vec();
+        }
+        if let Some(schema) = schema {
+            self.schemas = vec![schema.to_string()];
+            self.schema = schema.to_string();
+        }
+        self
+    }
+
+    /// 切换到要处理的模式，path 为配置的代码生成路径
+    ///
+    /// 配置了多个模式时输出到 path 下以模式名命名的子目录，避免不同模式中的同名表互相覆盖
+    pub fn use_schema(&mut self, schema: &str, path: &Path) {
+        self.schema = schema.to_string();
+        self.path = if self.schemas.len() > 1 {
+            // push 空路径会在末尾追加平台分隔符，与 deal_path 一致
+            path.join(schema).join("")
+        } else {
+            path.to_path_buf()
+        };
+    }
+
+    /// 处理路径，当路径不以分隔符结尾时，自动添加分隔符
     pub fn deal_path(&mut self) {
-        // if !self.path.is_empty() && !self.path.ends_with('/') {
-        //     self.path.push('/')
-        // }
+        let path = self.path.as_os_str().to_string_lossy();
+        if !path.is_empty() && !path.ends_with(std::path::is_separator) {
+            // push 空路径会在末尾追加平台分隔符
+            self.path.push("");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GeneratorConfig {
+        GeneratorConfig::try_from(
+            r#"
+            database_url = "mysql://localhost/test"
+            language = "rust"
+            table_names = []
+            ignore_tables = []
+            path = "./generated/"
+            override = false
+            gen_mod = false
+            gen_error = false
+            gen_entity = true
+            gen_mapper = false
+            gen_mapper_xml = false
+            gen_service = false
+            gen_controller = false
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn derives_skip_empty_and_duplicates() {
+        let mut config = config();
+        assert!(
+            config
+                .derives(false)
+                .starts_with(&["Debug", "Default", "Clone"])
+        );
+
+        config.derives = ["Debug", " ", "Clone", "Debug", "sqlx::FromRow", "Default"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            config.derives(false),
+            ["Debug", "Clone", "sqlx::FromRow", "Default"]
+        );
+        // 生成了 Default 实现时不再派生 Default，开启校验时追加 Validate
+        config.gen_validator = true;
+        assert_eq!(
+            config.derives(true),
+            ["Debug", "Clone", "sqlx::FromRow", "Validate"]
+        );
+    }
+
+    #[test]
+    fn singular_struct_names() {
+        let mut config = config();
+        assert_eq!(config.struct_name("categories"), "Categories");
+        config.singularize_struct_names = true;
+        assert_eq!(config.struct_name("categories"), "Category");
+        assert_eq!(config.struct_name("people"), "Person");
+        assert_eq!(config.struct_name("status"), "Status");
+    }
+
+    #[test]
+    fn database_url_from_env() {
+        let vars = HashMap::from([
+            ("APP_DATABASE_URL", "mysql://root@localhost/env"),
+            (DATABASE_URL_ENV, "postgres://postgres@localhost/env"),
+            ("EMPTY_URL", ""),
+        ]);
+        let var = |name: &str| vars.get(name).map(|v| v.to_string());
+        let mut config = config();
+        config.database_url = "env:APP_DATABASE_URL".to_string();
+        config.resolve_database_url(var).unwrap();
+        assert_eq!(config.database_url, "mysql://root@localhost/env");
+
+        config.database_url = " ".to_string();
+        config.resolve_database_url(var).unwrap();
+        assert_eq!(config.database_url, "postgres://postgres@localhost/env");
+
+        // 未引用环境变量时保持不变
+        config.resolve_database_url(var).unwrap();
+        assert_eq!(config.database_url, "postgres://postgres@localhost/env");
+
+        for name in ["MISSING_URL", "EMPTY_URL"] {
+            config.database_url = format!("env:{name}");
+            let err = config.resolve_database_url(var).unwrap_err();
+            assert!(err.to_string().contains(name));
+        }
+    }
+
+    #[test]
+    fn long_names_stay_distinct() {
+        let mut config = config();
+        let a = "customer_order_line_item_history_archive_2023";
+        let b = "customer_order_line_item_history_archive_2024";
+        assert_eq!(config.module_name(a), a);
+
+        config.max_name_length = Some(24);
+        let (module_a, module_b) = (config.module_name(a), config.module_name(b));
+        let (struct_a, struct_b) = (config.struct_name(a), config.struct_name(b));
+        assert_ne!(module_a, module_b);
+        assert_ne!(struct_a, struct_b);
+        for name in [&module_a, &module_b, &struct_a, &struct_b] {
+            assert!(name.len() <= 24, "{name}");
+        }
+        assert!(module_a.starts_with("customer_order_"));
+        assert!(struct_a.starts_with("CustomerOrder"));
+        // 同一表名每次生成相同的名称
+        assert_eq!(config.module_name(a), module_a);
+        assert_eq!(config.module_name("users"), "users");
     }
 }
@@ -0,0 +1,164 @@
+//! 增删改查
+//!
+//! 为有主键的表生成按主键查询、新增、修改、删除使用的 SQL，绑定参数的占位符由驱动决定
+
+use database::{Column, Driver};
+use serde::Serialize;
+
+/// 增删改查 SQL 及各语句绑定的列
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrudSql<'a> {
+    /// 主键列，组合主键按列顺序排列
+    pub primary_keys: Vec<&'a Column>,
+    pub find_by_id: String,
+    pub insert: String,
+    /// 新增时写入的列，不含自增列和生成列
+    pub insert_columns: Vec<&'a Column>,
+    /// 没有主键以外的列时不生成修改
+    pub update: Option<String>,
+    /// 修改的列，不含生成列，绑定完这些列后再绑定主键列
+    pub update_columns: Vec<&'a Column>,
+    pub delete: String,
+}
+
+/// 生成增删改查 SQL，表没有主键时返回 None
+pub fn crud_sql<'a>(table: &str, columns: &[&'a Column], driver: Driver) -> Option<CrudSql<'a>> {
+    let primary_keys = columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .copied()
+        .collect::<Vec<_>>();
+    if primary_keys.is_empty() {
+        return None;
+    }
+    let insert_columns = columns
+        .iter()
+        .filter(|c| !c.is_auto_incr && !c.is_generated)
+        .copied()
+        .collect::<Vec<_>>();
+    let update_columns = columns
+        .iter()
+        .filter(|c| !c.is_primary_key && !c.is_generated)
+        .copied()
+        .collect::<Vec<_>>();
+
+    let names = columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+    let find_by_id = format!(
+        "SELECT {} FROM {table} WHERE {}",
+        names.join(", "),
+        conditions(&primary_keys, driver, 1)
+    );
+    let insert = format!(
+        "INSERT INTO {table} ({}) VALUES ({})",
+        insert_columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        (1..=insert_columns.len())
+            .map(|i| driver.placeholder(i))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let update = (!update_columns.is_empty()).then(|| {
+        let sets = update_columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{} = {}", c.name, driver.placeholder(i + 1)))
+            .collect::<Vec<_>>();
+        format!(
+            "UPDATE {table} SET {} WHERE {}",
+            sets.join(", "),
+            conditions(&primary_keys, driver, update_columns.len() + 1)
+        )
+    });
+    let delete = format!(
+        "DELETE FROM {table} WHERE {}",
+        conditions(&primary_keys, driver, 1)
+    );
+    Some(CrudSql {
+        primary_keys,
+        find_by_id,
+        insert,
+        insert_columns,
+        update,
+        update_columns,
+        delete,
+    })
+}
+
+/// 主键条件，如 `a = $1 AND b = $2`，start 为第一个占位符的序号
+fn conditions(primary_keys: &[&Column], driver: Driver, start: usize) -> String {
+    primary_keys
+        .iter()
+        .enumerate()
+        .map(|(i, c)| format!("{} = {}", c.name, driver.placeholder(start + i)))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, is_primary_key: bool, is_auto_incr: bool) -> Column {
+        Column {
+            name: name.to_string(),
+            is_primary_key,
+            is_auto_incr,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn placeholders_per_driver() {
+        let columns = [
+            column("id", true, true),
+            column("name", false, false),
+            column("age", false, false),
+        ];
+        let columns = columns.iter().collect::<Vec<_>>();
+
+        let sql = crud_sql("users", &columns, Driver::Postgres).unwrap();
+        assert_eq!(
+            sql.find_by_id,
+            "SELECT id, name, age FROM users WHERE id = $1"
+        );
+        assert_eq!(sql.insert, "INSERT INTO users (name, age) VALUES ($1, $2)");
+        assert_eq!(
+            sql.update.as_deref(),
+            Some("UPDATE users SET name = $1, age = $2 WHERE id = $3")
+        );
+        assert_eq!(sql.delete, "DELETE FROM users WHERE id = $1");
+
+        for driver in [Driver::Mysql, Driver::Sqlite] {
+            let sql = crud_sql("users", &columns, driver).unwrap();
+            assert_eq!(
+                sql.find_by_id,
+                "SELECT id, name, age FROM users WHERE id = ?"
+            );
+            assert_eq!(sql.insert, "INSERT INTO users (name, age) VALUES (?, ?)");
+            assert_eq!(
+                sql.update.as_deref(),
+                Some("UPDATE users SET name = ?, age = ? WHERE id = ?")
+            );
+            assert_eq!(sql.delete, "DELETE FROM users WHERE id = ?");
+        }
+    }
+
+    #[test]
+    fn composite_and_missing_primary_key() {
+        let columns = [column("a", true, false), column("b", true, false)];
+        let columns = columns.iter().collect::<Vec<_>>();
+        let sql = crud_sql("t", &columns, Driver::Postgres).unwrap();
+        assert_eq!(sql.delete, "DELETE FROM t WHERE a = $1 AND b = $2");
+        assert_eq!(sql.insert, "INSERT INTO t (a, b) VALUES ($1, $2)");
+        // 只有主键列时不生成修改
+        assert_eq!(sql.update, None);
+
+        let columns = [column("name", false, false)];
+        let columns = columns.iter().collect::<Vec<_>>();
+        assert!(crud_sql("t", &columns, Driver::Mysql).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! 生成代码所需的依赖
+//!
+//! 根据生成的文件和模型字段实际使用的类型，输出可以直接复制到 Cargo.toml 的依赖片段，
+//! 包括 sqlx 需要开启的数据库和类型特性
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use database::{Column, Driver};
+
+use crate::config::{GeneratorConfig, UtcOrLocal};
+
+/// 依赖片段的文件名
+pub const DEPS_FILE: &str = "deps.toml";
+
+/// 生成依赖片段
+pub fn snippet(config: &GeneratorConfig, driver: Driver, columns: &[Column]) -> String {
+    let mut deps: BTreeMap<&str, String> = BTreeMap::new();
+    let mut sqlx_features = BTreeSet::from(["macros", "runtime-tokio", driver_feature(driver)]);
+
+    deps.insert("serde", dep("1", &["derive"]));
+    deps.insert("serde_json", dep("1", &[]));
+    deps.insert("log", dep("0.4", &[]));
+    if config.gen_mod {
+        deps.insert("async_static", dep("0.1", &[]));
+    }
+    if config.gen_error {
+        deps.insert("thiserror", dep("2", &[]));
+        deps.insert("anyhow", dep("1", &[]));
+        deps.insert("poem", dep("3", &[]));
+        deps.insert("reqwest", dep("0.12", &[]));
+        deps.insert("tera", dep("1", &[]));
+    }
+    if config.gen_validator {
+        deps.insert("validator", dep("0.20", &["derive"]));
+    }
+
+    for name in columns.iter().flat_map(|c| crates(&c.rust_type)) {
+        match name {
+            "time" => {
+                let features: &[&str] = match config.timestamp_default {
+                    UtcOrLocal::Utc => &["serde"],
+                    UtcOrLocal::Local => &["serde", "local-offset"],
+                };
+                deps.insert("time", dep("0.3", features));
+                sqlx_features.insert("time");
+            }
+            "chrono" => {
+                deps.insert("chrono", dep("0.4", &["serde"]));
+                sqlx_features.insert("chrono");
+            }
+            "bigdecimal" => {
+                deps.insert("bigdecimal", dep("0.4", &["serde"]));
+                sqlx_features.insert("bigdecimal");
+            }
+            "rust_decimal" => {
+                deps.insert("rust_decimal", dep("1", &["serde"]));
+                sqlx_features.insert("rust_decimal");
+            }
+            "uuid" => {
+                deps.insert("uuid", dep("1", &["serde"]));
+                sqlx_features.insert("uuid");
+            }
+            "serde_json" => {
+                sqlx_features.insert("json");
+            }
+            "mac_address" => {
+                deps.insert("mac_address", dep("1", &["serde"]));
+                sqlx_features.insert("mac_address");
+            }
+            "bit_vec" => {
+                deps.insert("bit-vec", dep("0.6", &["serde"]));
+                sqlx_features.insert("bit-vec");
+            }
+            // std 以及自定义类型所在的 crate 无法推断
+            _ => {}
+        }
+    }
+    let sqlx_features = sqlx_features.into_iter().collect::<Vec<_>>();
+    deps.insert("sqlx", dep("0.8", &sqlx_features));
+
+    let mut snippet =
+        String::from("# 生成代码所需的依赖，复制到 Cargo.toml 中使用\n[dependencies]\n");
+    for (name, spec) in deps {
+        snippet.push_str(&format!("{name} = {spec}\n"));
+    }
+    snippet
+}
+
+fn driver_feature(driver: Driver) -> &'static str {
+    match driver {
+        Driver::Mysql => "mysql",
+        Driver::Postgres => "postgres",
+        Driver::Sqlite => "sqlite",
+    }
+}
+
+fn dep(version: &str, features: &[&str]) -> String {
+    if features.is_empty() {
+        return format!("\"{version}\"");
+    }
+    let features = features
+        .iter()
+        .map(|f| format!("\"{f}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{{ version = \"{version}\", features = [{features}] }}")
+}
+
+/// 类型中引用的 crate，如 `chrono::DateTime<chrono::Utc>` 返回两个 chrono
+fn crates(rust_type: &str) -> impl Iterator<Item = &str> {
+    rust_type
+        .split(['<', '>', ',', '(', ')', '[', ']', '&', ' '])
+        .filter_map(|path| path.split_once("::").map(|(name, _)| name))
+}
@@ -0,0 +1,228 @@
+//! diesel schema.rs 生成辅助
+//!
+//! diesel 的 `table!` 使用 SQL 类型而不是 Rust 类型，这里根据列的数据库类型映射到
+//! `diesel::sql_types` 中的类型
+
+use database::{Column, Driver, ForeignKey, Table};
+use heck::ToUpperCamelCase as _;
+use serde::Serialize;
+
+/// schema.rs 的文件名
+pub const SCHEMA_FILE: &str = "schema.rs";
+
+/// schema.rs 的内容
+#[derive(Debug, Default, Serialize)]
+pub struct Schema<'a> {
+    pub tables: Vec<DieselTable<'a>>,
+    /// 外键对应的 `joinable!`
+    pub joinables: Vec<Joinable>,
+    /// 枚举等需要自定义的 SQL 类型
+    pub sql_types: Vec<SqlType>,
+}
+
+/// `table!` 中的表
+#[derive(Debug, Serialize)]
+pub struct DieselTable<'a> {
+    /// 表名对应的标识符
+    pub name: String,
+    /// 标识符与表名不同时为原表名，生成 `#[sql_name = "..."]`
+    pub sql_name: Option<&'a str>,
+    /// 主键列的标识符
+    pub primary_keys: Vec<String>,
+    pub columns: Vec<DieselColumn<'a>>,
+    pub comment: &'a str,
+}
+
+/// `table!` 中的列
+#[derive(Debug, Serialize)]
+pub struct DieselColumn<'a> {
+    /// 列名对应的标识符
+    pub name: String,
+    /// 标识符与列名不同时为原列名
+    pub sql_name: Option<&'a str>,
+    /// diesel SQL 类型，可为空的列包装为 Nullable
+    pub r#type: String,
+    pub comment: &'a str,
+}
+
+/// `joinable!(child -> parent (column));`
+#[derive(Debug, Serialize)]
+pub struct Joinable {
+    pub child: String,
+    pub parent: String,
+    pub column: String,
+}
+
+/// 自定义 SQL 类型，生成在 `sql_types` 模块中
+#[derive(Debug, Serialize)]
+pub struct SqlType {
+    /// 类型名，如 `OrderStatus`
+    pub name: String,
+    /// diesel 属性，如 `postgres_type(name = "order_status")`
+    pub attr: String,
+}
+
+/// 生成 schema.rs 的内容，没有主键的表 diesel 无法使用，跳过
+pub fn schema<'a>(
+    tables: &'a [(&'a Table, Vec<&'a Column>)],
+    foreign_keys: &[ForeignKey],
+    driver: Driver,
+) -> Schema<'a> {
+    let mut schema = Schema::default();
+    for (table, columns) in tables {
+        let primary_keys = columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| database::field_name(&c.name))
+            .collect::<Vec<_>>();
+        if primary_keys.is_empty() {
+            eprintln!("表 {} 没有主键，diesel 不支持，已跳过", table.name);
+            continue;
+        }
+        let columns = columns
+            .iter()
+            .map(|c| {
+                let mut ty = sql_type(c, driver);
+                if ty.is_none() && c.enum_values.is_some() {
+                    let sql_type = custom_type(c, driver);
+                    ty = Some(format!("super::sql_types::{}", sql_type.name));
+                    if !schema.sql_types.iter().any(|t| t.name == sql_type.name) {
+                        schema.sql_types.push(sql_type);
+                    }
+                }
+                // 无法识别的类型按文本处理
+                let ty = ty.unwrap_or_else(|| "Text".to_string());
+                DieselColumn {
+                    name: database::field_name(&c.name),
+                    sql_name: database::needs_rename(&c.name).then_some(c.name.as_str()),
+                    r#type: if c.is_null {
+                        format!("Nullable<{ty}>")
+                    } else {
+                        ty
+                    },
+                    comment: &c.comment,
+                }
+            })
+            .collect();
+        schema.tables.push(DieselTable {
+            name: database::field_name(&table.name),
+            sql_name: database::needs_rename(&table.name).then_some(table.name.as_str()),
+            primary_keys,
+            columns,
+            comment: &table.comment,
+        });
+    }
+
+    // joinable! 只支持单列外键，同一对表只能声明一次，且不能引用自身
+    let find = |name: &str| {
+        let name = database::field_name(name);
+        schema.tables.iter().find(|t| t.name == name)
+    };
+    let mut joinables: Vec<Joinable> = vec![];
+    for fk in foreign_keys {
+        let (Some(child), Some(parent)) = (find(&fk.table_name), find(&fk.referenced_table)) else {
+            continue;
+        };
+        if fk.columns.len() != 1
+            || child.name == parent.name
+            || joinables
+                .iter()
+                .any(|j| j.child == child.name && j.parent == parent.name)
+        {
+            continue;
+        }
+        joinables.push(Joinable {
+            child: child.name.clone(),
+            parent: parent.name.clone(),
+            column: database::field_name(&fk.columns[0]),
+        });
+    }
+    schema.joinables = joinables;
+    schema
+}
+
+/// 列类型对应的 diesel SQL 类型，不含 Nullable，无法识别时返回 None
+pub fn sql_type(column: &Column, driver: Driver) -> Option<String> {
+    // Mysql 的 TINYINT(1)、BIT(1) 等映射为 bool 的列
+    if column.rust_type == "bool" {
+        return Some("Bool".to_string());
+    }
+    let ty = column
+        .r#type
+        .as_ref()
+        .map(|t| t.to_string())
+        .unwrap_or_default()
+        .to_uppercase();
+    // Postgres 的数组类型以 _ 开头，如 _int4
+    if let Some(element) = ty.strip_prefix('_') {
+        let element = Column {
+            r#type: Some(element.to_string().into()),
+            ..Default::default()
+        };
+        return sql_type(&element, driver).map(|t| format!("Array<Nullable<{t}>>"));
+    }
+    let pg = driver == Driver::Postgres;
+    let ty = match ty.as_str() {
+        "BOOL" | "BOOLEAN" => "Bool",
+        "TINYINT" => "TinyInt",
+        "SMALLINT" | "INT2" | "SMALLSERIAL" | "YEAR" if pg => "Int2",
+        "SMALLINT" | "INT2" | "YEAR" => "SmallInt",
+        "INT" | "INTEGER" | "INT4" | "MEDIUMINT" | "SERIAL" if pg => "Int4",
+        "INT" | "INTEGER" | "INT4" | "MEDIUMINT" => "Integer",
+        "BIGINT" | "INT8" | "BIGSERIAL" if pg => "Int8",
+        "BIGINT" | "INT8" => "BigInt",
+        // Sqlite 的 REAL 为 8 字节浮点数
+        "REAL" if driver == Driver::Sqlite => "Double",
+        "FLOAT" | "FLOAT4" | "REAL" if pg => "Float4",
+        "FLOAT" | "FLOAT4" | "REAL" => "Float",
+        "DOUBLE" | "FLOAT8" | "DOUBLE PRECISION" if pg => "Float8",
+        "DOUBLE" | "FLOAT8" | "DOUBLE PRECISION" => "Double",
+        "DECIMAL" | "NUMERIC" => "Numeric",
+        "VARCHAR" | "CHARACTER VARYING" => "Varchar",
+        "CHAR" | "BPCHAR" | "CHARACTER" if pg => "Bpchar",
+        "CHAR" | "TEXT" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "CLOB" | "CITEXT" | "NAME" => {
+            "Text"
+        }
+        "BYTEA" => "Bytea",
+        "BINARY" | "VARBINARY" => "Binary",
+        "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" => "Blob",
+        "DATE" => "Date",
+        "TIME" => "Time",
+        "DATETIME" if driver == Driver::Mysql => "Datetime",
+        "TIMESTAMP" | "DATETIME" => "Timestamp",
+        "TIMESTAMPTZ" => "Timestamptz",
+        "INTERVAL" => "Interval",
+        "JSON" => "Json",
+        "JSONB" => "Jsonb",
+        "UUID" => "Uuid",
+        "INET" => "Inet",
+        "CIDR" => "Cidr",
+        "MACADDR" => "MacAddr",
+        "MONEY" => "Money",
+        _ => return None,
+    };
+    // Mysql 的无符号整数
+    if column.is_unsigned && ["TinyInt", "SmallInt", "Integer", "BigInt"].contains(&ty) {
+        return Some(format!("Unsigned<{ty}>"));
+    }
+    Some(ty.to_string())
+}
+
+/// 枚举列的自定义类型，Postgres 按枚举类型命名，Mysql 按表名和列名命名
+fn custom_type(column: &Column, driver: Driver) -> SqlType {
+    let ty = column
+        .r#type
+        .as_ref()
+        .map(|t| t.to_string())
+        .unwrap_or_default();
+    match driver {
+        Driver::Postgres => SqlType {
+            name: ty.to_upper_camel_case(),
+            attr: format!("postgres_type(name = {ty:?})"),
+        },
+        Driver::Mysql | Driver::Sqlite => SqlType {
+            name: format!("{}_{}_enum", column.table_name, column.name).to_upper_camel_case(),
+            attr: "mysql_type(name = \"Enum\")".to_string(),
+        },
+    }
+}
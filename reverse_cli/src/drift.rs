@@ -0,0 +1,85 @@
+//! 结构漂移检测
+//!
+//! 对比生成清单中记录的列结构与数据库当前的列结构，找出生成后发生变化的表
+
+use std::fmt::Display;
+
+use database::Column;
+
+use crate::manifest::{ColumnSignature, Manifest};
+
+/// 表结构漂移
+#[derive(Debug, Default)]
+pub struct Drift {
+    /// 表名
+    pub table: String,
+    /// 新增的列
+    pub added: Vec<String>,
+    /// 删除的列
+    pub removed: Vec<String>,
+    /// 类型、长度、是否为空或主键发生变化的列
+    pub changed: Vec<ColumnChange>,
+}
+
+/// 发生变化的列
+#[derive(Debug)]
+pub struct ColumnChange {
+    /// 生成时的列结构
+    pub old: ColumnSignature,
+    /// 当前的列结构
+    pub new: ColumnSignature,
+}
+
+impl Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let changed = self.changed.iter().map(|c| c.new.name.clone()).collect();
+        let parts = [
+            ("新增列", &self.added),
+            ("删除列", &self.removed),
+            ("变更列", &changed),
+        ]
+        .into_iter()
+        .filter(|(_, columns)| !columns.is_empty())
+        .map(|(label, columns)| format!("{label} {}", columns.join(", ")))
+        .collect::<Vec<_>>();
+        write!(f, "{}: {}", self.table, parts.join("；"))
+    }
+}
+
+/// 检测清单中记录的表是否与当前列结构一致，返回发生变化的表
+pub fn detect(manifest: &Manifest, columns: &[Column]) -> Vec<Drift> {
+    manifest
+        .tables
+        .iter()
+        .filter_map(|(table, generated)| {
+            let current = columns
+                .iter()
+                .filter(|c| &c.table_name == table)
+                .map(ColumnSignature::from)
+                .collect::<Vec<_>>();
+
+            let mut drift = Drift {
+                table: table.clone(),
+                ..Default::default()
+            };
+            for column in current.iter() {
+                match generated.iter().find(|g| g.name == column.name) {
+                    None => drift.added.push(column.name.clone()),
+                    Some(g) if g != column => drift.changed.push(ColumnChange {
+                        old: g.clone(),
+                        new: column.clone(),
+                    }),
+                    Some(_) => {}
+                }
+            }
+            drift.removed = generated
+                .iter()
+                .filter(|g| !current.iter().any(|c| c.name == g.name))
+                .map(|g| g.name.clone())
+                .collect();
+
+            (!drift.added.is_empty() || !drift.removed.is_empty() || !drift.changed.is_empty())
+                .then_some(drift)
+        })
+        .collect()
+}
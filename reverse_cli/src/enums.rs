@@ -0,0 +1,156 @@
+//! 枚举列
+//!
+//! 带有取值列表的 Mysql ENUM 列及 Postgres 自定义枚举类型的列生成独立的 Rust 枚举，
+//! 模型中的字段引用该枚举
+
+use std::collections::BTreeMap;
+
+use database::{Column, ColumnType};
+use heck::ToUpperCamelCase as _;
+use serde::Serialize;
+
+/// 与已有类型同名时追加的后缀
+const ENUM_SUFFIX: &str = "Enum";
+
+/// 生成的 Rust 枚举
+#[derive(Debug, Serialize)]
+pub struct RustEnum {
+    /// 枚举名
+    pub name: String,
+    /// 列备注
+    pub comment: String,
+    /// Postgres 自定义枚举的类型名，sqlx 据此进行类型检查
+    pub type_name: Option<String>,
+    pub variants: Vec<Variant>,
+}
+
+/// 枚举成员
+#[derive(Debug, Serialize)]
+pub struct Variant {
+    /// 成员名，由取值转换为合法的 Rust 标识符
+    pub name: String,
+    /// 数据库中的取值
+    pub value: String,
+    /// 取值的 Rust 字符串字面量，包含引号及转义
+    pub literal: String,
+}
+
+/// 需要生成枚举的列
+///
+/// 列的 Rust 类型需为默认映射：Mysql ENUM 为 String，Postgres 自定义枚举为类型名的大驼峰形式，
+/// 被类型覆盖或类型提示修改过类型的列不生成枚举
+pub fn is_enum_column(column: &Column) -> bool {
+    if column.enum_values.as_ref().is_none_or(|v| v.is_empty()) {
+        return false;
+    }
+    match &column.r#type {
+        Some(ColumnType::Enum) => column.rust_type == "String",
+        Some(ColumnType::Unknown(type_name)) => column.rust_type == type_name.to_upper_camel_case(),
+        _ => false,
+    }
+}
+
+/// 枚举列对应的枚举名，K：表名.列名，V：枚举名
+///
+/// 枚举名取列名的大驼峰形式，Postgres 自定义枚举取类型名的大驼峰形式；模型通过 mod.rs 统一导出，与模型结构体、类型别名或其他表的枚举
+/// 同名时追加 Enum 后缀，并给出提示。使用同一 Postgres 枚举类型的列共用一个枚举
+pub fn enum_names(columns: &[Column], reserved: &[String]) -> BTreeMap<String, String> {
+    let mut names = BTreeMap::new();
+    // K：Postgres 枚举类型名，V：枚举名
+    let mut type_names: BTreeMap<&str, String> = BTreeMap::new();
+    for column in columns.iter().filter(|c| is_enum_column(c)) {
+        let key = format!("{}.{}", column.table_name, column.name);
+        let mut name = match &column.r#type {
+            Some(ColumnType::Unknown(type_name)) => {
+                if let Some(name) = type_names.get(type_name.as_str()) {
+                    names.insert(key, name.clone());
+                    continue;
+                }
+                column.rust_type.clone()
+            }
+            _ => column.name.to_upper_camel_case(),
+        };
+        while reserved.contains(&name) || names.values().any(|n| n == &name) {
+            let renamed = format!("{name}{ENUM_SUFFIX}");
+            eprintln!(
+                "表 {} 的列 {} 生成的枚举 {name} 与已有类型重名，已改为 {renamed}",
+                column.table_name, column.name
+            );
+            name = renamed;
+        }
+        if let Some(ColumnType::Unknown(type_name)) = &column.r#type {
+            type_names.insert(type_name, name.clone());
+        }
+        names.insert(key, name);
+    }
+    names
+}
+
+/// 根据列的取值列表生成枚举定义
+pub fn rust_enum(name: &str, column: &Column) -> RustEnum {
+    let mut variants: Vec<Variant> = vec![];
+    for value in column.enum_values.iter().flatten() {
+        let mut ident = value.to_upper_camel_case();
+        // 空值、数字开头或 Self 不是合法的成员名
+        if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) || ident == "Self" {
+            ident = format!("V{ident}");
+        }
+        // 大小写不同的取值可能转换为相同的成员名
+        let base = ident.clone();
+        let mut i = 1;
+        while variants.iter().any(|v| v.name == ident) {
+            i += 1;
+            ident = format!("{base}{i}");
+        }
+        variants.push(Variant {
+            name: ident,
+            value: value.clone(),
+            literal: format!("{value:?}"),
+        });
+    }
+    RustEnum {
+        name: name.to_string(),
+        comment: column.comment.clone(),
+        type_name: match &column.r#type {
+            Some(ColumnType::Unknown(type_name)) => Some(type_name.clone()),
+            _ => None,
+        },
+        variants,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_names() {
+        let column = Column {
+            name: "kind".to_string(),
+            enum_values: Some(
+                ["new", "in progress", "1st", "", "Self", "NEW", "say \"hi\""]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            ..Default::default()
+        };
+        let rust_enum = rust_enum("Kind", &column);
+        let variants = rust_enum
+            .variants
+            .iter()
+            .map(|v| (v.name.as_str(), v.literal.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            variants,
+            [
+                ("New", "\"new\""),
+                ("InProgress", "\"in progress\""),
+                ("V1st", "\"1st\""),
+                ("V", "\"\""),
+                ("VSelf", "\"Self\""),
+                ("New2", "\"NEW\""),
+                ("SayHi", "\"say \\\"hi\\\"\""),
+            ]
+        );
+    }
+}
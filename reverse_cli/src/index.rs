@@ -0,0 +1,72 @@
+//! 模型注释中的索引说明
+//!
+//! 元数据中多列索引每列一行，按索引名合并，列按在索引中的位置排列
+
+use std::collections::BTreeMap;
+
+use database::Index;
+use serde::Serialize;
+
+/// 表上的索引
+#[derive(Debug, Serialize)]
+pub struct IndexDoc {
+    /// 索引名
+    pub name: String,
+    /// 按在索引中的位置排列的列名
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+/// 合并同名索引的各列，按索引名排序
+pub fn index_docs(indexes: &[Index]) -> Vec<IndexDoc> {
+    let mut groups: BTreeMap<&str, Vec<&Index>> = BTreeMap::new();
+    for index in indexes {
+        groups.entry(&index.key_name).or_default().push(index);
+    }
+    groups
+        .into_iter()
+        .map(|(name, mut columns)| {
+            columns.sort_by_key(|c| c.seq_in_index);
+            IndexDoc {
+                name: name.to_string(),
+                unique: columns[0].non_unique == 0,
+                columns: columns.iter().map(|c| c.column_name.clone()).collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(key_name: &str, column_name: &str, seq_in_index: u32, non_unique: i32) -> Index {
+        Index {
+            key_name: key_name.to_string(),
+            column_name: column_name.to_string(),
+            seq_in_index,
+            non_unique,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn composite_columns_in_order() {
+        let docs = index_docs(&[
+            index("idx_name", "last_name", 2, 1),
+            index("PRIMARY", "id", 1, 0),
+            index("idx_name", "first_name", 1, 1),
+        ]);
+        let docs = docs
+            .iter()
+            .map(|d| (d.name.as_str(), d.columns.join(","), d.unique))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            docs,
+            [
+                ("PRIMARY", "id".to_string(), true),
+                ("idx_name", "first_name,last_name".to_string(), false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! 英文单词的单复数转换
+//!
+//! 只处理表名中常见的规则，如 `categories` -> `category`、`boxes` -> `box`，
+//! 不可数名词及以 `us`、`ss`、`is` 结尾的单词保持不变
+
+/// 不规则复数，(复数, 单数)
+const IRREGULARS: [(&str, &str); 10] = [
+    ("people", "person"),
+    ("men", "man"),
+    ("women", "woman"),
+    ("children", "child"),
+    ("mice", "mouse"),
+    ("geese", "goose"),
+    ("teeth", "tooth"),
+    ("feet", "foot"),
+    ("indices", "index"),
+    ("matrices", "matrix"),
+];
+
+/// 单复数同形或不可数的单词
+const UNCOUNTABLES: [&str; 10] = [
+    "data",
+    "metadata",
+    "equipment",
+    "information",
+    "news",
+    "series",
+    "species",
+    "sheep",
+    "fish",
+    "media",
+];
+
+/// 将名称转为单数，snake_case 名称只转换最后一个单词，如 `order_items` -> `order_item`
+pub fn singularize(name: &str) -> String {
+    let (head, word) = match name.rfind('_') {
+        Some(i) => name.split_at(i + 1),
+        None => ("", name),
+    };
+    format!("{head}{}", singularize_word(word))
+}
+
+fn singularize_word(word: &str) -> String {
+    let lower = word.to_ascii_lowercase();
+    if !word.is_ascii() || UNCOUNTABLES.contains(&lower.as_str()) {
+        return word.to_string();
+    }
+    // 不规则复数替换结尾部分，保留前面的字符，如 `salesmen` -> `salesman`
+    if let Some((plural, singular)) = IRREGULARS.iter().find(|(p, _)| lower.ends_with(p)) {
+        return format!("{}{singular}", &word[..word.len() - plural.len()]);
+    }
+    let stem = |n: usize| word[..word.len() - n].to_string();
+    if lower.ends_with("ies") && lower.len() > 4 {
+        return format!("{}y", stem(3));
+    }
+    if ["sses", "xes", "ches", "shes", "zzes"]
+        .iter()
+        .any(|s| lower.ends_with(s))
+    {
+        return stem(2);
+    }
+    if lower.ends_with('s') && !["us", "ss", "is"].iter().any(|s| lower.ends_with(s)) {
+        return stem(1);
+    }
+    word.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn singular_names() {
+        for (plural, singular) in [
+            ("categories", "category"),
+            ("people", "person"),
+            ("status", "status"),
+            ("users", "user"),
+            ("boxes", "box"),
+            ("order_items", "order_item"),
+            ("news", "news"),
+            ("Users", "User"),
+        ] {
+            assert_eq!(singularize(plural), singular, "{plural}");
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Java 代码生成辅助
+
+use std::{collections::BTreeSet, path::PathBuf};
+
+use database::Column;
+use heck::ToLowerCamelCase as _;
+use serde::Serialize;
+
+/// Java 关键字及保留字面量
+const JAVA_KEYWORDS: [&str; 53] = [
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "try",
+    "void",
+    "volatile",
+    "while",
+    "true",
+    "false",
+    "null",
+];
+
+/// 实体字段
+#[derive(Debug, Serialize)]
+pub struct JavaField<'a> {
+    /// 字段名
+    pub name: String,
+    /// 字段名是否与列名的驼峰形式不同，不同时需要显式映射列名
+    pub renamed: bool,
+    /// Java 类型
+    pub r#type: &'static str,
+    /// 对应的列
+    pub column: &'a Column,
+}
+
+impl<'a> JavaField<'a> {
+    pub fn new(column: &'a Column) -> Self {
+        let camel = column.name.to_lower_camel_case();
+        let name = field_name(&column.name);
+        Self {
+            renamed: name != camel,
+            name,
+            r#type: java_type(&column.rust_type),
+            column,
+        }
+    }
+}
+
+/// 列名转换为 Java 字段名，与关键字冲突时添加 `_` 后缀，如 `class` -> `class_`
+pub fn field_name(column_name: &str) -> String {
+    let name = column_name.to_lower_camel_case();
+    if JAVA_KEYWORDS.contains(&name.as_str()) {
+        format!("{name}_")
+    } else {
+        name
+    }
+}
+
+/// Rust 类型转换为 Java 类型
+pub fn java_type(rust_type: &str) -> &'static str {
+    match rust_type {
+        "bool" => "Boolean",
+        "i8" => "Byte",
+        "i16" | "u8" => "Short",
+        "i32" | "u16" => "Integer",
+        "i64" | "u32" => "Long",
+        "u64" => "BigInteger",
+        "f32" => "Float",
+        "f64" => "Double",
+        "Vec<u8>" => "byte[]",
+        "time::Date" => "LocalDate",
+        "time::Time" => "LocalTime",
+        "time::PrimitiveDateTime" => "LocalDateTime",
+        "time::OffsetDateTime" => "OffsetDateTime",
+        "bigdecimal::BigDecimal" | "rust_decimal::Decimal" => "BigDecimal",
+        "uuid::Uuid" => "UUID",
+        _ => "String",
+    }
+}
+
+/// 字段类型需要导入的包
+pub fn imports(fields: &[JavaField]) -> BTreeSet<&'static str> {
+    fields
+        .iter()
+        .filter_map(|f| match f.r#type {
+            "BigInteger" => Some("java.math.BigInteger"),
+            "BigDecimal" => Some("java.math.BigDecimal"),
+            "LocalDate" => Some("java.time.LocalDate"),
+            "LocalTime" => Some("java.time.LocalTime"),
+            "LocalDateTime" => Some("java.time.LocalDateTime"),
+            "OffsetDateTime" => Some("java.time.OffsetDateTime"),
+            "UUID" => Some("java.util.UUID"),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 根据包名生成文件的相对路径，如 `com.example.entity` -> `com/example/entity/User.java`
+pub fn file_path(package: Option<&str>, file_name: &str) -> String {
+    let mut path = package
+        .unwrap_or_default()
+        .split('.')
+        .filter(|p| !p.is_empty())
+        .collect::<PathBuf>();
+    path.push(file_name);
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_field_names() {
+        assert_eq!(field_name("class"), "class_");
+        assert_eq!(field_name("default"), "default_");
+        assert_eq!(field_name("user_name"), "userName");
+        // 驼峰形式才与关键字冲突时同样转义，如 `Int` -> `int_`
+        assert_eq!(field_name("Int"), "int_");
+
+        let column = Column {
+            name: "class".to_string(),
+            rust_type: "String".to_string(),
+            ..Default::default()
+        };
+        let field = JavaField::new(&column);
+        assert_eq!(field.name, "class_");
+        assert!(field.renamed);
+    }
+}
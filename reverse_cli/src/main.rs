@@ -3,37 +3,46 @@
 //! 指定数据库和表名，生成对应的模型代码
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fs::{self, File},
     io::Write,
     path::{Path, PathBuf},
 };
 
 use anyhow::anyhow;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use config::GeneratorConfig;
-use database::{Column, Table, database_metadata};
-use heck::ToUpperCamelCase as _;
+use database::{Column, ColumnType, DatabaseMetadata, Table, TableKind};
+use heck::ToLowerCamelCase as _;
 use rust_embed::Embed;
 use template::{MOD_TEMPLATE, MODEL_TEMPLATE};
 use tera::Tera;
 
 use crate::{
     config::Language,
+    deps::DEPS_FILE,
+    drift::Drift,
+    java::JavaField,
+    manifest::Manifest,
     template::{ERROR_TEMPLATE, RESULT_TEMPLATE},
 };
 
+mod alias;
+mod changelog;
 mod config;
+mod crud;
+mod deps;
+mod diesel;
+mod drift;
+mod enums;
+mod index;
+mod inflect;
+mod java;
+mod manifest;
+mod proto;
+mod sea_orm;
 mod template;
-
-/// Rust 1.85关键字
-const KEYWORDS: [&str; 53] = [
-    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
-    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
-    "ref", "return", "Self", "self", "static", "struct", "super", "trait", "true", "type", "union",
-    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
-    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
-];
+mod validate;
 
 #[derive(Embed)]
 #[folder = "templates/"]
@@ -56,71 +65,255 @@ pub struct Generator {
     /// 配置文件路径
     #[clap(short('c'), long, default_value = "./generator.toml")]
     pub config_path: PathBuf,
+    /// 输出调试信息，如各表的列及生成的代码
+    #[clap(short, long, global = true)]
+    pub verbose: bool,
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// 生成代码
-    Create,
+    Create {
+        /// 只生成指定的表，可以重复使用，覆盖配置文件中的 table_names，仍然会跳过忽略的表
+        #[arg(long = "table")]
+        tables: Vec<String>,
+        /// 数据库模式，覆盖配置文件中的 schema
+        #[arg(long)]
+        schema: Option<String>,
+    },
+    /// 预览生成的代码，只输出文件路径及内容，不写入文件
+    Preview {
+        /// 输出到一个文件，未指定时输出到标准输出
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     /// 生成模板
     Template,
+    /// 清理已删除表对应的生成文件
+    Clean {
+        /// 只打印将要删除的文件，不实际删除
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 检测数据库结构与上次生成时是否一致，存在差异时以非零状态码退出
+    Drift,
+    /// 将数据库结构与上次生成时的差异写入 Markdown 变更日志片段
+    Changelog {
+        /// 输出文件
+        #[arg(short, long, default_value = "CHANGELOG.md")]
+        output: PathBuf,
+    },
+    /// 导出模式下所有表、列及索引的结构快照
+    Export {
+        /// 导出格式
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// 输出文件，未指定时输出到标准输出
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// 比较两个数据库的结构，存在差异时以非零状态码退出
+    Diff {
+        /// 原数据库连接地址
+        old: String,
+        /// 新数据库连接地址
+        new: String,
+        /// 数据库模式，未指定时使用各自连接的当前模式
+        #[arg(long)]
+        schema: Option<String>,
+    },
+}
+
+/// 结构快照的导出格式
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Json,
+    /// dbdiagram.io 等工具使用的 DBML
+    Dbml,
+    /// Mermaid ER 图
+    Mermaid,
+}
+
+/// 写入文件的结果
+#[derive(Debug, Default)]
+pub struct WriteReport {
+    /// 实际写入的文件
+    pub written: Vec<PathBuf>,
+    /// 已存在且未开启 override 而跳过的文件
+    pub skipped: Vec<PathBuf>,
+    /// 内容与上次生成相同而未重复写入的文件
+    pub unchanged: Vec<PathBuf>,
+    /// 新建的目录
+    pub created_dirs: Vec<PathBuf>,
+}
+
+impl WriteReport {
+    fn extend(&mut self, other: WriteReport) {
+        self.written.extend(other.written);
+        self.skipped.extend(other.skipped);
+        self.unchanged.extend(other.unchanged);
+        self.created_dirs.extend(other.created_dirs);
+    }
 }
 
 impl Generator {
-    pub async fn run(&mut self, config: &mut GeneratorConfig) -> anyhow::Result<()> {
+    pub async fn run(&mut self, config: &mut GeneratorConfig) -> anyhow::Result<WriteReport> {
         config.deal_path();
         database::init();
 
-        let (tables, tables_columns) = self.prepare(config).await?;
-        if tables.is_empty() {
-            println!("tables is empty");
-            return Ok(());
-        }
-        if tables_columns.is_empty() {
-            println!("table columns is empty");
-            return Ok(());
-        }
+        // 各模式共用同一个连接池
+        let meta = config.metadata().await?;
+        self.run_with(meta.as_ref(), config).await
+    }
 
-        let (tables, tables_columns) = self.prepare(config).await?;
-        if tables.is_empty() {
-            eprintln!("table is empty");
-            return Ok(());
+    /// 使用已有的元数据来源生成代码
+    async fn run_with(
+        &mut self,
+        meta: &dyn DatabaseMetadata,
+        config: &mut GeneratorConfig,
+    ) -> anyhow::Result<WriteReport> {
+        config.resolve_schemas(meta).await?;
+        let path = config.path.clone();
+        let mut report = WriteReport::default();
+        for schema in config.schemas.clone() {
+            config.use_schema(&schema, &path);
+            let (tables, tables_columns) = self.prepare(meta, config).await?;
+            if tables.is_empty() || tables_columns.is_empty() {
+                eprintln!("模式 {schema} 没有需要生成代码的表");
+                continue;
+            }
+            report.extend(self.write(meta, config, tables, tables_columns).await?);
         }
-        if tables_columns.is_empty() {
-            eprintln!("table columns is empty");
-            return Ok(());
+        Ok(report)
+    }
+
+    /// 预览生成的文件，不写入文件
+    /// return
+    ///     按路径排序的 (文件路径, 代码)
+    async fn preview_files(
+        &self,
+        config: &mut GeneratorConfig,
+    ) -> anyhow::Result<Vec<(PathBuf, String)>> {
+        config.deal_path();
+        database::init();
+
+        let meta = config.metadata().await?;
+        config.resolve_schemas(meta.as_ref()).await?;
+        let path = config.path.clone();
+        let mut files = vec![];
+        for schema in config.schemas.clone() {
+            config.use_schema(&schema, &path);
+            let (tables, tables_columns) = self.prepare(meta.as_ref(), config).await?;
+            if tables.is_empty() || tables_columns.is_empty() {
+                continue;
+            }
+            let data = self
+                .preview(meta.as_ref(), config, tables, tables_columns)
+                .await?;
+            files.extend(
+                Self::files(config, data)
+                    .into_iter()
+                    .map(|(path, code, _)| (path, code)),
+            );
         }
-        self.write(&config, tables, tables_columns).await?;
-        Ok(())
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(files)
     }
 
-    async fn prepare(&self, config: &GeneratorConfig) -> anyhow::Result<(Vec<Table>, Vec<Column>)> {
-        let meta = database_metadata(&config.database_url).await;
-        let tables = meta.tables("", &config.schema).await?;
-        let table_names;
-        if config.table_names.is_empty() {
-            table_names = tables.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
-        } else {
-            table_names = config.table_names.clone();
+    async fn prepare(
+        &self,
+        meta: &dyn DatabaseMetadata,
+        config: &GeneratorConfig,
+    ) -> anyhow::Result<(Vec<Table>, Vec<Column>)> {
+        let mut tables = meta.tables("", &config.schema).await?;
+        for name in config.table_names.iter().filter(|t| config.is_ignored(t)) {
+            eprintln!("表 {name} 在忽略列表中，已跳过");
+        }
+        // 指定的表不存在时多半是表名写错，提前报错；多个模式时表可能只存在于部分模式中，只提示
+        let missing = config
+            .table_names
+            .iter()
+            .filter(|name| !tables.iter().any(|t| &t.name == *name))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let missing = missing.join(", ");
+            if config.schemas.len() > 1 {
+                eprintln!("模式 {} 中不存在表 {missing}", config.schema);
+            } else {
+                return Err(anyhow!("表 {missing} 不存在"));
+            }
+        }
+        tables.retain(|t| {
+            !config.is_ignored(&t.name)
+                && (config.include_views || t.kind == TableKind::BaseTable)
+                && (config.table_names.is_empty() || config.table_names.contains(&t.name))
+        });
+        // Mysql、Postgres 一次查询所有表的列，其他数据源逐表并发查询
+        let table_names = tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>();
+        let mut columns = meta.all_columns("", &config.schema, &table_names).await?;
+        if !config.bit_as_bool {
+            for column in columns
+                .iter_mut()
+                .filter(|c| c.r#type == Some(ColumnType::Bit) && c.rust_type == "bool")
+            {
+                column.rust_type = "u8".to_string();
+            }
         }
-        let mut columns = vec![];
-        for t in table_names {
-            columns.extend(meta.columns("", &config.schema, &t).await?);
+        if !config.tinyint1_as_bool {
+            for column in columns.iter_mut().filter(|c| {
+                c.r#type == Some(ColumnType::TinyInt)
+                    && c.length == Some(1)
+                    && c.rust_type == "bool"
+            }) {
+                column.rust_type = "i8".to_string();
+            }
+        }
+        for column in columns.iter_mut() {
+            let ty = config.datetime_backend.rust_type(&column.rust_type);
+            let ty = config.decimal_backend.rust_type(ty, column.length);
+            if ty != column.rust_type {
+                column.rust_type = ty.to_string();
+            }
+        }
+        if config.honor_comment_hints {
+            for column in columns.iter_mut() {
+                if let Some(ty) = column.type_hint() {
+                    column.rust_type = ty.to_string();
+                }
+            }
+        }
+        for column in columns.iter_mut() {
+            if let Some(ty) = config.type_override(column) {
+                column.rust_type = ty.to_string();
+            }
         }
         Ok((tables, columns))
     }
 
-    /// 渲染模板
+    /// 渲染模板，优先使用 template_dir 中的同名模板，不存在时使用内置模板
     async fn render(
         &self,
+        config: &GeneratorConfig,
         path: &str,
         tera: &mut Tera,
         ctx: &tera::Context,
     ) -> anyhow::Result<String> {
-        let template = Templates::get(path).ok_or(anyhow!("模板文件不存在"))?;
-        Ok(tera
-            .render_str(str::from_utf8(template.data.as_ref()).unwrap(), ctx)
-            .map_err(|err| anyhow!("模板渲染失败，{err}"))?)
+        let custom = config
+            .template_dir
+            .as_ref()
+            .map(|dir| dir.join(path))
+            .filter(|path| path.is_file());
+        let template = match custom {
+            Some(path) => fs::read_to_string(&path)
+                .map_err(|err| anyhow!("读取模板文件 {} 错误，{err}", path.display()))?,
+            None => {
+                let template = Templates::get(path).ok_or(anyhow!("模板文件不存在"))?;
+                String::from_utf8_lossy(template.data.as_ref()).into_owned()
+            }
+        };
+        tera.render_str(&template, ctx)
+            .map_err(|err| anyhow!("模板渲染失败，{err}"))
     }
 
     /// 预览代码
@@ -131,68 +324,284 @@ impl Generator {
     ///         V：对应的code
     async fn preview(
         &self,
+        meta: &dyn DatabaseMetadata,
         config: &GeneratorConfig,
         tables: Vec<Table>,
-        tables_columns: Vec<Column>,
+        mut tables_columns: Vec<Column>,
     ) -> anyhow::Result<HashMap<String, HashMap<String, String>>> {
         let mut res_map = HashMap::with_capacity(config.table_names.len());
 
+        let struct_names = tables
+            .iter()
+            .map(|t| config.struct_name(&t.name))
+            .collect::<Vec<_>>();
+        let type_aliases = match config.language {
+            Language::Rust if config.gen_type_aliases => {
+                alias::type_aliases(&tables_columns, &struct_names)
+            }
+            _ => vec![],
+        };
+        // 枚举名不能与模型结构体及类型别名重名
+        let reserved_names = struct_names
+            .iter()
+            .cloned()
+            .chain(type_aliases.iter().map(|a| a.name.clone()))
+            .collect::<Vec<_>>();
+        // 非空时间字段无法派生 Default，按原始类型记录取当前时间的表达式，K：表名.列名
+        let now_exprs: HashMap<String, String> = tables_columns
+            .iter()
+            .filter(|c| !c.is_null)
+            .filter_map(|c| {
+                let expr = config.timestamp_default.now_expr(&c.rust_type)?;
+                Some((format!("{}.{}", c.table_name, c.name), expr))
+            })
+            .collect();
+        // 依赖根据实际类型推断，需要在替换为别名之前生成
+        let deps = match config.language {
+            Language::Rust if config.gen_deps => {
+                Some(deps::snippet(config, config.driver()?, &tables_columns))
+            }
+            _ => None,
+        };
+        // 检查约束中的数值范围，需要在替换为别名之前按实际类型生成参数，K：表名.列名，V：range 参数
+        let mut ranges: HashMap<String, String> = HashMap::new();
+        if config.gen_validator && matches!(config.language, Language::Rust) {
+            let driver = config.driver()?;
+            for table in tables.iter() {
+                // Mysql 8.0.16 之前等不支持检查约束的数据源不生成范围校验，其他错误照常返回
+                let checks = match meta
+                    .check_constraints("", &config.schema, &table.name)
+                    .await
+                {
+                    Err(database::error::Error::E("not supported")) => vec![],
+                    checks => checks?,
+                };
+                for range in database::numeric_ranges(&checks, driver) {
+                    let Some(column) = tables_columns
+                        .iter()
+                        .find(|c| c.table_name == table.name && c.name == range.column)
+                    else {
+                        continue;
+                    };
+                    if let Some(args) = validate::range_args(&range, &column.rust_type) {
+                        ranges.insert(format!("{}.{}", table.name, column.name), args);
+                    }
+                }
+            }
+        }
+        alias::apply(&type_aliases, &mut tables_columns);
+
+        // 表的外键，K：表名，sea-orm 根据外键生成 Relation，diesel 生成 joinable!
+        let mut foreign_keys: HashMap<String, Vec<database::ForeignKey>> = HashMap::new();
+        if config.foreign_key_hints
+            || matches!(config.language, Language::SeaOrm | Language::Diesel)
+        {
+            for table in tables.iter() {
+                foreign_keys.insert(
+                    table.name.clone(),
+                    meta.foreign_keys("", &config.schema, &table.name).await?,
+                );
+            }
+        }
+        // 表的索引，用于索引说明和 upsert 的冲突目标，K：表名
+        let mut table_indexes: HashMap<String, Vec<database::Index>> = HashMap::new();
+        if config.index_docs || (config.gen_upsert && matches!(config.language, Language::Rust)) {
+            for table in tables.iter() {
+                table_indexes.insert(
+                    table.name.clone(),
+                    meta.indexs("", &config.schema, &table.name).await?,
+                );
+            }
+        }
+        // 表的索引说明，K：表名
+        let mut indexes: HashMap<String, Vec<index::IndexDoc>> = HashMap::new();
+        if config.index_docs {
+            for (table_name, table_indexes) in table_indexes.iter() {
+                indexes.insert(table_name.clone(), index::index_docs(table_indexes));
+            }
+        }
+        // 外键列引用的表和列，K：表名.列名，V：引用的表名.列名
+        let mut references: HashMap<String, String> = HashMap::new();
+        if config.foreign_key_hints {
+            for fk in foreign_keys.values().flatten() {
+                for (column, referenced) in fk.columns.iter().zip(&fk.referenced_columns) {
+                    references.insert(
+                        format!("{}.{column}", fk.table_name),
+                        format!("{}.{referenced}", fk.referenced_table),
+                    );
+                }
+            }
+        }
+
+        // 带取值列表的 ENUM 列生成枚举，字段类型替换为枚举名，K：表名，V：表中的枚举
+        let mut table_enums: HashMap<String, Vec<enums::RustEnum>> = HashMap::new();
+        // 共用的枚举只在首个使用的表中生成，其他表导入，K：表名，V：导入的枚举名
+        let mut enum_imports: HashMap<String, BTreeSet<String>> = HashMap::new();
+        if let Language::Rust = config.language {
+            let enum_names = enums::enum_names(&tables_columns, &reserved_names);
+            // K：枚举名，V：生成该枚举的表名
+            let mut enum_tables: HashMap<String, String> = HashMap::new();
+            for column in tables_columns.iter_mut() {
+                let key = format!("{}.{}", column.table_name, column.name);
+                if let Some(name) = enum_names.get(&key) {
+                    match enum_tables.get(name) {
+                        Some(table) if table == &column.table_name => {}
+                        Some(_) => {
+                            enum_imports
+                                .entry(column.table_name.clone())
+                                .or_default()
+                                .insert(name.clone());
+                        }
+                        None => {
+                            enum_tables.insert(name.clone(), column.table_name.clone());
+                            table_enums
+                                .entry(column.table_name.clone())
+                                .or_default()
+                                .push(enums::rust_enum(name, column));
+                        }
+                    }
+                    column.rust_type = name.clone();
+                }
+            }
+        }
+
         // 将tables转换为map，K：表名，V：表信息
         let table_map: HashMap<String, Table> =
             tables.into_iter().map(|t| (t.name.to_owned(), t)).collect();
 
         // 组装表信息和表列信息，K：表名，V：表列信息
-        // FIXME：有没有办法直接将Vec分组，类似Java的Collectors.groupby
-        let table_column_map =
-            table_map
-                .keys()
-                .fold(HashMap::new(), |mut table_column_map, table_name| {
-                    table_column_map.insert(
-                        table_name,
-                        tables_columns
-                            .iter()
-                            .filter(|table_column| {
-                                Some(table_name.clone()) == Some(table_column.table_name.clone())
-                            })
-                            .collect::<Vec<_>>(),
-                    );
-                    table_column_map
-                });
-        dbg!(&table_column_map);
+        // 一次遍历按表名分组，再按列在表中的位置排序，字段顺序与数据库一致
+        let mut table_column_map: HashMap<&String, Vec<&Column>> =
+            table_map.keys().map(|name| (name, vec![])).collect();
+        for column in tables_columns.iter() {
+            if let Some(columns) = table_column_map.get_mut(&column.table_name) {
+                columns.push(column);
+            }
+        }
+        for columns in table_column_map.values_mut() {
+            columns.sort_by_key(|c| c.ordinal_position);
+        }
+        if self.verbose {
+            eprintln!("{table_column_map:#?}");
+        }
 
         // 创建模板引擎
         let mut ctx = tera::Context::new();
-        ctx.insert("driver", &config.driver()?);
+        let driver = config.driver()?;
+        ctx.insert("driver", &driver);
+        ctx.insert("limit_clause", &driver.limit_clause("{limit}", "{offset}"));
         ctx.insert("driver_url", &config.database_url);
         ctx.insert("table_names", &table_map);
+        // 模型文件的模块名，K：表名
+        let module_names = table_map
+            .keys()
+            .map(|t| (t, config.module_name(t)))
+            .collect::<HashMap<_, _>>();
+        ctx.insert("module_names", &module_names);
+        ctx.insert("gen_validator", &config.gen_validator);
+        ctx.insert("gen_new_model", &config.gen_new_model);
+        ctx.insert("type_aliases", &type_aliases);
+        ctx.insert("rust_imports", &config.rust_imports());
+        ctx.insert(
+            "nullable_wrapper",
+            config.nullable_wrapper.as_deref().unwrap_or("Option"),
+        );
         let mut tera = tera::Tera::default();
+        tera.register_filter("ident", ident);
         match config.language {
             Language::Rust => {
                 if config.gen_error {
+                    let file_name = config.language.file_name("error");
                     let mut map = HashMap::with_capacity(1);
                     map.insert(
-                        "error.rs".into(),
-                        self.render("rust/error.html", &mut tera, &ctx).await?,
+                        file_name.clone(),
+                        self.render(config, "rust/error.html", &mut tera, &ctx)
+                            .await?,
                     );
-                    res_map.insert("error.rs".into(), map);
+                    res_map.insert(file_name, map);
                 }
                 if config.gen_mod {
+                    let file_name = config.language.file_name("mod");
                     let mut map = HashMap::with_capacity(1);
                     map.insert(
-                        "mod.rs".into(),
-                        self.render("rust/mod.html", &mut tera, &ctx).await?,
+                        file_name.clone(),
+                        self.render(config, "rust/mod.html", &mut tera, &ctx)
+                            .await?,
                     );
-                    res_map.insert("mod.rs".into(), map);
+                    res_map.insert(file_name, map);
+                }
+                if let Some(deps) = deps {
+                    let mut map = HashMap::with_capacity(1);
+                    map.insert(DEPS_FILE.to_string(), deps);
+                    res_map.insert(DEPS_FILE.to_string(), map);
+                }
+                if !type_aliases.is_empty() {
+                    let file_name = config.language.file_name("types");
+                    let mut map = HashMap::with_capacity(1);
+                    map.insert(
+                        file_name.clone(),
+                        self.render(config, "rust/types.html", &mut tera, &ctx)
+                            .await?,
+                    );
+                    res_map.insert(file_name, map);
                 }
 
                 for (table_name, table) in table_map.iter() {
                     let column = table_column_map.get(&table_name);
                     // 创建上下文
-                    ctx.insert("struct_name", &table_name.to_upper_camel_case());
+                    ctx.insert("struct_name", &config.struct_name(table_name));
                     ctx.insert("table", &table);
+                    ctx.insert("indexes", &indexes.get(table_name.as_str()));
                     let mut has_columns = false;
+                    let mut upsert_sql = None;
+                    let mut crud_sql = None;
+                    let mut aliases = BTreeSet::new();
+                    let mut default_exprs = BTreeMap::new();
+                    let mut renames = BTreeSet::new();
+                    let mut column_references = BTreeMap::new();
+                    let mut column_ranges = BTreeMap::new();
                     if let Some(columns) = column {
+                        default_exprs.extend(columns.iter().filter_map(|c| {
+                            let expr = now_exprs.get(&format!("{}.{}", c.table_name, c.name))?;
+                            Some((c.name.as_str(), expr))
+                        }));
+                        column_references.extend(columns.iter().filter_map(|c| {
+                            let referenced =
+                                references.get(&format!("{}.{}", c.table_name, c.name))?;
+                            Some((c.name.as_str(), referenced))
+                        }));
+                        column_ranges.extend(columns.iter().filter_map(|c| {
+                            let args = ranges.get(&format!("{}.{}", c.table_name, c.name))?;
+                            Some((c.name.as_str(), args))
+                        }));
                         has_columns = !columns.is_empty();
+                        aliases.extend(
+                            columns
+                                .iter()
+                                .filter(|c| type_aliases.iter().any(|a| a.name == c.rust_type))
+                                .map(|c| c.rust_type.as_str()),
+                        );
+                        renames.extend(
+                            columns
+                                .iter()
+                                .filter(|c| database::needs_rename(&c.name))
+                                .map(|c| c.name.as_str()),
+                        );
+                        // 存在主键或唯一键时才能生成 upsert
+                        if config.gen_upsert {
+                            upsert_sql = database::upsert_sql(
+                                table_name,
+                                columns,
+                                table_indexes
+                                    .get(table_name.as_str())
+                                    .map_or(&[], |ix| ix.as_slice()),
+                                &[],
+                                driver,
+                            );
+                        }
+                        if config.gen_crud {
+                            crud_sql = crud::crud_sql(table_name, columns, driver);
+                        }
                         ctx.insert("column_num", &columns.len());
                         ctx.insert("columns", &columns);
                         ctx.insert(
@@ -205,12 +614,41 @@ impl Generator {
                         );
                     }
                     ctx.insert("has_columns", &has_columns);
+                    ctx.insert("upsert_sql", &upsert_sql);
+                    ctx.insert("crud", &crud_sql);
+                    ctx.insert("aliases", &aliases);
+                    ctx.insert("default_exprs", &default_exprs);
+                    ctx.insert("derives", &config.derives(!default_exprs.is_empty()));
+                    ctx.insert("renames", &renames);
+                    ctx.insert("references", &column_references);
+                    ctx.insert("ranges", &column_ranges);
+                    ctx.insert(
+                        "enums",
+                        table_enums
+                            .get(table_name)
+                            .map_or(&[][..], |e| e.as_slice()),
+                    );
+                    ctx.insert(
+                        "enum_imports",
+                        &enum_imports.get(table_name).cloned().unwrap_or_default(),
+                    );
 
                     let mut map = HashMap::with_capacity(3);
                     if config.gen_entity {
+                        let mut code = self
+                            .render(config, "rust/model.html", &mut tera, &ctx)
+                            .await?;
+                        // 增删改查方法追加到模型文件中
+                        if crud_sql.is_some() {
+                            code.push_str(
+                                &self
+                                    .render(config, "rust/crud.html", &mut tera, &ctx)
+                                    .await?,
+                            );
+                        }
                         map.insert(
-                            format!("{table_name}.rs"),
-                            self.render("rust/model.html", &mut tera, &ctx).await?,
+                            config.language.file_name(&config.module_name(table_name)),
+                            code,
                         );
                     }
                     // if self.gen_service {
@@ -230,140 +668,627 @@ impl Generator {
                 }
             }
             Language::Java => {
-                for table in &config.table_names {
+                for (table_name, table) in table_map.iter() {
+                    let columns = table_column_map
+                        .get(&table_name)
+                        .map(|c| c.as_slice())
+                        .unwrap_or_default();
+                    let fields = columns
+                        .iter()
+                        .map(|c| JavaField::new(c))
+                        .collect::<Vec<_>>();
+                    let struct_name = config.struct_name(table_name);
+                    let packages = &config.packages;
+                    ctx.insert("struct_name", &struct_name);
+                    ctx.insert("table", &table);
+                    ctx.insert("fields", &fields);
+                    ctx.insert("imports", &java::imports(&fields));
+                    ctx.insert("path_name", &struct_name.to_lower_camel_case());
+                    ctx.insert("entity_package_name", &packages.entity);
+                    ctx.insert("mapper_package_name", &packages.mapper);
+                    ctx.insert("service_package_name", &packages.service);
+                    ctx.insert("service_impl_package_name", &packages.service_impl);
+                    ctx.insert("controller_package_name", &packages.controller);
+
+                    // K：相对于生成路径、按包名划分目录的文件路径
                     let mut map = HashMap::with_capacity(6);
                     if config.gen_entity {
                         map.insert(
-                            "entity.java".into(),
-                            self.render("java/entity.html", &mut tera, &ctx).await?,
+                            java::file_path(
+                                packages.entity.as_deref(),
+                                &format!("{struct_name}.java"),
+                            ),
+                            self.render(config, "java/entity.html", &mut tera, &ctx)
+                                .await?,
                         );
                     }
                     if config.gen_mapper {
                         map.insert(
-                            "mapper.java".into(),
-                            self.render("java/mapper.html", &mut tera, &ctx).await?,
+                            java::file_path(
+                                packages.mapper.as_deref(),
+                                &format!("{struct_name}Mapper.java"),
+                            ),
+                            self.render(config, "java/mapper.html", &mut tera, &ctx)
+                                .await?,
                         );
                     }
                     if config.gen_mapper_xml {
                         map.insert(
-                            "mapper.xml".into(),
-                            self.render("java/mapperXml.html", &mut tera, &ctx).await?,
+                            java::file_path(
+                                packages.mapper_xml.as_deref(),
+                                &format!("{struct_name}Mapper.xml"),
+                            ),
+                            self.render(config, "java/mapperXml.html", &mut tera, &ctx)
+                                .await?,
                         );
                     }
                     if config.gen_service {
                         map.insert(
-                            "service.java".into(),
-                            self.render("java/service.html", &mut tera, &ctx).await?,
+                            java::file_path(
+                                packages.service.as_deref(),
+                                &format!("I{struct_name}Service.java"),
+                            ),
+                            self.render(config, "java/service.html", &mut tera, &ctx)
+                                .await?,
+                        );
+                        map.insert(
+                            java::file_path(
+                                packages.service_impl.as_deref(),
+                                &format!("{struct_name}ServiceImpl.java"),
+                            ),
+                            self.render(config, "java/serviceImpl.html", &mut tera, &ctx)
+                                .await?,
                         );
                     }
                     if config.gen_controller {
                         map.insert(
-                            "controller.java".into(),
-                            self.render("java/controller.html", &mut tera, &ctx).await?,
+                            java::file_path(
+                                packages.controller.as_deref(),
+                                &format!("{struct_name}Controller.java"),
+                            ),
+                            self.render(config, "java/controller.html", &mut tera, &ctx)
+                                .await?,
                         );
                     }
-                    res_map.insert(table.into(), map);
+                    res_map.insert(table_name.into(), map);
+                }
+            }
+            Language::SeaOrm => {
+                if config.gen_mod {
+                    let file_name = config.language.file_name("mod");
+                    let entity_names = table_map
+                        .keys()
+                        .map(|t| (t, config.struct_name(t)))
+                        .collect::<HashMap<_, _>>();
+                    ctx.insert("entity_names", &entity_names);
+                    let mut map = HashMap::with_capacity(1);
+                    map.insert(
+                        file_name.clone(),
+                        self.render(config, "sea_orm/mod.html", &mut tera, &ctx)
+                            .await?,
+                    );
+                    res_map.insert(file_name, map);
+                }
+                for (table_name, table) in table_map.iter() {
+                    let columns = table_column_map
+                        .get(&table_name)
+                        .map(|c| c.as_slice())
+                        .unwrap_or_default();
+                    let fields = columns
+                        .iter()
+                        .map(|c| sea_orm::SeaOrmField::new(c))
+                        .collect::<Vec<_>>();
+                    let relations = sea_orm::relations(
+                        table_name,
+                        foreign_keys
+                            .get(table_name)
+                            .map_or(&[][..], |f| f.as_slice()),
+                        |t| config.struct_name(t),
+                    );
+                    ctx.insert("table", &table);
+                    ctx.insert("fields", &fields);
+                    ctx.insert("relations", &relations);
+
+                    let mut map = HashMap::with_capacity(1);
+                    if config.gen_entity {
+                        map.insert(
+                            config.language.file_name(&config.module_name(table_name)),
+                            self.render(config, "sea_orm/entity.html", &mut tera, &ctx)
+                                .await?,
+                        );
+                    }
+                    res_map.insert(table_name.into(), map);
+                }
+            }
+            Language::Diesel => {
+                let mut tables = table_map
+                    .iter()
+                    .map(|(table_name, table)| {
+                        let columns = table_column_map
+                            .get(&table_name)
+                            .cloned()
+                            .unwrap_or_default();
+                        (table, columns)
+                    })
+                    .collect::<Vec<_>>();
+                tables.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+                let foreign_keys = foreign_keys.into_values().flatten().collect::<Vec<_>>();
+                ctx.insert("schema", &diesel::schema(&tables, &foreign_keys, driver));
+
+                let mut map = HashMap::with_capacity(1);
+                map.insert(
+                    diesel::SCHEMA_FILE.to_string(),
+                    self.render(config, "diesel/schema.html", &mut tera, &ctx)
+                        .await?,
+                );
+                res_map.insert(diesel::SCHEMA_FILE.to_string(), map);
+            }
+            Language::Proto => {
+                for (table_name, table) in table_map.iter() {
+                    let columns = table_column_map
+                        .get(&table_name)
+                        .map(|c| c.as_slice())
+                        .unwrap_or_default();
+                    let fields = columns
+                        .iter()
+                        .zip(1..)
+                        .map(|(c, number)| proto::ProtoField::new(c, number))
+                        .collect::<Vec<_>>();
+                    ctx.insert("struct_name", &config.struct_name(table_name));
+                    ctx.insert("table", &table);
+                    ctx.insert("fields", &fields);
+
+                    let mut map = HashMap::with_capacity(1);
+                    if config.gen_entity {
+                        map.insert(
+                            config.language.file_name(&config.module_name(table_name)),
+                            self.render(config, "proto/message.html", &mut tera, &ctx)
+                                .await?,
+                        );
+                    }
+                    res_map.insert(table_name.into(), map);
                 }
             }
         }
         Ok(res_map)
     }
 
-    /// 写入文件
+    /// 写入文件，返回写入、跳过的文件
     async fn write(
         &self,
+        meta: &dyn DatabaseMetadata,
         config: &GeneratorConfig,
         tables: Vec<Table>,
         tables_columns: Vec<Column>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<WriteReport> {
         if tables.is_empty() {
             return Err(anyhow!("表信息为空"));
         }
 
-        let data = self.preview(config, tables, tables_columns).await?;
-        dbg!(&data);
+        let mut manifest = Manifest::load(&config.path)?;
+        for table in tables.iter() {
+            manifest.record_table(
+                &table.name,
+                tables_columns.iter().filter(|c| c.table_name == table.name),
+            );
+        }
+        let data = self.preview(meta, config, tables, tables_columns).await?;
+        if self.verbose {
+            eprintln!("{data:#?}");
+        }
+        let mut report = WriteReport::default();
+        for (path, code, table) in Self::files(config, data) {
+            Self::write_tracked(
+                &mut manifest,
+                &mut report,
+                path,
+                &code,
+                table.as_deref(),
+                config.r#override,
+            )
+            .await?;
+        }
+        manifest.save(&config.path)?;
+        Ok(report)
+    }
+
+    /// 预览代码对应的输出文件
+    /// return
+    ///     (文件路径, 代码, 所属表名)，公共文件（mod.rs、error.rs 等）不属于某一个表
+    fn files(
+        config: &GeneratorConfig,
+        data: HashMap<String, HashMap<String, String>>,
+    ) -> Vec<(PathBuf, String, Option<String>)> {
+        let mut files = vec![];
         match config.language {
-            Language::Rust => {
+            // sea-orm 实体与 Rust 模型的文件布局相同
+            Language::Rust | Language::SeaOrm => {
+                let error_file = config.language.file_name("error");
+                let mod_file = config.language.file_name("mod");
+                let types_file = config.language.file_name("types");
+                let dir = config.out_dir(config.out_dirs.models.as_deref());
                 // 创建 error.rs 文件
                 if config.gen_error
-                    && let Some(code) = data.get("error.rs")
-                    && let Some(code) = code.get("error.rs")
+                    && let Some(code) = data.get(&error_file)
+                    && let Some(code) = code.get(&error_file)
                 {
-                    Self::write_file(
-                        &format!("{}/error.rs", config.path.display()),
-                        code,
-                        config.r#override,
-                    )
-                    .await?;
+                    let path = dir.join(&error_file);
+                    files.push((path, code.clone(), None));
                 }
                 // 创建 mod.rs 文件
                 if config.gen_mod
-                    && let Some(code) = data.get("mod.rs")
-                    && let Some(code) = code.get("mod.rs")
+                    && let Some(code) = data.get(&mod_file)
+                    && let Some(code) = code.get(&mod_file)
                 {
-                    Self::write_file(
-                        &format!("{}/mod.rs", config.path.display()),
-                        code,
-                        config.r#override,
-                    )
-                    .await?;
+                    let path = dir.join(&mod_file);
+                    files.push((path, code.clone(), None));
                 }
-                // 创建 model 文件
-                for (key, value) in data
-                    .into_iter()
-                    .filter(|(k, _)| !["error.rs", "mod.rs"].contains(&k.as_str()))
+                // 创建 types.rs 文件
+                if let Some(code) = data.get(&types_file)
+                    && let Some(code) = code.get(&types_file)
                 {
+                    let path = dir.join(&types_file);
+                    files.push((path, code.clone(), None));
+                }
+                // 创建 deps.toml 文件，与模型模块分开，放在输出目录的根目录
+                if let Some(code) = data.get(DEPS_FILE)
+                    && let Some(code) = code.get(DEPS_FILE)
+                {
+                    let path = config.path.join(DEPS_FILE);
+                    files.push((path, code.clone(), None));
+                }
+                // 创建 model 文件
+                for (key, value) in data.into_iter().filter(|(k, _)| {
+                    ![&error_file, &mod_file, &types_file].contains(&k) && k != DEPS_FILE
+                }) {
                     for (file_name, code) in value {
-                        dbg!(format!("{}{key}/{file_name}", config.path.display()));
-                        Self::write_file(
-                            &format!("{}{key}/{file_name}", config.path.display()),
-                            &code,
-                            config.r#override,
-                        )
-                        .await?;
+                        // mod.rs 中以 `mod 表名;` 声明模型，模型文件与 mod.rs 位于同一目录
+                        let path = dir.join(&file_name);
+                        files.push((path, code, Some(key.clone())));
                     }
                 }
             }
             Language::Java => {
-                todo!()
+                let java_dir = config.out_dir(config.out_dirs.java.as_deref());
+                let xml_dir = config.out_dir(config.out_dirs.mapper_xml.as_deref());
+                for (table, codes) in data {
+                    for (file_path, code) in codes {
+                        let dir = if file_path.ends_with(".xml") {
+                            &xml_dir
+                        } else {
+                            &java_dir
+                        };
+                        files.push((dir.join(file_path), code, Some(table.clone())));
+                    }
+                }
+            }
+            Language::Diesel => {
+                // schema.rs 包含所有表，不属于某一个表
+                if let Some(code) = data.get(diesel::SCHEMA_FILE)
+                    && let Some(code) = code.get(diesel::SCHEMA_FILE)
+                {
+                    let dir = config.out_dir(config.out_dirs.models.as_deref());
+                    let path = dir.join(diesel::SCHEMA_FILE);
+                    files.push((path, code.clone(), None));
+                }
+            }
+            Language::Proto => {
+                let dir = config.out_dir(config.out_dirs.models.as_deref());
+                for (table, codes) in data {
+                    for (file_name, code) in codes {
+                        files.push((dir.join(file_name), code, Some(table.clone())));
+                    }
+                }
             }
         }
+        files
+    }
+
+    /// 写入文件并记录到生成清单，内容未变化的文件不会重复写入
+    async fn write_tracked(
+        manifest: &mut Manifest,
+        report: &mut WriteReport,
+        path: PathBuf,
+        contents: &str,
+        table: Option<&str>,
+        r#override: bool,
+    ) -> anyhow::Result<()> {
+        let hash = manifest::content_hash(contents);
+        if manifest.is_unchanged(&path, &hash) {
+            report.unchanged.push(path);
+            return Ok(());
+        }
+        if Self::write_file(report, &path, contents, r#override).await? {
+            manifest.record(path, table, hash);
+        }
         Ok(())
     }
 
-    /// 写入文件
-    async fn write_file<P>(path: P, contents: &str, r#override: bool) -> anyhow::Result<()>
+    /// 写入文件，返回是否实际写入，写入、跳过的文件及新建的目录记录到 report
+    async fn write_file<P>(
+        report: &mut WriteReport,
+        path: P,
+        contents: &str,
+        r#override: bool,
+    ) -> anyhow::Result<bool>
     where
         P: AsRef<Path>,
     {
-        if let Some(path) = path.as_ref().parent() {
-            fs::create_dir_all(path)?;
+        let path = path.as_ref();
+        if let Some(dir) = path.parent()
+            && !dir.as_os_str().is_empty()
+            && !dir.exists()
+        {
+            fs::create_dir_all(dir)?;
+            report.created_dirs.push(dir.to_path_buf());
         }
-        if !path.as_ref().exists() || (path.as_ref().exists() && r#override) {
+        if !path.exists() || r#override {
             let mut tf = fs::File::create(path)?;
             tf.write_all(contents.as_bytes())?;
+            report.written.push(path.to_path_buf());
+            return Ok(true);
+        }
+        report.skipped.push(path.to_path_buf());
+        Ok(false)
+    }
+
+    /// 清理已删除表对应的生成文件
+    ///
+    /// 只处理生成清单中记录过的文件，公共文件（mod.rs、error.rs）不会被清理
+    async fn clean(&self, config: &mut GeneratorConfig, dry_run: bool) -> anyhow::Result<()> {
+        let meta = config.metadata().await?;
+        config.resolve_schemas(meta.as_ref()).await?;
+        let path = config.path.clone();
+        for schema in config.schemas.clone() {
+            config.use_schema(&schema, &path);
+            let mut manifest = Manifest::load(&config.path)?;
+            let tables = meta
+                .tables("", &config.schema)
+                .await?
+                .into_iter()
+                .map(|t| t.name)
+                .collect::<Vec<_>>();
+
+            for orphan in manifest.orphans(&tables) {
+                if dry_run {
+                    println!("将删除 {}", orphan.path.display());
+                    continue;
+                }
+                if orphan.path.exists() {
+                    fs::remove_file(&orphan.path)?;
+                }
+                println!("已删除 {}", orphan.path.display());
+                manifest.remove(&orphan.path);
+                if let Some(table) = &orphan.table {
+                    manifest.tables.remove(table);
+                }
+            }
+            if !dry_run {
+                manifest.save(&config.path)?;
+            }
         }
         Ok(())
     }
+
+    /// 检测上次生成后发生变化的表
+    async fn drift(&self, config: &mut GeneratorConfig) -> anyhow::Result<Vec<Drift>> {
+        let meta = config.metadata().await?;
+        config.resolve_schemas(meta.as_ref()).await?;
+        let path = config.path.clone();
+        let mut drifts = vec![];
+        for schema in config.schemas.clone() {
+            config.use_schema(&schema, &path);
+            let manifest = Manifest::load(&config.path)?;
+            let table_names = manifest
+                .tables
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            let columns = meta.all_columns("", &config.schema, &table_names).await?;
+            drifts.extend(drift::detect(&manifest, &columns));
+        }
+        Ok(drifts)
+    }
+
+    /// 生成结构变更日志，没有变化时返回 None
+    async fn changelog(&self, config: &mut GeneratorConfig) -> anyhow::Result<Option<String>> {
+        let meta = config.metadata().await?;
+        config.resolve_schemas(meta.as_ref()).await?;
+        let path = config.path.clone();
+        let mut sections = vec![];
+        for schema in config.schemas.clone() {
+            config.use_schema(&schema, &path);
+            let manifest = Manifest::load(&config.path)?;
+            let (tables, columns) = self.prepare(meta.as_ref(), config).await?;
+            // 只有一个模式时不区分模式
+            let title = if config.schemas.len() > 1 {
+                schema
+            } else {
+                String::new()
+            };
+            sections.push((title, changelog::changes(&manifest, &tables, &columns)));
+        }
+        Ok(changelog::markdown(&sections))
+    }
+
+    /// 比较两个数据库的结构
+    async fn diff(
+        &self,
+        old: &str,
+        new: &str,
+        schema: Option<&str>,
+    ) -> anyhow::Result<database::SchemaDiff> {
+        let mut snapshots = vec![];
+        for url in [old, new] {
+            let meta = database::database_metadata(url)
+                .await
+                .map_err(|e| anyhow!("数据库 {url} 连接失败，{e}"))?;
+            let schema = match schema {
+                Some(schema) => schema.to_string(),
+                None => meta.current_schema().await?,
+            };
+            snapshots.push(database::schema_snapshot(meta.as_ref(), &schema).await?);
+        }
+        Ok(database::diff_schemas(&snapshots[0], &snapshots[1]))
+    }
+
+    /// 导出结构快照
+    ///
+    /// 配置了多个模式时合并各模式的表，表的 schema 字段区分所属模式
+    async fn export(
+        &self,
+        config: &mut GeneratorConfig,
+        format: ExportFormat,
+    ) -> anyhow::Result<String> {
+        let meta = config.metadata().await?;
+        config.resolve_schemas(meta.as_ref()).await?;
+        let mut snapshot = database::SchemaSnapshot { tables: vec![] };
+        for schema in config.schemas.iter() {
+            let schema_snapshot = database::schema_snapshot(meta.as_ref(), schema).await?;
+            snapshot.tables.extend(schema_snapshot.tables);
+        }
+        Ok(match format {
+            ExportFormat::Json => serde_json::to_string_pretty(&snapshot)?,
+            ExportFormat::Dbml => database::to_dbml(&snapshot),
+            ExportFormat::Mermaid => database::to_mermaid_er(&snapshot),
+        })
+    }
+}
+
+/// 模板过滤器，列名转换为 Rust 字段名，如 `type` -> `r#type`、`order-id` -> `order_id`
+fn ident(value: &tera::Value, _: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    let name = tera::from_value::<String>(value.clone())?;
+    Ok(tera::to_value(database::field_name(&name))?)
 }
 
 #[tokio::main]
 async fn main() {
     let mut generator = Generator::parse();
     match generator.command {
-        Commands::Create => {
+        Commands::Create {
+            ref tables,
+            ref schema,
+        } => {
             let config = GeneratorConfig::new(&generator.config_path);
             match config {
-                Ok(mut config) => {
-                    if let Err(err) = generator.run(&mut config).await {
-                        eprintln!("生成代码错误，{err}");
+                Ok(mut config) => match generator
+                    .run(config.with_overrides(tables, schema.as_deref()))
+                    .await
+                {
+                    Ok(report) => {
+                        for path in report.skipped.iter() {
+                            eprintln!("文件已存在，未覆盖 {}", path.display());
+                        }
+                        eprintln!(
+                            "已写入 {} 个文件，跳过 {} 个已存在的文件，{} 个文件未变化",
+                            report.written.len(),
+                            report.skipped.len(),
+                            report.unchanged.len()
+                        );
                     }
+                    Err(err) => eprintln!("生成代码错误，{err}"),
+                },
+                Err(err) => {
+                    eprintln!("读取配置文件错误，{err}");
+                }
+            }
+        }
+        Commands::Preview { ref output } => match GeneratorConfig::new(&generator.config_path) {
+            Ok(mut config) => match generator.preview_files(&mut config).await {
+                Ok(files) if files.is_empty() => eprintln!("没有需要生成代码的表"),
+                Ok(files) => {
+                    let preview = files
+                        .iter()
+                        .map(|(path, code)| format!("==> {} <==\n{code}\n", path.display()))
+                        .collect::<String>();
+                    match output {
+                        Some(output) => match fs::write(output, preview) {
+                            Ok(()) => println!("预览已写入 {}", output.display()),
+                            Err(err) => eprintln!("写入预览错误，{err}"),
+                        },
+                        None => print!("{preview}"),
+                    }
+                }
+                Err(err) => eprintln!("生成代码错误，{err}"),
+            },
+            Err(err) => {
+                eprintln!("读取配置文件错误，{err}");
+            }
+        },
+        Commands::Clean { dry_run } => match GeneratorConfig::new(&generator.config_path) {
+            Ok(mut config) => {
+                if let Err(err) = generator.clean(&mut config, dry_run).await {
+                    eprintln!("清理生成文件错误，{err}");
+                }
+            }
+            Err(err) => {
+                eprintln!("读取配置文件错误，{err}");
+            }
+        },
+        Commands::Drift => match GeneratorConfig::new(&generator.config_path) {
+            Ok(mut config) => match generator.drift(&mut config).await {
+                Ok(drifts) if drifts.is_empty() => println!("数据库结构与生成代码一致"),
+                Ok(drifts) => {
+                    for drift in drifts {
+                        println!("{drift}");
+                    }
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("检测结构漂移错误，{err}");
+                    std::process::exit(2);
                 }
+            },
+            Err(err) => {
+                eprintln!("读取配置文件错误，{err}");
+                std::process::exit(2);
+            }
+        },
+        Commands::Changelog { ref output } => match GeneratorConfig::new(&generator.config_path) {
+            Ok(mut config) => match generator.changelog(&mut config).await {
+                Ok(None) => println!("数据库结构与生成代码一致"),
+                Ok(Some(markdown)) => match fs::write(output, markdown) {
+                    Ok(()) => println!("变更日志已写入 {}", output.display()),
+                    Err(err) => eprintln!("写入变更日志错误，{err}"),
+                },
+                Err(err) => eprintln!("生成变更日志错误，{err}"),
+            },
+            Err(err) => {
+                eprintln!("读取配置文件错误，{err}");
+            }
+        },
+        Commands::Export { format, ref output } => {
+            match GeneratorConfig::new(&generator.config_path) {
+                Ok(mut config) => match generator.export(&mut config, format).await {
+                    Ok(snapshot) => match output {
+                        Some(output) => match fs::write(output, snapshot) {
+                            Ok(()) => println!("结构快照已写入 {}", output.display()),
+                            Err(err) => eprintln!("写入结构快照错误，{err}"),
+                        },
+                        None => println!("{snapshot}"),
+                    },
+                    Err(err) => eprintln!("导出结构快照错误，{err}"),
+                },
                 Err(err) => {
                     eprintln!("读取配置文件错误，{err}");
                 }
             }
         }
+        Commands::Diff {
+            ref old,
+            ref new,
+            ref schema,
+        } => match generator.diff(old, new, schema.as_deref()).await {
+            Ok(diff) if diff.is_empty() => println!("两个数据库的结构一致"),
+            Ok(diff) => {
+                print!("{diff}");
+                std::process::exit(1);
+            }
+            Err(err) => {
+                eprintln!("比较数据库结构错误，{err}");
+                std::process::exit(2);
+            }
+        },
         Commands::Template => {
             if generator.config_path.exists() {
                 println!("配置文件 {} 已存在", generator.config_path.display());
@@ -379,3 +1304,1414 @@ async fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use database::{DecimalBackend, Driver, DumpMetadata};
+
+    use super::*;
+
+    /// 测试使用的配置，只生成模型文件，其他选项在测试中按需修改
+    fn config(database_url: &str) -> GeneratorConfig {
+        let mut config = GeneratorConfig::try_from(
+            format!(
+                r#"
+                database_url = "{database_url}"
+                language = "rust"
+                table_names = []
+                ignore_tables = []
+                path = "./generated/"
+                override = false
+                gen_mod = false
+                gen_error = false
+                gen_entity = true
+                gen_mapper = false
+                gen_mapper_xml = false
+                gen_service = false
+                gen_controller = false
+                "#
+            )
+            .as_str(),
+        )
+        .unwrap();
+        config.schemas = vec![String::new()];
+        config
+    }
+
+    fn generator() -> Generator {
+        Generator {
+            command: Commands::Template,
+            config_path: PathBuf::new(),
+            verbose: false,
+        }
+    }
+
+    /// 从转储文件生成代码，返回 K：文件名，V：代码
+    async fn generate(config: &GeneratorConfig, sql: &str) -> HashMap<String, String> {
+        let meta = DumpMetadata::new(sql, config.driver().unwrap()).unwrap();
+        let generator = generator();
+        let (tables, columns) = generator.prepare(&meta, config).await.unwrap();
+        generator
+            .preview(&meta, config, tables, columns)
+            .await
+            .unwrap()
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn upsert_behind_flag() {
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY, name VARCHAR(50));";
+        let mut config = config("mysql://localhost/test");
+        let files = generate(&config, sql).await;
+        assert!(!files["users.rs"].contains("pub async fn upsert"));
+
+        config.gen_upsert = true;
+        let files = generate(&config, sql).await;
+        assert!(files["users.rs"].contains("pub async fn upsert"));
+        assert!(files["users.rs"].contains("ON DUPLICATE KEY UPDATE"));
+    }
+
+    #[tokio::test]
+    async fn validator_length_and_range() {
+        let mut config = config("mysql://localhost/test");
+        config.gen_validator = true;
+        let files = generate(
+            &config,
+            "CREATE TABLE users (
+                id INT PRIMARY KEY,
+                name VARCHAR(50) NOT NULL,
+                age INT CHECK (age BETWEEN 0 AND 150),
+                score DOUBLE,
+                CONSTRAINT score_ck CHECK (score > 0 AND score <= 100)
+            );",
+        )
+        .await;
+        let code = &files["users.rs"];
+        assert!(code.contains("#[validate(length(max = 50))]\n    pub name: String,"));
+        assert!(code.contains("#[validate(range(min = 0, max = 150))]\n    pub age: Option<i32>,"));
+        assert!(code.contains(
+            "#[validate(range(max = 100.0, exclusive_min = 0.0))]\n    pub score: Option<f64>,"
+        ));
+        assert!(code.contains("Validate"));
+
+        config.gen_validator = false;
+        let files = generate(&config, "CREATE TABLE users (name VARCHAR(50));").await;
+        assert!(!files["users.rs"].contains("#[validate"));
+    }
+
+    #[tokio::test]
+    async fn comment_type_hint() {
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY, tag VARCHAR(20) NOT NULL COMMENT '标签 @rust:MyType');";
+        let mut config = config("mysql://localhost/test");
+        config.honor_comment_hints = true;
+        let files = generate(&config, sql).await;
+        assert!(files["users.rs"].contains("pub tag: MyType,"));
+
+        config.honor_comment_hints = false;
+        let files = generate(&config, sql).await;
+        assert!(files["users.rs"].contains("pub tag: String,"));
+    }
+
+    /// 生成文件的路径，按路径排序
+    async fn file_paths(config: &GeneratorConfig, sql: &str) -> Vec<PathBuf> {
+        let meta = DumpMetadata::new(sql, config.driver().unwrap()).unwrap();
+        let generator = generator();
+        let (tables, columns) = generator.prepare(&meta, config).await.unwrap();
+        let data = generator
+            .preview(&meta, config, tables, columns)
+            .await
+            .unwrap();
+        let mut paths = Generator::files(config, data)
+            .into_iter()
+            .map(|(path, _, _)| path)
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    #[tokio::test]
+    async fn file_extension_follows_language() {
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY);";
+        let mut config = config("mysql://localhost/test");
+        config.gen_mod = true;
+        config.gen_error = true;
+        let paths = file_paths(&config, sql).await;
+        assert_eq!(
+            paths,
+            ["error.rs", "mod.rs", "users.rs"].map(|f| PathBuf::from("./generated/").join(f))
+        );
+
+        config.language = Language::Java;
+        config.gen_mapper = true;
+        config.gen_mapper_xml = true;
+        let paths = file_paths(&config, sql).await;
+        assert_eq!(
+            paths,
+            ["Users.java", "UsersMapper.java", "UsersMapper.xml"]
+                .map(|f| PathBuf::from("./generated/").join(f))
+        );
+
+        config.language = Language::Proto;
+        let paths = file_paths(&config, sql).await;
+        assert_eq!(paths, [PathBuf::from("./generated/users.proto")]);
+    }
+
+    #[tokio::test]
+    async fn proto_message() {
+        let mut config = config("mysql://localhost/test");
+        config.language = Language::Proto;
+        let files = generate(
+            &config,
+            "CREATE TABLE users (
+                id BIGINT PRIMARY KEY,
+                name VARCHAR(50) NOT NULL COMMENT '名称',
+                age TINYINT,
+                avatar BLOB
+            ) COMMENT '用户';",
+        )
+        .await;
+        assert_eq!(
+            files["users.proto"],
+            "syntax = \"proto3\";
+
+// 用户
+message Users {
+  int64 id = 1;
+  // 名称
+  string name = 2;
+  optional int32 age = 3;
+  optional bytes avatar = 4;
+}
+"
+        );
+    }
+
+    /// 测试使用的空临时目录
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("reverse_cli_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 以转储文件为数据源，输出到临时目录的配置
+    fn dump_config(dir: &Path, sql: &str) -> GeneratorConfig {
+        let dump_file = dir.join("schema.sql");
+        fs::write(&dump_file, sql).unwrap();
+        let mut config = config("mysql://localhost/test");
+        config.dump_file = Some(dump_file);
+        config.path = dir.join("out");
+        config
+    }
+
+    #[tokio::test]
+    async fn clean_removes_orphans_only() {
+        let dir = temp_dir("clean");
+        let mut config = dump_config(
+            &dir,
+            "CREATE TABLE users (id INT PRIMARY KEY); CREATE TABLE orders (id INT PRIMARY KEY);",
+        );
+        let mut generator = generator();
+        generator.run(&mut config).await.unwrap();
+        let out = config.path.clone();
+        fs::write(out.join("custom.rs"), "// 手写的文件").unwrap();
+        assert!(out.join("orders.rs").exists());
+
+        fs::write(
+            config.dump_file.as_ref().unwrap(),
+            "CREATE TABLE users (id INT PRIMARY KEY);",
+        )
+        .unwrap();
+        // 只打印不删除
+        generator.clean(&mut config, true).await.unwrap();
+        assert!(out.join("orders.rs").exists());
+
+        generator.clean(&mut config, false).await.unwrap();
+        assert!(!out.join("orders.rs").exists());
+        assert!(out.join("users.rs").exists());
+        assert!(out.join("custom.rs").exists());
+        let manifest = Manifest::load(&out).unwrap();
+        assert!(
+            manifest
+                .files
+                .iter()
+                .all(|f| !f.path.ends_with("orders.rs"))
+        );
+        assert!(!manifest.tables.contains_key("orders"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn manifest_records_files_and_skips_unchanged() {
+        let dir = temp_dir("manifest");
+        let mut config = dump_config(
+            &dir,
+            "CREATE TABLE users (id INT PRIMARY KEY); CREATE TABLE orders (id INT PRIMARY KEY);",
+        );
+        config.gen_mod = true;
+        let mut generator = generator();
+        let report = generator.run(&mut config).await.unwrap();
+        let mut written = report.written.clone();
+        written.sort();
+        assert_eq!(
+            written,
+            ["mod.rs", "orders.rs", "users.rs"].map(|f| config.path.join(f))
+        );
+
+        let manifest = Manifest::load(&config.path).unwrap();
+        assert_eq!(manifest.files.len(), 3);
+        for entry in manifest.files.iter() {
+            let contents = fs::read(&entry.path).unwrap();
+            assert_eq!(entry.hash, manifest::content_hash(contents));
+        }
+        assert_eq!(
+            manifest.tables.keys().collect::<Vec<_>>(),
+            ["orders", "users"]
+        );
+
+        // 再次生成时内容未变化的文件不会重复写入
+        let report = generator.run(&mut config).await.unwrap();
+        assert!(report.written.is_empty());
+        assert_eq!(report.unchanged.len(), 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn java_keyword_column() {
+        let mut config = config("mysql://localhost/test");
+        config.language = Language::Java;
+        let files = generate(
+            &config,
+            "CREATE TABLE course (id INT PRIMARY KEY, class VARCHAR(20));",
+        )
+        .await;
+        let entity = &files["Course.java"];
+        assert!(entity.contains("@TableField(\"class\")"));
+        assert!(entity.contains("private String class_;"));
+    }
+
+    #[tokio::test]
+    async fn nullable_wrapper() {
+        let sql = "CREATE TABLE users (code VARCHAR(20) NOT NULL, name VARCHAR(20));";
+        for url in [
+            "mysql://localhost/test",
+            "postgres://localhost/test",
+            "sqlite://test.db",
+        ] {
+            let mut config = config(url);
+            let files = generate(&config, sql).await;
+            let code = &files["users.rs"];
+            assert!(code.contains("pub code: String,"), "{url}");
+            assert!(code.contains("pub name: Option<String>,"), "{url}");
+
+            config.nullable_wrapper = Some("MaybeNull".to_string());
+            let files = generate(&config, sql).await;
+            let code = &files["users.rs"];
+            assert!(code.contains("pub code: String,"), "{url}");
+            assert!(code.contains("pub name: MaybeNull<String>,"), "{url}");
+        }
+    }
+
+    #[tokio::test]
+    async fn models_from_mysql_dump() {
+        let dir = temp_dir("mysql_dump");
+        let mut config = dump_config(
+            &dir,
+            "/*!40101 SET NAMES utf8mb4 */;
+            CREATE TABLE `users` (
+              `id` bigint NOT NULL AUTO_INCREMENT,
+              `name` varchar(50) NOT NULL,
+              `email` varchar(100) DEFAULT NULL,
+              PRIMARY KEY (`id`)
+            ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='用户';
+            INSERT INTO `users` VALUES (1,'a',NULL);",
+        );
+        generator().run(&mut config).await.unwrap();
+        let code = fs::read_to_string(config.path.join("users.rs")).unwrap();
+        assert!(code.contains("pub struct Users {"));
+        assert!(code.contains("pub id: i64,"));
+        assert!(code.contains("pub name: String,"));
+        assert!(code.contains("pub email: Option<String>,"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn drift_reports_added_column() {
+        let dir = temp_dir("drift");
+        let mut config = dump_config(
+            &dir,
+            "CREATE TABLE users (id INT PRIMARY KEY, name VARCHAR(20));",
+        );
+        let mut generator = generator();
+        generator.run(&mut config).await.unwrap();
+        assert!(generator.drift(&mut config).await.unwrap().is_empty());
+
+        fs::write(
+            config.dump_file.as_ref().unwrap(),
+            "CREATE TABLE users (id INT PRIMARY KEY, name VARCHAR(20), email VARCHAR(100));",
+        )
+        .unwrap();
+        let drifts = generator.drift(&mut config).await.unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].table, "users");
+        assert_eq!(drifts[0].added, ["email"]);
+        assert!(drifts[0].removed.is_empty() && drifts[0].changed.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn type_alias_for_repeated_id() {
+        let sql = "CREATE TABLE users (id BIGINT PRIMARY KEY, name VARCHAR(20) NOT NULL);
+            CREATE TABLE orders (id BIGINT PRIMARY KEY, name VARCHAR(20) NOT NULL, total INT);";
+        let mut config = config("mysql://localhost/test");
+        config.gen_type_aliases = true;
+        let files = generate(&config, sql).await;
+        let types = &files["types.rs"];
+        assert!(types.contains("pub type Id = i64;"));
+        // String 和只出现一次的列不生成别名
+        assert!(!types.contains("pub type Name"));
+        assert!(!types.contains("pub type Total"));
+        for model in ["users.rs", "orders.rs"] {
+            assert!(files[model].contains("pub id: Id,"), "{model}");
+            assert!(files[model].contains("pub name: String,"), "{model}");
+        }
+
+        config.gen_type_aliases = false;
+        let files = generate(&config, sql).await;
+        assert!(!files.contains_key("types.rs"));
+        assert!(files["users.rs"].contains("pub id: i64,"));
+    }
+
+    #[tokio::test]
+    async fn bit_as_bool() {
+        let sql = "CREATE TABLE flags (a BIT(1) NOT NULL, b BIT(8) NOT NULL);";
+        let mut config = config("mysql://localhost/test");
+        let files = generate(&config, sql).await;
+        assert!(files["flags.rs"].contains("pub a: bool,"));
+        assert!(files["flags.rs"].contains("pub b: u8,"));
+
+        config.bit_as_bool = false;
+        let files = generate(&config, sql).await;
+        assert!(files["flags.rs"].contains("pub a: u8,"));
+        assert!(files["flags.rs"].contains("pub b: u8,"));
+    }
+
+    #[tokio::test]
+    async fn tinyint1_as_bool() {
+        let sql = "CREATE TABLE flags (a TINYINT(1) NOT NULL, b TINYINT(3) NOT NULL);";
+        let mut config = config("mysql://localhost/test");
+        let files = generate(&config, sql).await;
+        assert!(files["flags.rs"].contains("pub a: bool,"));
+        assert!(files["flags.rs"].contains("pub b: i8,"));
+
+        config.tinyint1_as_bool = false;
+        let files = generate(&config, sql).await;
+        assert!(files["flags.rs"].contains("pub a: i8,"));
+        assert!(files["flags.rs"].contains("pub b: i8,"));
+    }
+
+    #[tokio::test]
+    async fn ignore_tables_and_prefix() {
+        let meta = DumpMetadata::new(
+            "CREATE TABLE users (id INT PRIMARY KEY);
+            CREATE TABLE orders (id INT PRIMARY KEY);
+            CREATE TABLE tmp_users (id INT PRIMARY KEY);
+            CREATE TABLE tmp_orders (id INT PRIMARY KEY);",
+            Driver::Mysql,
+        )
+        .unwrap();
+        let mut config = config("mysql://localhost/test");
+        config.ignore_tables = vec!["orders".to_string()];
+        config.ignore_table_prefix = Some("tmp_".to_string());
+        let table_names = async |config: &GeneratorConfig| {
+            let (tables, columns) = generator().prepare(&meta, config).await.unwrap();
+            let names = tables.into_iter().map(|t| t.name).collect::<Vec<_>>();
+            assert!(columns.iter().all(|c| names.contains(&c.table_name)));
+            names
+        };
+        assert_eq!(table_names(&config).await, ["users"]);
+
+        // 显式指定的表被忽略时跳过
+        config.table_names = vec!["users".to_string(), "tmp_users".to_string()];
+        assert_eq!(table_names(&config).await, ["users"]);
+        config.table_names = vec!["users".to_string(), "orders".to_string()];
+        assert_eq!(table_names(&config).await, ["users"]);
+
+        // 空前缀不忽略任何表
+        config.table_names = vec![];
+        config.ignore_tables = vec![];
+        config.ignore_table_prefix = Some(String::new());
+        assert_eq!(table_names(&config).await.len(), 4);
+    }
+
+    #[test]
+    fn struct_name_strips_prefix() {
+        let mut config = config("mysql://localhost/test");
+        assert_eq!(config.struct_name("t_user"), "TUser");
+
+        config.strip_table_prefix = Some("t_".to_string());
+        for (table, name) in [
+            ("t_user", "User"),
+            ("t_order_item", "OrderItem"),
+            // 表名就是前缀或不以前缀开头时不去掉
+            ("t_", "T"),
+            ("user", "User"),
+            ("tt_user", "TtUser"),
+        ] {
+            assert_eq!(config.struct_name(table), name, "{table}");
+        }
+    }
+
+    #[tokio::test]
+    async fn output_path_with_or_without_separator() {
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY);";
+        for path in ["./out", "./out/"] {
+            let mut config = config("mysql://localhost/test");
+            config.gen_mod = true;
+            config.path = PathBuf::from(path);
+            config.deal_path();
+            assert!(
+                config
+                    .path
+                    .to_string_lossy()
+                    .ends_with(std::path::is_separator),
+                "{path}"
+            );
+            let paths = file_paths(&config, sql).await;
+            assert_eq!(
+                paths,
+                [
+                    PathBuf::from("./out/mod.rs"),
+                    PathBuf::from("./out/users.rs")
+                ],
+                "{path}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn not_null_default_in_new_model() {
+        let mut config = config("mysql://localhost/test");
+        config.gen_new_model = true;
+        let files = generate(
+            &config,
+            "CREATE TABLE users (
+                id INT PRIMARY KEY AUTO_INCREMENT,
+                status VARCHAR(20) NOT NULL DEFAULT 'active',
+                name VARCHAR(20) NOT NULL
+            );",
+        )
+        .await;
+        let code = &files["users.rs"];
+        let (model, new_model) = code.split_once("pub struct NewUsers {").unwrap();
+        assert!(model.contains("pub status: String,"));
+        assert!(new_model.contains(
+            "#[serde(skip_serializing_if = \"Option::is_none\")]\n    pub status: Option<String>,"
+        ));
+        assert!(new_model.contains("pub name: String,"));
+        // 自增列由数据库生成，不出现在新增模型中
+        assert!(!new_model[..new_model.find('}').unwrap()].contains("pub id:"));
+    }
+
+    /// 元数据查询的返回值
+    type BoxFuture<'a, T> =
+        std::pin::Pin<Box<dyn Future<Output = database::error::Result<T>> + Send + 'a>>;
+
+    /// 统计 tables 查询次数的元数据来源，其他查询转发给内部的数据源
+    struct CountingMetadata {
+        inner: DumpMetadata,
+        tables: std::sync::atomic::AtomicUsize,
+        /// 检查约束查询返回的错误
+        check_error: Option<&'static str>,
+    }
+
+    impl DatabaseMetadata for CountingMetadata {
+        fn ping(&self) -> BoxFuture<'_, ()> {
+            self.inner.ping()
+        }
+
+        fn databases(&self) -> BoxFuture<'_, Vec<database::Database>> {
+            self.inner.databases()
+        }
+
+        fn schemas(&self) -> BoxFuture<'_, Vec<database::Schema>> {
+            self.inner.schemas()
+        }
+
+        fn current_schema(&self) -> BoxFuture<'_, String> {
+            self.inner.current_schema()
+        }
+
+        fn tables<'a>(&'a self, database: &'a str, schema: &'a str) -> BoxFuture<'a, Vec<Table>> {
+            self.tables
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.tables(database, schema)
+        }
+
+        fn columns<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<Column>> {
+            self.inner.columns(database, schema, table_name)
+        }
+
+        fn indexs<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<database::Index>> {
+            self.inner.indexs(database, schema, table_name)
+        }
+
+        fn foreign_keys<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<database::ForeignKey>> {
+            self.inner.foreign_keys(database, schema, table_name)
+        }
+
+        fn create_table_sql<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, String> {
+            self.inner.create_table_sql(database, schema, table_name)
+        }
+
+        fn check_constraints<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<database::CheckConstraint>> {
+            match self.check_error {
+                Some(e) => Box::pin(async move { Err(database::error::Error::E(e)) }),
+                None => self.inner.check_constraints(database, schema, table_name),
+            }
+        }
+
+        fn routines<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+        ) -> BoxFuture<'a, Vec<database::Routine>> {
+            self.inner.routines(database, schema)
+        }
+    }
+
+    #[tokio::test]
+    async fn run_queries_tables_once() {
+        let dir = temp_dir("query_once");
+        let sql =
+            "CREATE TABLE users (id INT PRIMARY KEY); CREATE TABLE orders (id INT PRIMARY KEY);";
+        let mut config = dump_config(&dir, sql);
+        let meta = CountingMetadata {
+            inner: DumpMetadata::new(sql, Driver::Mysql).unwrap(),
+            tables: Default::default(),
+            check_error: None,
+        };
+        let report = generator().run_with(&meta, &mut config).await.unwrap();
+        assert_eq!(report.written.len(), 2);
+        assert_eq!(meta.tables.load(std::sync::atomic::Ordering::SeqCst), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn check_constraints_errors() {
+        let dir = temp_dir("check_constraints_errors");
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY, age INT CHECK (age >= 0));";
+        let mut config = dump_config(&dir, sql);
+        config.gen_validator = true;
+        // 不支持检查约束时不生成范围校验
+        let meta = CountingMetadata {
+            inner: DumpMetadata::new(sql, Driver::Mysql).unwrap(),
+            tables: Default::default(),
+            check_error: Some("not supported"),
+        };
+        let report = generator().run_with(&meta, &mut config).await.unwrap();
+        let users = report
+            .written
+            .iter()
+            .find(|p| p.ends_with("users.rs"))
+            .unwrap();
+        assert!(!fs::read_to_string(users).unwrap().contains("range("));
+        // 其他错误中止生成
+        let meta = CountingMetadata {
+            check_error: Some("connection lost"),
+            ..meta
+        };
+        let err = generator().run_with(&meta, &mut config).await.unwrap_err();
+        assert!(err.to_string().contains("connection lost"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn table_names_from_file() {
+        let dir = temp_dir("table_names_file");
+        fs::write(
+            dir.join("schema.sql"),
+            "CREATE TABLE users (id INT PRIMARY KEY);
+            CREATE TABLE orders (id INT PRIMARY KEY);
+            CREATE TABLE items (id INT PRIMARY KEY);
+            CREATE TABLE logs (id INT PRIMARY KEY);",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("tables.txt"),
+            "# 需要生成的表\nusers\n\n  orders  \nitems\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("generator.toml"),
+            format!(
+                r#"
+                database_url = "mysql://localhost/test"
+                dump_file = "{dir}/schema.sql"
+                language = "rust"
+                table_names = ["users"]
+                table_names_file = "{dir}/tables.txt"
+                ignore_tables = []
+                path = "{dir}/out"
+                override = false
+                gen_mod = false
+                gen_error = false
+                gen_entity = true
+                gen_mapper = false
+                gen_mapper_xml = false
+                gen_service = false
+                gen_controller = false
+                "#,
+                dir = dir.display()
+            ),
+        )
+        .unwrap();
+        let mut config = GeneratorConfig::new(dir.join("generator.toml")).unwrap();
+        assert_eq!(config.table_names, ["users", "orders", "items"]);
+
+        generator().run(&mut config).await.unwrap();
+        let mut files = fs::read_dir(dir.join("out"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|f| f.ends_with(".rs"))
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["items.rs", "orders.rs", "users.rs"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn java_files_in_package_dirs() {
+        let dir = temp_dir("java_packages");
+        let mut config = dump_config(&dir, "CREATE TABLE users (id INT PRIMARY KEY);");
+        config.language = Language::Java;
+        config.gen_mapper = true;
+        config.gen_mapper_xml = true;
+        config.gen_service = true;
+        config.gen_controller = true;
+        config.packages = config::Packages {
+            entity: Some("com.example.entity".to_string()),
+            mapper: Some("com.example.mapper".to_string()),
+            mapper_xml: Some("mapper".to_string()),
+            service: Some("com.example.service".to_string()),
+            service_impl: Some("com.example.service.impl".to_string()),
+            controller: Some("com.example.controller".to_string()),
+        };
+        let out = config.path.clone();
+        let entity = out.join("com/example/entity/Users.java");
+        fs::create_dir_all(entity.parent().unwrap()).unwrap();
+        fs::write(&entity, "// 手写的文件").unwrap();
+
+        let report = generator().run(&mut config).await.unwrap();
+        for file in [
+            "com/example/mapper/UsersMapper.java",
+            "mapper/UsersMapper.xml",
+            "com/example/service/IUsersService.java",
+            "com/example/service/impl/UsersServiceImpl.java",
+            "com/example/controller/UsersController.java",
+        ] {
+            assert!(out.join(file).exists(), "{file}");
+        }
+        // 未开启 override 时不覆盖已有的文件
+        assert_eq!(report.skipped, std::slice::from_ref(&entity));
+        assert_eq!(fs::read_to_string(&entity).unwrap(), "// 手写的文件");
+
+        config.r#override = true;
+        generator().run(&mut config).await.unwrap();
+        let code = fs::read_to_string(&entity).unwrap();
+        assert!(code.contains("package com.example.entity;"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn timestamp_default_expression() {
+        let sql = "CREATE TABLE events (id INT PRIMARY KEY, created_at TIMESTAMP NOT NULL);";
+        let mut config = config("postgres://localhost/test");
+        config.gen_new_model = true;
+        let files = generate(&config, sql).await;
+        let code = &files["events.rs"];
+        assert!(code.contains("impl Default for NewEvents {"));
+        assert!(code.contains("time::OffsetDateTime::now_utc()"));
+        assert!(!code.contains("now_local()"));
+
+        config.timestamp_default = config::UtcOrLocal::Local;
+        let files = generate(&config, sql).await;
+        assert!(files["events.rs"].contains("time::OffsetDateTime::now_local()"));
+    }
+
+    #[tokio::test]
+    async fn out_dirs_per_target() {
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY);";
+        let mut java = config("mysql://localhost/test");
+        java.language = Language::Java;
+        java.gen_mapper = true;
+        java.gen_mapper_xml = true;
+        java.out_dirs.java = Some(PathBuf::from("src/main/java"));
+        java.out_dirs.mapper_xml = Some(PathBuf::from("src/main/resources/mapper"));
+        assert_eq!(
+            file_paths(&java, sql).await,
+            [
+                "src/main/java/Users.java",
+                "src/main/java/UsersMapper.java",
+                "src/main/resources/mapper/UsersMapper.xml",
+            ]
+            .map(|f| PathBuf::from("./generated/").join(f))
+        );
+
+        let mut rust = config("mysql://localhost/test");
+        rust.gen_mod = true;
+        rust.out_dirs.models = Some(PathBuf::from("models"));
+        assert_eq!(
+            file_paths(&rust, sql).await,
+            ["models/mod.rs", "models/users.rs"].map(|f| PathBuf::from("./generated/").join(f))
+        );
+    }
+
+    #[tokio::test]
+    async fn rust_imports_in_models() {
+        let mut config = config("mysql://localhost/test");
+        config.rust_imports = vec![
+            "use my_crate::MyType;".to_string(),
+            "my_crate::MyType".to_string(),
+            " other::Tag ".to_string(),
+            String::new(),
+        ];
+        let files = generate(&config, "CREATE TABLE users (id INT PRIMARY KEY);").await;
+        let code = &files["users.rs"];
+        assert_eq!(code.matches("use my_crate::MyType;").count(), 1);
+        assert!(code.contains("use other::Tag;"));
+        assert!(!code.contains("use ;"));
+    }
+
+    #[tokio::test]
+    async fn type_overrides_precedence() {
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY, profile JSON, settings JSON);";
+        let mut config = config("mysql://localhost/test");
+        config.type_overrides = HashMap::from([
+            ("json".to_string(), "MyJson".to_string()),
+            ("users.settings".to_string(), "Settings".to_string()),
+        ]);
+        let files = generate(&config, sql).await;
+        let code = &files["users.rs"];
+        assert!(code.contains("pub profile: Option<MyJson>,"));
+        assert!(code.contains("pub settings: Option<Settings>,"));
+        assert!(code.contains("pub id: i32,"));
+    }
+
+    #[tokio::test]
+    async fn deps_follow_used_types() {
+        let sql = "CREATE TABLE events (id UUID PRIMARY KEY, created_at TIMESTAMPTZ NOT NULL);";
+        let mut config = config("postgres://localhost/test");
+        config.gen_deps = true;
+        let files = generate(&config, sql).await;
+        let deps = &files[DEPS_FILE];
+        assert!(deps.contains("uuid = { version = \"1\", features = [\"serde\"] }"));
+        assert!(deps.contains("time = { version = \"0.3\", features = [\"serde\"] }"));
+        assert!(deps.contains(
+            "sqlx = { version = \"0.8\", features = [\"macros\", \"postgres\", \"runtime-tokio\", \"time\", \"uuid\"] }"
+        ));
+        assert!(!deps.contains("chrono"));
+
+        config.datetime_backend = database::DateTimeBackend::Chrono;
+        let files = generate(&config, sql).await;
+        let deps = &files[DEPS_FILE];
+        assert!(deps.contains("chrono = { version = \"0.4\", features = [\"serde\"] }"));
+        assert!(!deps.contains("time = "));
+    }
+
+    #[tokio::test]
+    async fn enum_named_like_table() {
+        let files = generate(
+            &config("mysql://localhost/test"),
+            "CREATE TABLE status (id INT PRIMARY KEY, status ENUM('active', 'inactive') NOT NULL);",
+        )
+        .await;
+        let code = &files["status.rs"];
+        assert!(code.contains("pub struct Status {"));
+        assert!(code.contains("pub enum StatusEnum {"));
+        assert!(code.contains("pub status: StatusEnum,"));
+        assert!(!code.contains("pub enum Status {"));
+    }
+
+    #[tokio::test]
+    async fn enum_for_enum_values() {
+        let files = generate(
+            &config("mysql://localhost/test"),
+            "CREATE TABLE tasks (id INT PRIMARY KEY, state ENUM('new', 'in_progress') NOT NULL, note VARCHAR(20));",
+        )
+        .await;
+        let code = &files["tasks.rs"];
+        assert!(code.contains("pub enum State {"));
+        assert!(code.contains(
+            "    #[serde(rename = \"in_progress\")]\n    #[sqlx(rename = \"in_progress\")]\n    InProgress,"
+        ));
+        assert!(code.contains("Self::InProgress => f.write_str(\"in_progress\"),"));
+        assert!(code.contains("pub state: State,"));
+        assert!(code.contains("pub note: Option<String>,"));
+    }
+
+    #[tokio::test]
+    async fn changelog_markdown() {
+        let dir = temp_dir("changelog");
+        let mut config = dump_config(
+            &dir,
+            "CREATE TABLE users (id INT PRIMARY KEY, name VARCHAR(20));",
+        );
+        let mut generator = generator();
+        generator.run(&mut config).await.unwrap();
+        assert_eq!(generator.changelog(&mut config).await.unwrap(), None);
+
+        fs::write(
+            config.dump_file.as_ref().unwrap(),
+            "CREATE TABLE users (id BIGINT PRIMARY KEY, name VARCHAR(20), email VARCHAR(100));
+            CREATE TABLE orders (id INT PRIMARY KEY);",
+        )
+        .unwrap();
+        let markdown = generator.changelog(&mut config).await.unwrap().unwrap();
+        assert_eq!(
+            markdown,
+            "## 数据库结构变更
+
+- 新增表 `orders`
+- 表 `users` 新增列 `email`
+- 列 `users.id` 类型由 `INT` 变更为 `BIGINT`
+"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rename_invalid_column_names() {
+        let files = generate(
+            &config("mysql://localhost/test"),
+            "CREATE TABLE users (id INT PRIMARY KEY, `2fa_enabled` TINYINT(1), `order-id` INT);",
+        )
+        .await;
+        let code = &files["users.rs"];
+        assert!(code.contains(
+            "#[serde(rename = \"2fa_enabled\")]\n    #[sqlx(rename = \"2fa_enabled\")]\n    pub _2fa_enabled: Option<bool>,"
+        ));
+        assert!(code.contains(
+            "#[serde(rename = \"order-id\")]\n    #[sqlx(rename = \"order-id\")]\n    pub order_id: Option<i32>,"
+        ));
+        assert!(!code.contains("#[serde(rename = \"id\")]"));
+    }
+
+    #[tokio::test]
+    async fn foreign_key_hints() {
+        let sql = "CREATE TABLE users (id INT PRIMARY KEY);
+            CREATE TABLE orders (
+                id INT PRIMARY KEY,
+                user_id INT COMMENT '用户',
+                FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+            );";
+        let mut config = config("mysql://localhost/test");
+        config.foreign_key_hints = true;
+        let files = generate(&config, sql).await;
+        assert!(files["orders.rs"].contains("/// 用户\n    ///\n    /// 引用 users.id\n"));
+
+        config.foreign_key_hints = false;
+        let files = generate(&config, sql).await;
+        assert!(!files["orders.rs"].contains("引用"));
+    }
+
+    #[tokio::test]
+    async fn decimal_backends() {
+        let sql = "CREATE TABLE items (id INT PRIMARY KEY, price NUMERIC(12,4) NOT NULL);";
+        for url in ["mysql://localhost/test", "postgres://localhost/test"] {
+            let mut config = config(url);
+            let files = generate(&config, sql).await;
+            assert!(
+                files["items.rs"].contains("pub price: bigdecimal::BigDecimal,"),
+                "{url}"
+            );
+
+            config.decimal_backend = DecimalBackend::RustDecimal;
+            let files = generate(&config, sql).await;
+            assert!(
+                files["items.rs"].contains("pub price: rust_decimal::Decimal,"),
+                "{url}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_derives() {
+        let mut config = config("mysql://localhost/test");
+        config.derives = vec!["Debug".to_string(), "PartialEq".to_string()];
+        let files = generate(&config, "CREATE TABLE users (id INT PRIMARY KEY);").await;
+        assert!(
+            files["users.rs"].contains("#[derive(\n    Debug,\n    PartialEq,\n    FromRow,\n)]")
+        );
+    }
+
+    #[tokio::test]
+    async fn sea_orm_entity() {
+        let mut config = config("mysql://localhost/test");
+        config.language = Language::SeaOrm;
+        let files = generate(
+            &config,
+            "CREATE TABLE users (id BIGINT PRIMARY KEY AUTO_INCREMENT);
+            CREATE TABLE orders (
+                id BIGINT PRIMARY KEY AUTO_INCREMENT,
+                user_id BIGINT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+            );",
+        )
+        .await;
+        let code = &files["orders.rs"];
+        assert!(code.contains("#[sea_orm(primary_key, auto_increment = true)]\n    pub id: i64,"));
+        assert!(code.contains(
+            r#"belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_delete = "Cascade""#
+        ));
+        assert!(code.contains("impl Related<super::users::Entity> for Entity {"));
+        assert!(!files["users.rs"].contains("belongs_to"));
+    }
+
+    #[tokio::test]
+    async fn diesel_schema() {
+        let mut config = config("postgres://localhost/test");
+        config.language = Language::Diesel;
+        let files = generate(
+            &config,
+            "CREATE TABLE users (id SERIAL PRIMARY KEY, name VARCHAR(50) NOT NULL);
+            CREATE TABLE orders (
+                id INT PRIMARY KEY,
+                user_id INT REFERENCES users (id),
+                created_at TIMESTAMP
+            );",
+        )
+        .await;
+        assert_eq!(
+            files["schema.rs"],
+            r#"// @generated automatically by reverse_cli
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    orders (id) {
+        id -> Int4,
+        user_id -> Nullable<Int4>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    users (id) {
+        id -> Int4,
+        name -> Varchar,
+    }
+}
+
+diesel::joinable!(orders -> users (user_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    orders,
+    users,
+);
+"#
+        );
+    }
+
+    #[tokio::test]
+    async fn preview_files_without_writing() {
+        let dir = temp_dir("preview");
+        let mut config = dump_config(
+            &dir,
+            "CREATE TABLE users (id INT PRIMARY KEY);
+            CREATE TABLE orders (id INT PRIMARY KEY);
+            CREATE TABLE tmp_log (id INT PRIMARY KEY);",
+        );
+        config.gen_mod = true;
+        config.ignore_table_prefix = Some("tmp_".to_string());
+        let files = generator().preview_files(&mut config).await.unwrap();
+        let out = config.path.clone();
+        let paths = files.into_iter().map(|(path, _)| path).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                out.join("mod.rs"),
+                out.join("orders.rs"),
+                out.join("users.rs")
+            ]
+        );
+        assert!(!out.exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn verbose_flag() {
+        let generator = Generator::try_parse_from(["reverse_cli", "create"]).unwrap();
+        assert!(!generator.verbose);
+        let generator = Generator::try_parse_from(["reverse_cli", "create", "-v"]).unwrap();
+        assert!(generator.verbose);
+    }
+
+    /// 在子进程中执行 quiet_run_child，检查默认运行时标准错误没有输出
+    #[test]
+    fn quiet_run() {
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["tests::quiet_run_child", "--exact", "--nocapture"])
+            .env("REVERSE_CLI_QUIET_RUN", "1")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    }
+
+    #[tokio::test]
+    async fn quiet_run_child() {
+        if std::env::var_os("REVERSE_CLI_QUIET_RUN").is_none() {
+            return;
+        }
+        let dir = temp_dir("quiet");
+        let mut config = dump_config(&dir, "CREATE TABLE users (id INT PRIMARY KEY);");
+        generator().run(&mut config).await.unwrap();
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn group_many_columns_by_table() {
+        let sql = (0..20)
+            .map(|t| {
+                let columns = (0..15)
+                    .map(|c| format!("t{t}_c{c} INT"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("CREATE TABLE t{t} ({columns});")
+            })
+            .collect::<String>();
+        let config = config("mysql://localhost/test");
+        let meta = DumpMetadata::new(&sql, Driver::Mysql).unwrap();
+        let generator = generator();
+        let (tables, mut columns) = generator.prepare(&meta, &config).await.unwrap();
+        assert_eq!(columns.len(), 300);
+        // 列的顺序被打乱时仍按表分组并按位置排序
+        columns.reverse();
+        let files: HashMap<String, String> = generator
+            .preview(&meta, &config, tables, columns)
+            .await
+            .unwrap()
+            .into_values()
+            .flatten()
+            .collect();
+        for t in 0..20 {
+            let code = &files[&format!("t{t}.rs")];
+            // 模型结构体的字段
+            let (_, model) = code.split_once(&format!("pub struct T{t} {{")).unwrap();
+            let (model, _) = model.split_once("\n}").unwrap();
+            let fields = model
+                .lines()
+                .filter_map(|l| l.trim().strip_prefix("pub "))
+                .filter_map(|l| l.split_once(": Option<i32>,"))
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<_>>();
+            let expected = (0..15).map(|c| format!("t{t}_c{c}")).collect::<Vec<_>>();
+            assert_eq!(fields, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn fields_follow_ordinal_position() {
+        let config = config("mysql://localhost/test");
+        let meta =
+            DumpMetadata::new("CREATE TABLE t (z INT, a INT, m INT);", Driver::Mysql).unwrap();
+        let generator = generator();
+        let (tables, mut columns) = generator.prepare(&meta, &config).await.unwrap();
+        // 列按 m、z、a 的顺序返回，位置仍为 z、a、m
+        columns.rotate_left(2);
+        assert_eq!(
+            columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            ["m", "z", "a"]
+        );
+        let files: HashMap<String, String> = generator
+            .preview(&meta, &config, tables, columns)
+            .await
+            .unwrap()
+            .into_values()
+            .flatten()
+            .collect();
+        assert!(files["t.rs"].contains(
+            "pub z: Option<i32>,\n    /// \n    pub a: Option<i32>,\n    /// \n    pub m: Option<i32>,"
+        ));
+    }
+
+    /// 按模式区分数据源的元数据来源，每个模式的查询转发给该模式的转储
+    struct SchemasMetadata {
+        schemas: Vec<(&'static str, DumpMetadata)>,
+    }
+
+    impl SchemasMetadata {
+        fn schema(&self, schema: &str) -> &DumpMetadata {
+            &self.schemas.iter().find(|(s, _)| *s == schema).unwrap().1
+        }
+    }
+
+    impl DatabaseMetadata for SchemasMetadata {
+        fn ping(&self) -> BoxFuture<'_, ()> {
+            self.schemas[0].1.ping()
+        }
+
+        fn databases(&self) -> BoxFuture<'_, Vec<database::Database>> {
+            self.schemas[0].1.databases()
+        }
+
+        fn schemas(&self) -> BoxFuture<'_, Vec<database::Schema>> {
+            self.schemas[0].1.schemas()
+        }
+
+        fn current_schema(&self) -> BoxFuture<'_, String> {
+            Box::pin(async move { Ok(self.schemas[0].0.to_string()) })
+        }
+
+        fn tables<'a>(&'a self, database: &'a str, schema: &'a str) -> BoxFuture<'a, Vec<Table>> {
+            self.schema(schema).tables(database, schema)
+        }
+
+        fn columns<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<Column>> {
+            self.schema(schema).columns(database, schema, table_name)
+        }
+
+        fn indexs<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<database::Index>> {
+            self.schema(schema).indexs(database, schema, table_name)
+        }
+
+        fn foreign_keys<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<database::ForeignKey>> {
+            self.schema(schema)
+                .foreign_keys(database, schema, table_name)
+        }
+
+        fn create_table_sql<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, String> {
+            self.schema(schema)
+                .create_table_sql(database, schema, table_name)
+        }
+
+        fn check_constraints<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+            table_name: &'a str,
+        ) -> BoxFuture<'a, Vec<database::CheckConstraint>> {
+            self.schema(schema)
+                .check_constraints(database, schema, table_name)
+        }
+
+        fn routines<'a>(
+            &'a self,
+            database: &'a str,
+            schema: &'a str,
+        ) -> BoxFuture<'a, Vec<database::Routine>> {
+            self.schema(schema).routines(database, schema)
+        }
+    }
+
+    #[tokio::test]
+    async fn same_table_in_two_schemas() {
+        let dir = temp_dir("two_schemas");
+        let mut config = dump_config(&dir, "");
+        config.schemas = vec!["sales".to_string(), "hr".to_string()];
+        let meta = SchemasMetadata {
+            schemas: vec![
+                (
+                    "sales",
+                    DumpMetadata::new(
+                        "CREATE TABLE people (id INT, region VARCHAR(20));",
+                        Driver::Mysql,
+                    )
+                    .unwrap(),
+                ),
+                (
+                    "hr",
+                    DumpMetadata::new("CREATE TABLE people (id INT, salary INT);", Driver::Mysql)
+                        .unwrap(),
+                ),
+            ],
+        };
+        generator().run_with(&meta, &mut config).await.unwrap();
+        let out = dir.join("out");
+        let sales = fs::read_to_string(out.join("sales").join("people.rs")).unwrap();
+        let hr = fs::read_to_string(out.join("hr").join("people.rs")).unwrap();
+        assert!(sales.contains("pub region: Option<String>,"));
+        assert!(!sales.contains("salary"));
+        assert!(hr.contains("pub salary: Option<i32>,"));
+        assert!(!hr.contains("region"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn index_docs_in_model() {
+        let mut config = config("mysql://localhost/test");
+        config.index_docs = true;
+        let files = generate(
+            &config,
+            "CREATE TABLE users (
+                id INT PRIMARY KEY,
+                first_name VARCHAR(20),
+                last_name VARCHAR(20),
+                INDEX idx_name (last_name, first_name)
+            ) COMMENT='用户';",
+        )
+        .await;
+        assert!(files["users.rs"].contains(
+            "/// 用户\n///\n/// 索引：\n/// - `PRIMARY`（id）唯一\n/// - `idx_name`（last_name, first_name）\n#[derive("
+        ));
+    }
+
+    #[tokio::test]
+    async fn no_doc_comment_for_empty_table_comment() {
+        let files = generate(
+            &config("postgres://localhost/test"),
+            "CREATE TABLE users (id INT PRIMARY KEY);",
+        )
+        .await;
+        assert!(!files["users.rs"].contains("/// users\n"));
+        assert!(files["users.rs"].contains("\n#[derive("));
+        assert!(!files["users.rs"].contains("///\n#[derive("));
+    }
+
+    #[tokio::test]
+    async fn existing_file_reported_as_skipped() {
+        let dir = temp_dir("skipped");
+        let mut config = dump_config(
+            &dir,
+            "CREATE TABLE users (id INT PRIMARY KEY); CREATE TABLE orders (id INT PRIMARY KEY);",
+        );
+        let out = config.path.clone();
+        fs::create_dir_all(&out).unwrap();
+        fs::write(out.join("users.rs"), "// 手写的文件").unwrap();
+
+        let report = generator().run(&mut config).await.unwrap();
+        assert_eq!(report.skipped, [out.join("users.rs")]);
+        assert_eq!(report.written, [out.join("orders.rs")]);
+        assert!(report.created_dirs.is_empty());
+        assert_eq!(
+            fs::read_to_string(out.join("users.rs")).unwrap(),
+            "// 手写的文件"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn table_flag_overrides_config() {
+        let generator = Generator::try_parse_from([
+            "reverse_cli",
+            "create",
+            "--table",
+            "users",
+            "--table",
+            "logs",
+            "--schema",
+            "app",
+        ])
+        .unwrap();
+        let Commands::Create {
+            ref tables,
+            ref schema,
+        } = generator.command
+        else {
+            panic!("expected create");
+        };
+        let mut config = config("mysql://localhost/test");
+        config.table_names = vec!["orders".to_string()];
+        config.ignore_tables = vec!["logs".to_string()];
+        config.with_overrides(tables, schema.as_deref());
+        assert_eq!(config.schemas, ["app"]);
+        assert_eq!(config.schema, "app");
+
+        let meta = DumpMetadata::new(
+            "CREATE TABLE users (id INT PRIMARY KEY);
+            CREATE TABLE orders (id INT PRIMARY KEY);
+            CREATE TABLE logs (id INT PRIMARY KEY);",
+            Driver::Mysql,
+        )
+        .unwrap();
+        let (tables, _) = generator.prepare(&meta, &config).await.unwrap();
+        assert_eq!(
+            tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            ["users"]
+        );
+    }
+
+    #[tokio::test]
+    async fn template_dir_overrides_embedded() {
+        let dir = temp_dir("template_dir");
+        fs::create_dir_all(dir.join("rust")).unwrap();
+        fs::write(dir.join("rust/model.html"), "// custom {{ struct_name }}\n").unwrap();
+        let mut config = config("mysql://localhost/test");
+        config.gen_mod = true;
+        config.template_dir = Some(dir.clone());
+        let files = generate(&config, "CREATE TABLE users (id INT PRIMARY KEY);").await;
+        assert_eq!(files["users.rs"], "// custom Users\n");
+        // 目录中没有的模板使用内置模板
+        assert!(files["mod.rs"].contains("mod users;\npub use users::*;"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_table_name_is_an_error() {
+        let meta =
+            DumpMetadata::new("CREATE TABLE users (id INT PRIMARY KEY);", Driver::Mysql).unwrap();
+        let mut config = config("mysql://localhost/test");
+        config.table_names = vec!["users".to_string(), "userz".to_string()];
+        let err = generator().prepare(&meta, &config).await.unwrap_err();
+        assert_eq!(err.to_string(), "表 userz 不存在");
+
+        // 多个模式时只提示，继续生成存在的表
+        config.schemas = vec!["a".to_string(), "b".to_string()];
+        let (tables, _) = generator().prepare(&meta, &config).await.unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].name, "users");
+    }
+}
@@ -0,0 +1,136 @@
+//! 生成清单
+//!
+//! 记录生成器写入过的文件、内容哈希及其来源表，用于清理已删除表对应的文件，
+//! 避免误删非生成器创建的文件，以及在重新生成时跳过未变化的文件。
+//! 同时保存生成时各表的列结构，用于检测数据库结构漂移
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use database::Column;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 生成清单
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// 生成的文件
+    pub files: Vec<ManifestEntry>,
+    /// 生成时各表的列结构，K：表名
+    #[serde(default)]
+    pub tables: BTreeMap<String, Vec<ColumnSignature>>,
+}
+
+/// 清单中的文件记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// 文件路径
+    pub path: PathBuf,
+    /// 文件内容的 SHA-256 哈希
+    #[serde(default)]
+    pub hash: String,
+    /// 来源表名，mod.rs、error.rs 等公共文件为空
+    pub table: Option<String>,
+}
+
+/// 生成时的列结构
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnSignature {
+    /// 字段名
+    pub name: String,
+    /// 字段类型
+    pub r#type: String,
+    /// 字段长度
+    pub length: Option<i32>,
+    /// 是否为空
+    pub is_null: bool,
+    /// 是否主键
+    pub is_primary_key: bool,
+}
+
+impl From<&Column> for ColumnSignature {
+    fn from(c: &Column) -> Self {
+        Self {
+            name: c.name.clone(),
+            r#type: c.r#type.as_ref().map(|t| t.to_string()).unwrap_or_default(),
+            length: c.length,
+            is_null: c.is_null,
+            is_primary_key: c.is_primary_key,
+        }
+    }
+}
+
+impl Manifest {
+    /// 清单文件名，保存在代码生成的路径下
+    pub const FILE_NAME: &str = ".generated-manifest.json";
+
+    /// 读取生成目录下的清单，清单不存在时返回空清单
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let path = dir.join(Self::FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// 将清单保存到生成目录下
+    pub fn save(&self, dir: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(
+            dir.join(Self::FILE_NAME),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// 记录生成的文件，相同路径的记录会被替换
+    pub fn record(&mut self, path: impl Into<PathBuf>, table: Option<&str>, hash: String) {
+        let path = path.into();
+        self.files.retain(|f| f.path != path);
+        self.files.push(ManifestEntry {
+            path,
+            hash,
+            table: table.map(|t| t.to_string()),
+        });
+    }
+
+    /// 记录表生成时的列结构
+    pub fn record_table<'a>(&mut self, table: &str, columns: impl IntoIterator<Item = &'a Column>) {
+        self.tables.insert(
+            table.to_string(),
+            columns.into_iter().map(ColumnSignature::from).collect(),
+        );
+    }
+
+    /// 文件是否无需重新写入
+    ///
+    /// 上次生成的内容与本次相同，且磁盘上的文件未被修改过
+    pub fn is_unchanged(&self, path: &Path, hash: &str) -> bool {
+        self.files.iter().find(|f| f.path == path).is_some_and(|f| {
+            f.hash == hash && fs::read(path).is_ok_and(|contents| content_hash(&contents) == hash)
+        })
+    }
+
+    /// 来源表已不存在的文件
+    pub fn orphans(&self, tables: &[String]) -> Vec<ManifestEntry> {
+        self.files
+            .iter()
+            .filter(|f| f.table.as_ref().is_some_and(|t| !tables.contains(t)))
+            .cloned()
+            .collect()
+    }
+
+    /// 移除文件记录
+    pub fn remove(&mut self, path: &Path) {
+        self.files.retain(|f| f.path != path);
+    }
+}
+
+/// 计算内容的 SHA-256 哈希
+pub fn content_hash(contents: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
@@ -0,0 +1,77 @@
+//! protobuf 消息生成辅助
+//!
+//! 每个表生成一个 `.proto` 文件，字段类型根据列的 Rust 类型映射到 proto3 的标量类型，
+//! 日期时间、十进制数、UUID、JSON 等没有对应标量的类型使用 string
+
+use database::Column;
+use serde::Serialize;
+
+/// message 的字段
+#[derive(Debug, Serialize)]
+pub struct ProtoField<'a> {
+    /// 字段名，Rust 关键字不需要转义
+    pub name: String,
+    /// proto3 类型，如 `int64`、`string`
+    pub r#type: &'static str,
+    /// 可为空的列标记为 optional
+    pub optional: bool,
+    /// 字段编号，按列的顺序从 1 开始
+    pub number: usize,
+    /// 列的注释
+    pub comment: &'a str,
+}
+
+impl<'a> ProtoField<'a> {
+    pub fn new(column: &'a Column, number: usize) -> Self {
+        let name = database::field_name(&column.name);
+        Self {
+            name: name.trim_start_matches("r#").to_string(),
+            r#type: scalar_type(&column.rust_type),
+            optional: column.is_null,
+            number,
+            comment: &column.comment,
+        }
+    }
+}
+
+/// Rust 类型对应的 proto3 标量类型
+fn scalar_type(rust_type: &str) -> &'static str {
+    match rust_type {
+        "bool" => "bool",
+        "i8" | "i16" | "i32" => "int32",
+        "i64" => "int64",
+        "u8" | "u16" | "u32" => "uint32",
+        "u64" => "uint64",
+        "f32" => "float",
+        "f64" => "double",
+        "Vec<u8>" => "bytes",
+        _ => "string",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar_types() {
+        assert_eq!(scalar_type("i16"), "int32");
+        assert_eq!(scalar_type("u64"), "uint64");
+        assert_eq!(scalar_type("Vec<u8>"), "bytes");
+        assert_eq!(scalar_type("chrono::NaiveDateTime"), "string");
+    }
+
+    #[test]
+    fn keyword_field_name() {
+        let column = Column {
+            name: "type".to_string(),
+            rust_type: "String".to_string(),
+            is_null: true,
+            ..Default::default()
+        };
+        let field = ProtoField::new(&column, 2);
+        assert_eq!(field.name, "type");
+        assert_eq!(field.r#type, "string");
+        assert!(field.optional);
+    }
+}
@@ -0,0 +1,135 @@
+//! sea-orm 实体生成辅助
+
+use database::{Column, ForeignKey};
+use heck::ToUpperCamelCase as _;
+use serde::Serialize;
+
+/// Model 的字段
+#[derive(Debug, Serialize)]
+pub struct SeaOrmField<'a> {
+    /// 字段名
+    pub name: String,
+    /// 字段对应的 Column 枚举值，如 `user_id` -> `UserId`
+    pub variant: String,
+    /// Rust 类型，可为空的列包装为 Option
+    pub r#type: String,
+    /// `#[sea_orm(...)]` 中的属性，如 `primary_key`、`auto_increment = false`
+    pub attrs: Vec<String>,
+    /// 对应的列
+    pub column: &'a Column,
+}
+
+impl<'a> SeaOrmField<'a> {
+    pub fn new(column: &'a Column) -> Self {
+        let name = database::field_name(&column.name);
+        let mut attrs = vec![];
+        if column.is_primary_key {
+            attrs.push("primary_key".to_string());
+            // sea-orm 的主键默认自增
+            attrs.push(format!("auto_increment = {}", column.is_auto_incr));
+        }
+        if column.is_unique && !column.is_primary_key {
+            attrs.push("unique".to_string());
+        }
+        // 字段名与列名不同时显式指定列名
+        if database::needs_rename(&column.name) {
+            attrs.push(format!("column_name = {:?}", column.name));
+        }
+        let r#type = if column.is_null {
+            format!("Option<{}>", column.rust_type)
+        } else {
+            column.rust_type.clone()
+        };
+        Self {
+            variant: variant(&column.name),
+            name,
+            r#type,
+            attrs,
+            column,
+        }
+    }
+}
+
+/// 外键对应的 Relation 枚举值
+#[derive(Debug, Serialize)]
+pub struct Relation {
+    /// 枚举值名称
+    pub name: String,
+    /// 引用表的模块名，引用自身时为 None
+    pub module: Option<String>,
+    /// 外键列，如 `Column::UserId`，组合外键为元组
+    pub from: String,
+    /// 引用的列，如 `super::user::Column::Id`
+    pub to: String,
+    /// 删除动作，如 `Cascade`
+    pub on_delete: Option<String>,
+    /// 更新动作
+    pub on_update: Option<String>,
+    /// 是否实现 Related，同一个表被多个外键引用时只为第一个实现
+    pub related: bool,
+}
+
+/// 根据表的外键生成 Relation，entity_name 为引用表的实体名
+pub fn relations(
+    table_name: &str,
+    foreign_keys: &[ForeignKey],
+    entity_name: impl Fn(&str) -> String,
+) -> Vec<Relation> {
+    let mut relations: Vec<Relation> = vec![];
+    for fk in foreign_keys {
+        let self_ref = fk.referenced_table == table_name;
+        let module = (!self_ref).then(|| fk.referenced_table.clone());
+        let prefix = module
+            .as_ref()
+            .map_or(String::new(), |m| format!("super::{m}::"));
+        // 多个外键引用同一个表时，枚举值名称附加外键列名以区分
+        let duplicated = foreign_keys
+            .iter()
+            .filter(|f| f.referenced_table == fk.referenced_table)
+            .count()
+            > 1;
+        let mut name = if self_ref {
+            "SelfRef".to_string()
+        } else {
+            entity_name(&fk.referenced_table)
+        };
+        if duplicated {
+            name.push_str(&fk.columns.join("_").to_upper_camel_case());
+        }
+        let related = !self_ref && !relations.iter().any(|r| r.module == module);
+        relations.push(Relation {
+            name,
+            from: columns("", &fk.columns),
+            to: columns(&prefix, &fk.referenced_columns),
+            module,
+            on_delete: fk.on_delete.as_deref().map(action),
+            on_update: fk.on_update.as_deref().map(action),
+            related,
+        });
+    }
+    relations
+}
+
+/// 列名对应的 Column 枚举值，与 DeriveEntityModel 的命名一致
+fn variant(column_name: &str) -> String {
+    database::field_name(column_name)
+        .trim_start_matches("r#")
+        .to_upper_camel_case()
+}
+
+/// 关系中的列，如 `Column::UserId`，多列时为 `(Column::A, Column::B)`
+fn columns(prefix: &str, columns: &[String]) -> String {
+    let columns = columns
+        .iter()
+        .map(|c| format!("{prefix}Column::{}", variant(c)))
+        .collect::<Vec<_>>();
+    match columns.as_slice() {
+        [column] => column.clone(),
+        columns => format!("({})", columns.join(", ")),
+    }
+}
+
+/// 外键动作转换为 ForeignKeyAction 的枚举值，如 `SET NULL` -> `SetNull`
+fn action(action: &str) -> String {
+    action.to_lowercase().to_upper_camel_case()
+}
@@ -0,0 +1,92 @@
+//! validator 的范围校验
+//!
+//! 检查约束中的数值范围转换为 `#[validate(range(...))]` 的参数，超出字段类型的取值会被丢弃，
+//! 避免生成无法编译的代码，如 u32 字段的 `min = -1`
+
+use database::NumericRange;
+
+/// 范围校验的参数，如 `min = 0, max = 150`，字段不是数值类型或没有可用的边界时返回 None
+pub fn range_args(range: &NumericRange, rust_type: &str) -> Option<String> {
+    let args = [
+        ("min", &range.min),
+        ("max", &range.max),
+        ("exclusive_min", &range.exclusive_min),
+        ("exclusive_max", &range.exclusive_max),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        let value = literal(value.as_deref()?, rust_type)?;
+        Some(format!("{name} = {value}"))
+    })
+    .collect::<Vec<_>>();
+    (!args.is_empty()).then(|| args.join(", "))
+}
+
+/// 取值转换为字段类型的字面量，浮点字段的整数取值补上 `.0`
+fn literal(value: &str, rust_type: &str) -> Option<String> {
+    let fits = match rust_type {
+        "i8" => value.parse::<i8>().is_ok(),
+        "i16" => value.parse::<i16>().is_ok(),
+        "i32" => value.parse::<i32>().is_ok(),
+        "i64" => value.parse::<i64>().is_ok(),
+        "u8" => value.parse::<u8>().is_ok(),
+        "u16" => value.parse::<u16>().is_ok(),
+        "u32" => value.parse::<u32>().is_ok(),
+        "u64" => value.parse::<u64>().is_ok(),
+        "f32" | "f64" => {
+            value.parse::<f64>().ok()?;
+            return Some(if value.contains(['.', 'e', 'E']) {
+                value.to_string()
+            } else {
+                format!("{value}.0")
+            });
+        }
+        _ => false,
+    };
+    fits.then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(min: Option<&str>, exclusive_max: Option<&str>) -> NumericRange {
+        NumericRange {
+            column: "n".to_string(),
+            min: min.map(String::from),
+            exclusive_max: exclusive_max.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn integer_and_float_literals() {
+        let range = range(Some("0"), Some("100"));
+        assert_eq!(
+            range_args(&range, "i32").as_deref(),
+            Some("min = 0, exclusive_max = 100")
+        );
+        assert_eq!(
+            range_args(&range, "f64").as_deref(),
+            Some("min = 0.0, exclusive_max = 100.0")
+        );
+        assert_eq!(range_args(&range, "String"), None);
+    }
+
+    #[test]
+    fn drops_values_outside_field_type() {
+        assert_eq!(
+            range_args(&range(Some("-1"), Some("10")), "u32").as_deref(),
+            Some("exclusive_max = 10")
+        );
+        assert_eq!(range_args(&range(Some("0.5"), None), "i64"), None);
+        assert_eq!(
+            range_args(&range(Some("0"), Some("1000")), "i8").as_deref(),
+            Some("min = 0")
+        );
+        assert_eq!(
+            range_args(&range(Some("1e3"), None), "f32").as_deref(),
+            Some("min = 1e3")
+        );
+    }
+}